@@ -68,6 +68,9 @@ pub struct Frontend<T> {
 
 	window_audio_settings: WguiWindow,
 	view_audio_settings: Option<views::audio_settings::View>,
+
+	window_keyboard_editor: WguiWindow,
+	view_keyboard_editor: Option<views::keyboard_editor::View>,
 }
 
 pub struct FrontendUpdateParams<'a, T> {
@@ -103,6 +106,9 @@ pub enum FrontendTask {
 	RefreshPopupManager,
 	ShowAudioSettings,
 	UpdateAudioSettingsView,
+	ShowKeyboardEditor,
+	UpdateKeyboardEditorView,
+	RefreshLayoutProfiles,
 	RecenterPlayspace,
 	PushToast(Translation),
 	PlaySound(SoundType),
@@ -178,6 +184,8 @@ impl<T: 'static> Frontend<T> {
 			toast_manager,
 			window_audio_settings: WguiWindow::default(),
 			view_audio_settings: None,
+			window_keyboard_editor: WguiWindow::default(),
+			view_keyboard_editor: None,
 			executor: Rc::new(smol::LocalExecutor::new()),
 			sounds_to_play: Vec::new(),
 		};
@@ -370,6 +378,13 @@ impl<T: 'static> Frontend<T> {
 			FrontendTask::RefreshPopupManager => self.refresh_popup_manager()?,
 			FrontendTask::ShowAudioSettings => self.action_show_audio_settings()?,
 			FrontendTask::UpdateAudioSettingsView => self.action_update_audio_settings()?,
+			FrontendTask::ShowKeyboardEditor => self.action_show_keyboard_editor(params.data)?,
+			FrontendTask::UpdateKeyboardEditorView => self.action_update_keyboard_editor(params.data)?,
+			FrontendTask::RefreshLayoutProfiles => {
+				if let Some(tab) = &mut self.current_tab {
+					tab.layout_profiles_changed();
+				}
+			}
 			FrontendTask::RecenterPlayspace => self.action_recenter_playspace(params.data)?,
 			FrontendTask::PushToast(content) => self.toast_manager.push(content),
 			FrontendTask::PlaySound(sound_type) => self.queue_play_sound(sound_type),
@@ -537,6 +552,47 @@ impl<T: 'static> Frontend<T> {
 		Ok(())
 	}
 
+	fn action_show_keyboard_editor(&mut self, data: &mut T) -> anyhow::Result<()> {
+		self.window_keyboard_editor.open(&mut WguiWindowParams {
+			globals: &self.globals,
+			position: Vec2::new(64.0, 64.0),
+			layout: &mut self.layout,
+			extra: WguiWindowParamsExtra {
+				fixed_width: Some(600.0),
+				title: Some(Translation::from_translation_key("KEYBOARD_EDITOR.TITLE")),
+				..Default::default()
+			},
+		})?;
+
+		let content = self.window_keyboard_editor.get_content();
+
+		self.view_keyboard_editor = Some(views::keyboard_editor::View::new(views::keyboard_editor::Params {
+			globals: self.globals.clone(),
+			frontend_tasks: self.tasks.clone(),
+			layout: &mut self.layout,
+			parent_id: content.id,
+			on_update: {
+				let tasks = self.tasks.clone();
+				Rc::new(move || {
+					tasks.push(FrontendTask::UpdateKeyboardEditorView);
+				})
+			},
+			keyboard: self.interface.keyboard_layout_get(data, false),
+			key_names: self.interface.keyboard_key_names(data),
+		})?);
+		Ok(())
+	}
+
+	fn action_update_keyboard_editor(&mut self, data: &mut T) -> anyhow::Result<()> {
+		let Some(view) = &mut self.view_keyboard_editor else {
+			return Ok(());
+		};
+
+		view.update(&mut self.layout, &mut self.interface, data)?;
+
+		Ok(())
+	}
+
 	fn action_recenter_playspace(&mut self, data: &mut T) -> anyhow::Result<()> {
 		self.interface.recenter_playspace(data, RecenterMode::Recenter)?;
 		Ok(())
@@ -22,4 +22,7 @@ pub trait Tab<T> {
 	fn update(&mut self, _frontend: &mut Frontend<T>, _time_ms: u32, _user_data: &mut T) -> anyhow::Result<()> {
 		Ok(())
 	}
+
+	/// A layout profile was saved after the tab asked for it.
+	fn layout_profiles_changed(&mut self) {}
 }
@@ -20,6 +20,7 @@ enum Task {
 	Refresh,
 	FocusClient(String),
 	SetBrightness(f32),
+	SetDoNotDisturbApp(String, bool),
 }
 
 pub struct TabMonado<T> {
@@ -48,6 +49,7 @@ impl<T> Tab<T> for TabMonado<T> {
 				Task::Refresh => self.refresh(frontend, data)?,
 				Task::FocusClient(name) => self.focus_client(frontend, data, name)?,
 				Task::SetBrightness(brightness) => self.set_brightness(frontend, data, brightness),
+				Task::SetDoNotDisturbApp(name, enabled) => self.set_do_not_disturb_app(frontend, data, name, enabled),
 			}
 		}
 
@@ -99,7 +101,12 @@ impl<T> TabMonado<T> {
 		})
 	}
 
-	fn mount_client(&mut self, frontend: &mut Frontend<T>, client: &dash_interface::MonadoClient) -> anyhow::Result<()> {
+	fn mount_client(
+		&mut self,
+		frontend: &mut Frontend<T>,
+		client: &dash_interface::MonadoClient,
+		do_not_disturb: bool,
+	) -> anyhow::Result<()> {
 		let mut par = HashMap::<Rc<str>, Rc<str>>::new();
 		par.insert(
 			"checked".into(),
@@ -110,6 +117,10 @@ impl<T> TabMonado<T> {
 			},
 		);
 		par.insert("name".into(), client.name.clone().into());
+		par.insert(
+			"do_not_disturb".into(),
+			if do_not_disturb { Rc::from("1") } else { Rc::from("0") },
+		);
 		par.insert("flag_active".into(), yesno(client.is_active).into());
 		par.insert("flag_focused".into(), yesno(client.is_focused).into());
 		par.insert("flag_io_active".into(), yesno(client.is_io_active).into());
@@ -137,6 +148,16 @@ impl<T> TabMonado<T> {
 			})
 		});
 
+		let checkbox = state_cell.fetch_component_as::<ComponentCheckbox>("do_not_disturb")?;
+		checkbox.on_toggle({
+			let tasks = self.tasks.clone();
+			let client_name = client.name.clone();
+			Box::new(move |_common, e| {
+				tasks.push(Task::SetDoNotDisturbApp(client_name.clone(), e.checked));
+				Ok(())
+			})
+		});
+
 		self.cells.push(state_cell);
 
 		Ok(())
@@ -146,12 +167,14 @@ impl<T> TabMonado<T> {
 		log::debug!("refreshing monado client list");
 
 		let clients = frontend.interface.monado_client_list(data)?;
+		let do_not_disturb_apps = frontend.interface.general_config(data).do_not_disturb_apps.clone();
 
 		frontend.layout.remove_children(self.id_list_parent);
 		self.cells.clear();
 
 		for client in clients {
-			self.mount_client(frontend, &client)?;
+			let do_not_disturb = do_not_disturb_apps.contains(&client.name);
+			self.mount_client(frontend, &client, do_not_disturb)?;
 		}
 
 		// get brightness
@@ -182,4 +205,13 @@ impl<T> TabMonado<T> {
 	fn set_brightness(&mut self, frontend: &mut Frontend<T>, data: &mut T, brightness: f32) {
 		frontend.interface.monado_brightness_set(data, brightness);
 	}
+
+	fn set_do_not_disturb_app(&mut self, frontend: &mut Frontend<T>, data: &mut T, name: String, enabled: bool) {
+		let config = frontend.interface.general_config(data);
+		config.do_not_disturb_apps.retain(|n| *n != name);
+		if enabled {
+			config.do_not_disturb_apps.push(name);
+		}
+		frontend.interface.config_changed(data);
+	}
 }
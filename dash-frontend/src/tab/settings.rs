@@ -1,12 +1,19 @@
-use std::{collections::HashMap, marker::PhantomData, rc::Rc, str::FromStr};
+use std::{
+	collections::{HashMap, HashSet},
+	marker::PhantomData,
+	rc::Rc,
+	str::FromStr,
+};
 
 use glam::Vec2;
 use strum::{AsRefStr, EnumProperty, EnumString, VariantArray};
+use wayvr_ipc::packet_client::WlxScreenArrangement;
 use wgui::{
 	assets::AssetPath,
 	components::{
 		button::{ButtonClickEvent, ComponentButton},
 		checkbox::ComponentCheckbox,
+		radio_group::ComponentRadioGroup,
 		slider::ComponentSlider,
 		tabs::ComponentTabs,
 	},
@@ -26,7 +33,12 @@ use wgui::{
 	},
 	windowing::context_menu::{self, Blueprint, ContextMenu, TickResult},
 };
-use wlx_common::{config::GeneralConfig, config_io::ConfigRoot, dash_interface::RecenterMode};
+use wlx_common::{
+	config::{GeneralConfig, NotificationRule},
+	config_io::ConfigRoot,
+	dash_interface::{RecentNotification, RecenterMode},
+	overlays::{ToastDisplayMethod, ToastUrgency},
+};
 
 use crate::{
 	frontend::{Frontend, FrontendTask},
@@ -40,6 +52,8 @@ enum TabNameEnum {
 	Controls,
 	Misc,
 	AutostartApps,
+	LayoutProfiles,
+	NotificationRules,
 	Troubleshooting,
 }
 
@@ -51,6 +65,8 @@ impl TabNameEnum {
 			"controls" => Some(TabNameEnum::Controls),
 			"misc" => Some(TabNameEnum::Misc),
 			"autostart_apps" => Some(TabNameEnum::AutostartApps),
+			"layout_profiles" => Some(TabNameEnum::LayoutProfiles),
+			"notification_rules" => Some(TabNameEnum::NotificationRules),
 			"troubleshooting" => Some(TabNameEnum::Troubleshooting),
 			_ => None,
 		}
@@ -68,15 +84,57 @@ enum Task {
 	DeleteAllConfigs,
 	ResetPlayspace,
 	RestartSoftware,
+	ArrangeScreens(WlxScreenArrangement),
+	EditKeyboardLayout,
 	RemoveAutostartApp(Rc<str>),
+	SaveLayoutProfile(Option<Rc<str>>), // None to create a new one
+	LoadLayoutProfile(Rc<str>),
+	DeleteLayoutProfile(Rc<str>),
+	ExportLayoutProfile(Rc<str>),
+	OpenLayoutProfileImportMenu(Vec2),
+	ImportLayoutProfile(Rc<str>),
+	AddNotificationRule,
+	UpdateNotificationRule(usize, NotificationRuleChange),
+	RemoveNotificationRule(usize),
+	OpenNotificationRuleMenu(Vec2, usize, NotificationRuleField),
 	SetTab(TabNameEnum),
 }
 
+#[derive(Clone)]
+enum NotificationRuleChange {
+	AppName(Option<String>),
+	Summary(Option<String>), // regex
+	Urgency(Option<ToastUrgency>),
+	Display(Option<ToastDisplayMethod>), // None to use the topic's method
+	Timeout(Option<f32>),
+	Silent(bool),
+	Sticky(bool),
+}
+
+/// Fields of a notification rule that are picked from recent notifications
+#[derive(Clone, Copy)]
+enum NotificationRuleField {
+	AppName,
+	Summary,
+}
+
+/// Context menu actions that pick from `TabSettings::layout_profile_imports`
+const LAYOUT_PROFILE_IMPORT_ACTION: &str = "layout_profile_import;";
+/// Context menu actions that pick from `TabSettings::notification_rule_menu`
+const NOTIFICATION_RULE_ACTION: &str = "notification_rule;";
+/// Recent notifications offered when picking an application or summary
+const MAX_NOTIFICATION_RULE_CHOICES: usize = 10;
+
 pub struct TabSettings<T> {
 	pub state: ParserState,
 
 	app_button_ids: Vec<Rc<str>>,
+	shown_tab: TabNameEnum,
 	context_menu: ContextMenu,
+	/// Profile names for each entry of the open import menu
+	layout_profile_imports: Vec<String>,
+	/// Rule index and change for each entry of the open notification rule menu
+	notification_rule_menu: Vec<(usize, NotificationRuleChange)>,
 
 	tasks: Tasks<Task>,
 	marker: PhantomData<T>,
@@ -87,6 +145,12 @@ impl<T> Tab<T> for TabSettings<T> {
 		TabType::Settings
 	}
 
+	fn layout_profiles_changed(&mut self) {
+		if matches!(self.shown_tab, TabNameEnum::LayoutProfiles) {
+			self.tasks.push(Task::SetTab(TabNameEnum::LayoutProfiles));
+		}
+	}
+
 	fn update(&mut self, frontend: &mut Frontend<T>, _time_ms: u32, data: &mut T) -> anyhow::Result<()> {
 		let mut changed = false;
 		for task in self.tasks.drain() {
@@ -142,6 +206,12 @@ impl<T> Tab<T> for TabSettings<T> {
 					frontend.interface.restart(data);
 					return Ok(());
 				}
+				Task::ArrangeScreens(arrangement) => {
+					frontend.interface.screens_arrange(data, arrangement);
+				}
+				Task::EditKeyboardLayout => {
+					frontend.tasks.push(FrontendTask::ShowKeyboardEditor);
+				}
 				Task::OpenContextMenu(position, cells) => {
 					self.context_menu.open(context_menu::OpenParams {
 						on_custom_attribs: None,
@@ -161,6 +231,152 @@ impl<T> Tab<T> for TabSettings<T> {
 						changed = true;
 					}
 				}
+				Task::SaveLayoutProfile(name) => {
+					let name = name.unwrap_or_else(|| {
+						let existing = frontend.interface.layout_profile_list(data);
+						(1..)
+							.map(|i| format!("Layout {i}"))
+							.find(|n| !existing.contains(n))
+							.unwrap() // infinite iterator
+							.into()
+					});
+					// otherwise the list is refreshed by `layout_profiles_changed`
+					if frontend.interface.layout_profile_save(data, &name) {
+						self.set_tab(frontend, data, TabNameEnum::LayoutProfiles)?;
+					}
+				}
+				Task::LoadLayoutProfile(name) => {
+					frontend.interface.layout_profile_load(data, &name);
+				}
+				Task::DeleteLayoutProfile(name) => {
+					let _ = frontend
+						.interface
+						.layout_profile_delete(data, &name)
+						.log_err("Could not delete layout profile");
+					self.set_tab(frontend, data, TabNameEnum::LayoutProfiles)?;
+				}
+				Task::ExportLayoutProfile(name) => {
+					let globals = frontend.layout.state.globals.clone();
+					let toast = match frontend.interface.layout_profile_export(data, &name) {
+						Ok(path) => Translation::from_raw_text_string(format!(
+							"{}: {}",
+							globals.i18n().translate("APP_SETTINGS.LAYOUT_PROFILE_EXPORTED"),
+							path.display()
+						)),
+						Err(e) => Translation::from_raw_text_string(format!(
+							"{}: {e}",
+							globals.i18n().translate("APP_SETTINGS.LAYOUT_PROFILE_EXPORT_FAILED")
+						)),
+					};
+					frontend.tasks.push(FrontendTask::PushToast(toast));
+				}
+				Task::OpenLayoutProfileImportMenu(position) => {
+					self.layout_profile_imports = frontend.interface.layout_profile_import_list(data);
+					if self.layout_profile_imports.is_empty() {
+						frontend
+							.tasks
+							.push(FrontendTask::PushToast(Translation::from_translation_key(
+								"APP_SETTINGS.NO_LAYOUT_PROFILES_TO_IMPORT",
+							)));
+						continue;
+					}
+
+					let cells = self
+						.layout_profile_imports
+						.iter()
+						.enumerate()
+						.map(|(i, name)| context_menu::Cell {
+							action_name: Some(format!("{LAYOUT_PROFILE_IMPORT_ACTION}{i}").into()),
+							title: Translation::from_raw_text(name),
+							tooltip: None,
+							attribs: vec![],
+						})
+						.collect();
+
+					self.context_menu.open(context_menu::OpenParams {
+						on_custom_attribs: None,
+						position,
+						blueprint: Blueprint::Cells(cells),
+					});
+				}
+				Task::ImportLayoutProfile(name) => match frontend.interface.layout_profile_import(data, &name) {
+					Ok(()) => self.set_tab(frontend, data, TabNameEnum::LayoutProfiles)?,
+					Err(e) => {
+						let globals = frontend.layout.state.globals.clone();
+						frontend
+							.tasks
+							.push(FrontendTask::PushToast(Translation::from_raw_text_string(format!(
+								"{}: {e}",
+								globals.i18n().translate("APP_SETTINGS.LAYOUT_PROFILE_IMPORT_FAILED")
+							))));
+					}
+				},
+				Task::AddNotificationRule => {
+					let config = frontend.interface.general_config(data);
+					config.notification_rules.push(NotificationRule::default());
+					changed = true;
+					self.set_tab(frontend, data, TabNameEnum::NotificationRules)?;
+				}
+				Task::UpdateNotificationRule(idx, change) => {
+					// the title of the rule shows what it matches
+					let refresh = matches!(
+						change,
+						NotificationRuleChange::AppName(_)
+							| NotificationRuleChange::Summary(_)
+							| NotificationRuleChange::Urgency(_)
+					);
+					let config = frontend.interface.general_config(data);
+					if let Some(rule) = config.notification_rules.get_mut(idx) {
+						match change {
+							NotificationRuleChange::AppName(app_name) => rule.app_name = app_name,
+							NotificationRuleChange::Summary(summary) => rule.summary = summary,
+							NotificationRuleChange::Urgency(urgency) => rule.urgency = urgency,
+							NotificationRuleChange::Display(display) => rule.display = display,
+							NotificationRuleChange::Timeout(timeout) => rule.timeout = timeout,
+							NotificationRuleChange::Silent(silent) => rule.silent = silent,
+							NotificationRuleChange::Sticky(sticky) => rule.sticky = sticky,
+						}
+						changed = true;
+					}
+					if refresh {
+						self.set_tab(frontend, data, TabNameEnum::NotificationRules)?;
+					}
+				}
+				Task::OpenNotificationRuleMenu(position, idx, field) => {
+					let recent = frontend.interface.notification_recent_list(data);
+					let config = frontend.interface.general_config(data);
+					let Some(rule) = config.notification_rules.get(idx) else {
+						continue;
+					};
+
+					let choices = notification_rule_choices(rule, field, &recent);
+					let cells = choices
+						.iter()
+						.enumerate()
+						.map(|(i, (title, _))| context_menu::Cell {
+							action_name: Some(format!("{NOTIFICATION_RULE_ACTION}{i}").into()),
+							title: title.clone(),
+							tooltip: None,
+							attribs: vec![],
+						})
+						.collect();
+
+					self.notification_rule_menu = choices.into_iter().map(|(_, change)| (idx, change)).collect();
+					self.context_menu.open(context_menu::OpenParams {
+						on_custom_attribs: None,
+						position,
+						blueprint: Blueprint::Cells(cells),
+					});
+				}
+				Task::RemoveNotificationRule(idx) => {
+					let config = frontend.interface.general_config(data);
+					if idx < config.notification_rules.len() {
+						config.notification_rules.remove(idx);
+						changed = true;
+					}
+					// indices of the following rules have shifted
+					self.set_tab(frontend, data, TabNameEnum::NotificationRules)?;
+				}
 				Task::SettingUpdated(setting) => match setting {
 					SettingType::UiAnimationSpeed | SettingType::UiGradientIntensity | SettingType::UiRoundMultiplier => {
 						frontend.tasks.push(FrontendTask::UpdateWguiDefaultsFromConfig);
@@ -170,8 +386,32 @@ impl<T> Tab<T> for TabSettings<T> {
 			}
 		}
 
+		let tick = self.context_menu.tick(&mut frontend.layout, &mut self.state)?;
+
+		// Layout profile import menu
+		if let TickResult::Action(name) = &tick
+			&& let Some(choice) = name.strip_prefix(LAYOUT_PROFILE_IMPORT_ACTION)
+			&& let Some(profile) = choice
+				.parse::<usize>()
+				.ok()
+				.and_then(|choice| self.layout_profile_imports.get(choice))
+		{
+			self.tasks.push(Task::ImportLayoutProfile(profile.as_str().into()));
+		}
+
+		// Notification rule menu
+		if let TickResult::Action(name) = &tick
+			&& let Some(choice) = name.strip_prefix(NOTIFICATION_RULE_ACTION)
+			&& let Some((idx, change)) = choice
+				.parse::<usize>()
+				.ok()
+				.and_then(|choice| self.notification_rule_menu.get(choice))
+		{
+			self.tasks.push(Task::UpdateNotificationRule(*idx, change.clone()));
+		}
+
 		// Dropdown handling
-		if let TickResult::Action(name) = self.context_menu.tick(&mut frontend.layout, &mut self.state)?
+		if let TickResult::Action(name) = tick
 			&& let (Some(setting), Some(id), Some(value), Some(text), Some(translated)) = {
 				let mut s = name.splitn(5, ';');
 				(s.next(), s.next(), s.next(), s.next(), s.next())
@@ -218,6 +458,7 @@ enum SettingType {
 	BlockPosesOnKbdInteraction,
 	CaptureMethod,
 	ClickFreezeTimeMs,
+	ClipboardSync,
 	Clock12h,
 	DoubleCursorFix,
 	FocusFollowsMouseMode,
@@ -226,6 +467,7 @@ enum SettingType {
 	HideUsername,
 	InvertScrollDirectionX,
 	InvertScrollDirectionY,
+	KeyboardIme,
 	KeyboardMiddleClick,
 	KeyboardSoundEnabled,
 	Language,
@@ -235,9 +477,12 @@ enum SettingType {
 	NotificationsSoundEnabled,
 	OpaqueBackground,
 	PointerLerpFactor,
+	ScreenArcRadius,
 	ScreenRenderDown,
 	ScrollSpeed,
 	SetsOnWatch,
+	SnapAngle,
+	SnapOverlays,
 	SpaceDragMultiplier,
 	SpaceDragUnlocked,
 	SpaceRotateUnlocked,
@@ -265,6 +510,7 @@ impl SettingType {
 			Self::SetsOnWatch => &mut config.sets_on_watch,
 			Self::HideGrabHelp => &mut config.hide_grab_help,
 			Self::AllowSliding => &mut config.allow_sliding,
+			Self::SnapOverlays => &mut config.snap_overlays,
 			Self::FocusFollowsMouseMode => &mut config.focus_follows_mouse_mode,
 			Self::LeftHandedMouse => &mut config.left_handed_mouse,
 			Self::BlockGameInput => &mut config.block_game_input,
@@ -279,6 +525,8 @@ impl SettingType {
 			Self::HideUsername => &mut config.hide_username,
 			Self::OpaqueBackground => &mut config.opaque_background,
 			Self::XwaylandByDefault => &mut config.xwayland_by_default,
+			Self::ClipboardSync => &mut config.clipboard_sync,
+			Self::KeyboardIme => &mut config.keyboard_ime,
 			_ => panic!("Requested bool for non-bool SettingType"),
 		}
 	}
@@ -294,6 +542,8 @@ impl SettingType {
 			Self::XrClickSensitivityRelease => &mut config.xr_click_sensitivity_release,
 			Self::SpaceDragMultiplier => &mut config.space_drag_multiplier,
 			Self::PointerLerpFactor => &mut config.pointer_lerp_factor,
+			Self::SnapAngle => &mut config.snap_angle,
+			Self::ScreenArcRadius => &mut config.screen_arc_radius,
 			_ => panic!("Requested f32 for non-f32 SettingType"),
 		}
 	}
@@ -365,6 +615,7 @@ impl SettingType {
 			Self::BlockPosesOnKbdInteraction => Ok("APP_SETTINGS.BLOCK_POSES_ON_KBD_INTERACTION"),
 			Self::CaptureMethod => Ok("APP_SETTINGS.CAPTURE_METHOD"),
 			Self::ClickFreezeTimeMs => Ok("APP_SETTINGS.CLICK_FREEZE_TIME_MS"),
+			Self::ClipboardSync => Ok("APP_SETTINGS.CLIPBOARD_SYNC"),
 			Self::Clock12h => Ok("APP_SETTINGS.CLOCK_12H"),
 			Self::DoubleCursorFix => Ok("APP_SETTINGS.DOUBLE_CURSOR_FIX"),
 			Self::FocusFollowsMouseMode => Ok("APP_SETTINGS.FOCUS_FOLLOWS_MOUSE_MODE"),
@@ -373,6 +624,7 @@ impl SettingType {
 			Self::HideUsername => Ok("APP_SETTINGS.HIDE_USERNAME"),
 			Self::InvertScrollDirectionX => Ok("APP_SETTINGS.INVERT_SCROLL_DIRECTION_X"),
 			Self::InvertScrollDirectionY => Ok("APP_SETTINGS.INVERT_SCROLL_DIRECTION_Y"),
+			Self::KeyboardIme => Ok("APP_SETTINGS.KEYBOARD_IME"),
 			Self::KeyboardMiddleClick => Ok("APP_SETTINGS.KEYBOARD_MIDDLE_CLICK"),
 			Self::KeyboardSoundEnabled => Ok("APP_SETTINGS.KEYBOARD_SOUND_ENABLED"),
 			Self::Language => Ok("APP_SETTINGS.LANGUAGE"),
@@ -382,9 +634,12 @@ impl SettingType {
 			Self::NotificationsSoundEnabled => Ok("APP_SETTINGS.NOTIFICATIONS_SOUND_ENABLED"),
 			Self::OpaqueBackground => Ok("APP_SETTINGS.OPAQUE_BACKGROUND"),
 			Self::PointerLerpFactor => Ok("APP_SETTINGS.POINTER_LERP_FACTOR"),
+			Self::ScreenArcRadius => Ok("APP_SETTINGS.SCREEN_ARC_RADIUS"),
 			Self::ScreenRenderDown => Ok("APP_SETTINGS.SCREEN_RENDER_DOWN"),
 			Self::ScrollSpeed => Ok("APP_SETTINGS.SCROLL_SPEED"),
 			Self::SetsOnWatch => Ok("APP_SETTINGS.SETS_ON_WATCH"),
+			Self::SnapAngle => Ok("APP_SETTINGS.SNAP_ANGLE"),
+			Self::SnapOverlays => Ok("APP_SETTINGS.SNAP_OVERLAYS"),
 			Self::SpaceDragMultiplier => Ok("APP_SETTINGS.SPACE_DRAG_MULTIPLIER"),
 			Self::SpaceDragUnlocked => Ok("APP_SETTINGS.SPACE_DRAG_UNLOCKED"),
 			Self::SpaceRotateUnlocked => Ok("APP_SETTINGS.SPACE_ROTATE_UNLOCKED"),
@@ -407,11 +662,15 @@ impl SettingType {
 			Self::BlockGameInputIgnoreWatch => Some("APP_SETTINGS.BLOCK_GAME_INPUT_IGNORE_WATCH_HELP"),
 			Self::BlockPosesOnKbdInteraction => Some("APP_SETTINGS.BLOCK_POSES_ON_KBD_INTERACTION_HELP"),
 			Self::CaptureMethod => Some("APP_SETTINGS.CAPTURE_METHOD_HELP"),
+			Self::ClipboardSync => Some("APP_SETTINGS.CLIPBOARD_SYNC_HELP"),
 			Self::DoubleCursorFix => Some("APP_SETTINGS.DOUBLE_CURSOR_FIX_HELP"),
 			Self::HandsfreePointer => Some("APP_SETTINGS.HANDSFREE_POINTER_HELP"),
+			Self::KeyboardIme => Some("APP_SETTINGS.KEYBOARD_IME_HELP"),
 			Self::KeyboardMiddleClick => Some("APP_SETTINGS.KEYBOARD_MIDDLE_CLICK_HELP"),
 			Self::LeftHandedMouse => Some("APP_SETTINGS.LEFT_HANDED_MOUSE_HELP"),
 			Self::ScreenRenderDown => Some("APP_SETTINGS.SCREEN_RENDER_DOWN_HELP"),
+			Self::SnapAngle => Some("APP_SETTINGS.SNAP_ANGLE_HELP"),
+			Self::SnapOverlays => Some("APP_SETTINGS.SNAP_OVERLAYS_HELP"),
 			Self::UprightScreenFix => Some("APP_SETTINGS.UPRIGHT_SCREEN_FIX_HELP"),
 			Self::UsePassthrough => Some("APP_SETTINGS.USE_PASSTHROUGH_HELP"),
 			Self::UseSkybox => Some("APP_SETTINGS.USE_SKYBOX_HELP"),
@@ -698,8 +957,8 @@ macro_rules! dropdown {
 	};
 }
 
-macro_rules! danger_button {
-	($mp:expr, $root:expr, $translation:expr, $icon:expr, $task:expr) => {
+macro_rules! template_button {
+	($mp:expr, $root:expr, $template:expr, $translation:expr, $icon:expr, $task:expr) => {
 		let id = $mp.idx.to_string();
 		$mp.idx += 1;
 
@@ -710,7 +969,7 @@ macro_rules! danger_button {
 
 		$mp
 			.parser_state
-			.instantiate_template($mp.doc_params, "DangerButton", $mp.layout, $root, params)?;
+			.instantiate_template($mp.doc_params, $template, $mp.layout, $root, params)?;
 
 		let btn = $mp.parser_state.fetch_component_as::<ComponentButton>(&id)?;
 		btn.on_click(Rc::new({
@@ -723,6 +982,18 @@ macro_rules! danger_button {
 	};
 }
 
+macro_rules! danger_button {
+	($mp:expr, $root:expr, $translation:expr, $icon:expr, $task:expr) => {
+		template_button!($mp, $root, "DangerButton", $translation, $icon, $task);
+	};
+}
+
+macro_rules! action_button {
+	($mp:expr, $root:expr, $translation:expr, $icon:expr, $task:expr) => {
+		template_button!($mp, $root, "ActionButton", $translation, $icon, $task);
+	};
+}
+
 macro_rules! autostart_app {
 	($mp:expr, $root:expr, $text:expr, $ids:expr) => {
 		let id = $mp.idx.to_string();
@@ -751,6 +1022,287 @@ macro_rules! autostart_app {
 	};
 }
 
+macro_rules! layout_profile {
+	($mp:expr, $root:expr, $name:expr) => {
+		let id = $mp.idx.to_string();
+		$mp.idx += 1;
+
+		let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+		params.insert(Rc::from("id"), Rc::from(id.as_ref()));
+		params.insert(Rc::from("text"), Rc::from($name.as_str()));
+
+		$mp
+			.parser_state
+			.instantiate_template($mp.doc_params, "LayoutProfile", $mp.layout, $root, params)?;
+
+		let name: Rc<str> = Rc::from($name.as_str());
+
+		let btn = $mp
+			.parser_state
+			.fetch_component_as::<ComponentButton>(&format!("{id}_load"))?;
+		btn.on_click(Rc::new({
+			let tasks = $mp.tasks.clone();
+			let name = name.clone();
+			move |_common, _e| {
+				tasks.push(Task::LoadLayoutProfile(name.clone()));
+				Ok(())
+			}
+		}));
+
+		let btn = $mp
+			.parser_state
+			.fetch_component_as::<ComponentButton>(&format!("{id}_save"))?;
+		btn.on_click(Rc::new({
+			let tasks = $mp.tasks.clone();
+			let name = name.clone();
+			move |_common, _e| {
+				tasks.push(Task::SaveLayoutProfile(Some(name.clone())));
+				Ok(())
+			}
+		}));
+
+		let btn = $mp
+			.parser_state
+			.fetch_component_as::<ComponentButton>(&format!("{id}_export"))?;
+		btn.on_click(Rc::new({
+			let tasks = $mp.tasks.clone();
+			let name = name.clone();
+			move |_common, _e| {
+				tasks.push(Task::ExportLayoutProfile(name.clone()));
+				Ok(())
+			}
+		}));
+
+		let btn = $mp
+			.parser_state
+			.fetch_component_as::<ComponentButton>(&format!("{id}_delete"))?;
+		btn.on_click(Rc::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, _e| {
+				tasks.push(Task::DeleteLayoutProfile(name.clone()));
+				Ok(())
+			}
+		}));
+	};
+}
+
+fn notification_rule_text(rule: &NotificationRule) -> String {
+	let mut parts = vec![];
+	if let Some(app_name) = &rule.app_name {
+		parts.push(app_name.clone());
+	}
+	if let Some(summary) = &rule.summary {
+		parts.push(format!("/{summary}/"));
+	}
+	if let Some(urgency) = rule.urgency {
+		parts.push(urgency.as_ref().to_string());
+	}
+	if parts.is_empty() {
+		parts.push("*".into());
+	}
+	parts.join(" · ")
+}
+
+/// "Any", followed by the applications or summaries of recent notifications.
+/// Summaries are only offered for the rule's application and are matched as a whole.
+fn notification_rule_choices(
+	rule: &NotificationRule,
+	field: NotificationRuleField,
+	recent: &[RecentNotification],
+) -> Vec<(Translation, NotificationRuleChange)> {
+	let any = Translation::from_translation_key("APP_SETTINGS.NOTIFICATION_RULE.ANY");
+	let mut choices = vec![match field {
+		NotificationRuleField::AppName => (any, NotificationRuleChange::AppName(None)),
+		NotificationRuleField::Summary => (any, NotificationRuleChange::Summary(None)),
+	}];
+
+	let mut seen = HashSet::new();
+	for notification in recent {
+		if choices.len() > MAX_NOTIFICATION_RULE_CHOICES {
+			break;
+		}
+
+		let value = match field {
+			NotificationRuleField::AppName => notification.app_name.clone(),
+			NotificationRuleField::Summary => {
+				let same_app = rule.app_name.as_deref().is_none_or(|app_name| {
+					notification
+						.app_name
+						.as_deref()
+						.is_some_and(|name| name.eq_ignore_ascii_case(app_name))
+				});
+				same_app.then(|| notification.summary.clone())
+			}
+		};
+		let Some(value) = value.filter(|v| !v.is_empty() && seen.insert(v.clone())) else {
+			continue;
+		};
+
+		let change = match field {
+			NotificationRuleField::AppName => NotificationRuleChange::AppName(Some(value.clone())),
+			NotificationRuleField::Summary => NotificationRuleChange::Summary(Some(format!("^{}$", regex::escape(&value)))),
+		};
+		choices.push((Translation::from_raw_text_string(value), change));
+	}
+
+	choices
+}
+
+macro_rules! notification_rule {
+	($mp:expr, $root:expr, $idx:expr, $rule:expr) => {
+		let id = $mp.idx.to_string();
+		$mp.idx += 1;
+
+		let checked = |b: bool| Rc::from(if b { "1" } else { "0" });
+		let display = $rule.display;
+
+		let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+		params.insert(Rc::from("id"), Rc::from(id.as_ref()));
+		params.insert(Rc::from("text"), Rc::from(notification_rule_text($rule)));
+		params.insert(Rc::from("default_checked"), checked(display.is_none()));
+		params.insert(Rc::from("center_checked"), checked(display == Some(ToastDisplayMethod::Center)));
+		params.insert(Rc::from("watch_checked"), checked(display == Some(ToastDisplayMethod::Watch)));
+		params.insert(Rc::from("hide_checked"), checked(display == Some(ToastDisplayMethod::Hide)));
+		params.insert(Rc::from("silent"), checked($rule.silent));
+		params.insert(Rc::from("sticky"), checked($rule.sticky));
+
+		let urgency = $rule.urgency;
+		params.insert(Rc::from("any_urgency_checked"), checked(urgency.is_none()));
+		params.insert(Rc::from("low_checked"), checked(urgency == Some(ToastUrgency::Low)));
+		params.insert(Rc::from("normal_checked"), checked(urgency == Some(ToastUrgency::Normal)));
+		params.insert(Rc::from("critical_checked"), checked(urgency == Some(ToastUrgency::Critical)));
+		params.insert(Rc::from("timeout"), Rc::from($rule.timeout.unwrap_or(0.0).to_string()));
+
+		$mp
+			.parser_state
+			.instantiate_template($mp.doc_params, "NotificationRule", $mp.layout, $root, params)?;
+
+		let idx: usize = $idx;
+
+		let id_match = $mp.parser_state.get_widget_id(&format!("{id}_match"))?;
+		for (i, (field, value, translation)) in [
+			(
+				NotificationRuleField::AppName,
+				&$rule.app_name,
+				"APP_SETTINGS.NOTIFICATION_RULE.APP_NAME",
+			),
+			(
+				NotificationRuleField::Summary,
+				&$rule.summary,
+				"APP_SETTINGS.NOTIFICATION_RULE.SUMMARY",
+			),
+		]
+		.into_iter()
+		.enumerate()
+		{
+			let id_field = format!("{id}_match_{i}");
+
+			let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+			params.insert(Rc::from("id"), Rc::from(id_field.as_str()));
+			params.insert(Rc::from("translation"), Rc::from(translation));
+			params.insert(Rc::from("tooltip"), Rc::from(format!("{translation}_HELP")));
+
+			let id_cell = horiz_cell($mp.layout, id_match)?;
+			$mp
+				.parser_state
+				.instantiate_template($mp.doc_params, "DropdownButton", $mp.layout, id_cell, params)?;
+
+			{
+				let mut label = $mp
+					.parser_state
+					.fetch_widget_as::<WidgetLabel>(&$mp.layout.state, &format!("{id_field}_value"))?;
+				let title = value.as_deref().map_or_else(
+					|| Translation::from_translation_key("APP_SETTINGS.NOTIFICATION_RULE.ANY"),
+					Translation::from_raw_text,
+				);
+				label.set_text_simple(&mut $mp.layout.state.globals.get(), title);
+			}
+
+			let btn = $mp.parser_state.fetch_component_as::<ComponentButton>(&id_field)?;
+			btn.on_click(Rc::new({
+				let tasks = $mp.tasks.clone();
+				move |_common, e: ButtonClickEvent| {
+					tasks.push(Task::OpenNotificationRuleMenu(
+						e.mouse_pos_absolute.unwrap_or_default(),
+						idx,
+						field,
+					));
+					Ok(())
+				}
+			}));
+		}
+
+		let btn = $mp.parser_state.fetch_component_as::<ComponentButton>(&format!("{id}_delete"))?;
+		btn.on_click(Rc::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, _e| {
+				tasks.push(Task::RemoveNotificationRule(idx));
+				Ok(())
+			}
+		}));
+
+		let radio = $mp
+			.parser_state
+			.fetch_component_as::<ComponentRadioGroup>(&format!("{id}_display"))?;
+		radio.on_value_changed(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				// "Default" is not a ToastDisplayMethod
+				let display = e.value.and_then(|v| ToastDisplayMethod::from_str(&v).ok());
+				tasks.push(Task::UpdateNotificationRule(idx, NotificationRuleChange::Display(display)));
+				Ok(())
+			}
+		}));
+
+		let radio = $mp
+			.parser_state
+			.fetch_component_as::<ComponentRadioGroup>(&format!("{id}_urgency"))?;
+		radio.on_value_changed(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				// "Any" is not a ToastUrgency
+				let urgency = e.value.and_then(|v| ToastUrgency::from_str(&v).ok());
+				tasks.push(Task::UpdateNotificationRule(idx, NotificationRuleChange::Urgency(urgency)));
+				Ok(())
+			}
+		}));
+
+		let slider = $mp
+			.parser_state
+			.fetch_component_as::<ComponentSlider>(&format!("{id}_timeout"))?;
+		slider.on_value_changed(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				let timeout = Some(e.value).filter(|t| *t > 0.0);
+				tasks.push(Task::UpdateNotificationRule(idx, NotificationRuleChange::Timeout(timeout)));
+				Ok(())
+			}
+		}));
+
+		let checkbox = $mp
+			.parser_state
+			.fetch_component_as::<ComponentCheckbox>(&format!("{id}_silent"))?;
+		checkbox.on_toggle(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				tasks.push(Task::UpdateNotificationRule(idx, NotificationRuleChange::Silent(e.checked)));
+				Ok(())
+			}
+		}));
+
+		let checkbox = $mp
+			.parser_state
+			.fetch_component_as::<ComponentCheckbox>(&format!("{id}_sticky"))?;
+		checkbox.on_toggle(Box::new({
+			let tasks = $mp.tasks.clone();
+			move |_common, e| {
+				tasks.push(Task::UpdateNotificationRule(idx, NotificationRuleChange::Sticky(e.checked)));
+				Ok(())
+			}
+		}));
+	};
+}
+
 struct MacroParams<'a> {
 	layout: &'a mut Layout,
 	parser_state: &'a mut ParserState,
@@ -772,8 +1324,15 @@ impl<T> TabSettings<T> {
 	fn set_tab(&mut self, frontend: &mut Frontend<T>, data: &mut T, name: TabNameEnum) -> anyhow::Result<()> {
 		let root = self.state.get_widget_id("settings_root")?;
 		frontend.layout.remove_children(root);
+		self.shown_tab = name.clone();
 		let globals = frontend.layout.state.globals.clone();
 
+		// fetched before MacroParams borrows the interface
+		let layout_profiles = match name {
+			TabNameEnum::LayoutProfiles => frontend.interface.layout_profile_list(data),
+			_ => Vec::new(),
+		};
+
 		let mut mp = MacroParams {
 			layout: &mut frontend.layout,
 			parser_state: &mut self.state,
@@ -827,6 +1386,8 @@ impl<T> TabSettings<T> {
 				checkbox!(mp, c, SettingType::FocusFollowsMouseMode);
 				checkbox!(mp, c, SettingType::LeftHandedMouse);
 				checkbox!(mp, c, SettingType::AllowSliding);
+				checkbox!(mp, c, SettingType::SnapOverlays);
+				slider_f32!(mp, c, SettingType::SnapAngle, 0.0, 45.0, 5.0);
 				checkbox!(mp, c, SettingType::InvertScrollDirectionX);
 				checkbox!(mp, c, SettingType::InvertScrollDirectionY);
 				slider_f32!(mp, c, SettingType::ScrollSpeed, 0.1, 5.0, 0.1);
@@ -835,6 +1396,13 @@ impl<T> TabSettings<T> {
 				slider_f32!(mp, c, SettingType::XrClickSensitivity, 0.1, 1.0, 0.1);
 				slider_f32!(mp, c, SettingType::XrClickSensitivityRelease, 0.1, 1.0, 0.1);
 				slider_i32!(mp, c, SettingType::ClickFreezeTimeMs, 0, 500, 50);
+				action_button!(
+					mp,
+					c,
+					"APP_SETTINGS.EDIT_KEYBOARD_LAYOUT",
+					"dashboard/keyboard.svg",
+					Task::EditKeyboardLayout
+				);
 			}
 			TabNameEnum::Misc => {
 				let c = category!(mp, root, "APP_SETTINGS.MISC", "dashboard/blocks.svg")?;
@@ -845,6 +1413,8 @@ impl<T> TabSettings<T> {
 					wlx_common::config::CaptureMethod::VARIANTS
 				);
 				checkbox!(mp, c, SettingType::XwaylandByDefault);
+				checkbox!(mp, c, SettingType::ClipboardSync);
+				checkbox!(mp, c, SettingType::KeyboardIme);
 				checkbox!(mp, c, SettingType::UprightScreenFix);
 				checkbox!(mp, c, SettingType::DoubleCursorFix);
 				checkbox!(mp, c, SettingType::ScreenRenderDown);
@@ -860,6 +1430,110 @@ impl<T> TabSettings<T> {
 					}
 				}
 			}
+			TabNameEnum::LayoutProfiles => {
+				let c = category!(mp, root, "APP_SETTINGS.LAYOUT_PROFILES", "dashboard/window.svg")?;
+
+				let id = mp.idx.to_string();
+				mp.idx += 1;
+
+				let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+				params.insert(Rc::from("id"), Rc::from(id.as_ref()));
+				mp.parser_state
+					.instantiate_template(mp.doc_params, "NewLayoutProfile", mp.layout, c, params)?;
+
+				let btn = mp.parser_state.fetch_component_as::<ComponentButton>(&id)?;
+				btn.on_click(Rc::new({
+					let tasks = mp.tasks.clone();
+					move |_common, _e| {
+						tasks.push(Task::SaveLayoutProfile(None));
+						Ok(())
+					}
+				}));
+
+				let btn = mp
+					.parser_state
+					.fetch_component_as::<ComponentButton>(&format!("{id}_import"))?;
+				btn.on_click(Rc::new({
+					let tasks = mp.tasks.clone();
+					move |_common, e: ButtonClickEvent| {
+						tasks.push(Task::OpenLayoutProfileImportMenu(
+							e.mouse_pos_absolute.unwrap_or_default(),
+						));
+						Ok(())
+					}
+				}));
+
+				for name in layout_profiles {
+					layout_profile!(mp, c, name);
+				}
+
+				let c = category!(mp, root, "APP_SETTINGS.ARRANGE_SCREENS", "dashboard/display.svg")?;
+				action_button!(
+					mp,
+					c,
+					"APP_SETTINGS.ARRANGE_SCREENS_ROW",
+					"dashboard/display.svg",
+					Task::ArrangeScreens(WlxScreenArrangement::Row)
+				);
+				action_button!(
+					mp,
+					c,
+					"APP_SETTINGS.ARRANGE_SCREENS_ARC",
+					"dashboard/panorama.svg",
+					Task::ArrangeScreens(WlxScreenArrangement::Arc { radius: None })
+				);
+				action_button!(
+					mp,
+					c,
+					"APP_SETTINGS.ARRANGE_SCREENS_STACK",
+					"dashboard/blocks.svg",
+					Task::ArrangeScreens(WlxScreenArrangement::Stack)
+				);
+				action_button!(
+					mp,
+					c,
+					"APP_SETTINGS.ARRANGE_SCREENS_DESKTOP",
+					"dashboard/window.svg",
+					Task::ArrangeScreens(WlxScreenArrangement::Desktop)
+				);
+				slider_f32!(mp, c, SettingType::ScreenArcRadius, 0.5, 5.0, 0.1);
+			}
+			TabNameEnum::NotificationRules => {
+				let c = category!(mp, root, "APP_SETTINGS.NOTIFICATION_RULES", "dashboard/bell.svg")?;
+
+				let id = mp.idx.to_string();
+				mp.idx += 1;
+
+				let mut params: HashMap<Rc<str>, Rc<str>> = HashMap::new();
+				params.insert(Rc::from("id"), Rc::from(id.as_ref()));
+				mp.parser_state
+					.instantiate_template(mp.doc_params, "NewNotificationRule", mp.layout, c, params)?;
+
+				let btn = mp.parser_state.fetch_component_as::<ComponentButton>(&id)?;
+				btn.on_click(Rc::new({
+					let tasks = mp.tasks.clone();
+					move |_common, _e| {
+						tasks.push(Task::AddNotificationRule);
+						Ok(())
+					}
+				}));
+
+				if mp.config.notification_rules.is_empty() {
+					mp.parser_state.instantiate_template(
+						mp.doc_params,
+						"NotificationRulesEmpty",
+						mp.layout,
+						c,
+						HashMap::new(),
+					)?;
+				}
+
+				// cloned, as the macro needs `mp` mutably
+				let rules = mp.config.notification_rules.clone();
+				for (idx, rule) in rules.iter().enumerate() {
+					notification_rule!(mp, c, idx, rule);
+				}
+			}
 			TabNameEnum::Troubleshooting => {
 				let c = category!(mp, root, "APP_SETTINGS.TROUBLESHOOTING", "dashboard/cpu.svg")?;
 				danger_button!(
@@ -927,10 +1601,13 @@ impl<T> TabSettings<T> {
 
 		Ok(Self {
 			app_button_ids: Vec::new(),
+			shown_tab: TabNameEnum::LookAndFeel,
 			tasks,
 			state: parser_state,
 			marker: PhantomData,
 			context_menu: ContextMenu::default(),
+			layout_profile_imports: Vec::new(),
+			notification_rule_menu: Vec::new(),
 		})
 	}
 }
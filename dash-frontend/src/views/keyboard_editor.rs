@@ -0,0 +1,483 @@
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use wgui::{
+	assets::AssetPath,
+	components::{
+		button::{ButtonClickCallback, ComponentButton},
+		slider::ComponentSlider,
+	},
+	globals::WguiGlobals,
+	i18n::Translation,
+	layout::{Layout, WidgetID},
+	parser::{Fetchable, ParseDocumentParams, ParserState},
+	task::Tasks,
+	widget::label::WidgetLabel,
+};
+use wlx_common::{dash_interface::BoxDashInterface, keyboard_layout::KeyboardLayout};
+
+use crate::frontend::{FrontendTask, FrontendTasks};
+
+/// Width of the whole keyboard preview, in pixels
+const PREVIEW_WIDTH: f32 = 560.0;
+/// Upper limit for the width of a 1-unit key, in pixels
+const MAX_KEY_UNIT: f32 = 32.0;
+
+/// Held down for the rest of the macro when picked while recording
+const MACRO_MODIFIERS: [&str; 8] = ["LShift", "RShift", "LCtrl", "RCtrl", "LAlt", "LSuper", "RSuper", "Meta"];
+
+#[derive(Clone)]
+enum ViewTask {
+	Remount,
+	RemountPicker,
+	Select(usize, usize),
+	SetSize(f32),
+	InsertKey,
+	RemoveKey,
+	ClearKey,
+	ResetLabel,
+	InsertRow,
+	RemoveRow,
+	Pick(Rc<str>),
+	ToggleRecording,
+	Revert,
+	LoadDefault,
+	Save,
+}
+
+type ViewTasks = Tasks<ViewTask>;
+
+/// Macro that picked keys are appended to
+struct MacroRecording {
+	name: String,
+	held: Vec<String>,
+}
+
+pub struct View {
+	tasks: ViewTasks,
+	frontend_tasks: FrontendTasks,
+	on_update: Rc<dyn Fn()>,
+
+	globals: WguiGlobals,
+	state: ParserState,
+
+	keyboard: KeyboardLayout,
+	key_names: Vec<String>,
+	selected: Option<(usize, usize)>,
+	recording: Option<MacroRecording>,
+
+	id_rows: WidgetID,
+	id_picker: WidgetID,
+	id_errors: WidgetID,
+	id_label_selected: WidgetID,
+	id_label_picker: WidgetID,
+
+	slider_size: Rc<ComponentSlider>,
+	/// Set while the slider is moved to match the selection, so that it doesn't resize anything
+	slider_syncing: Rc<Cell<bool>>,
+}
+
+pub struct Params<'a> {
+	pub globals: WguiGlobals,
+	pub frontend_tasks: FrontendTasks,
+	pub layout: &'a mut Layout,
+	pub parent_id: WidgetID,
+	pub on_update: Rc<dyn Fn()>,
+	pub keyboard: KeyboardLayout,
+	pub key_names: Vec<String>,
+}
+
+fn doc_params(globals: &WguiGlobals) -> ParseDocumentParams<'_> {
+	ParseDocumentParams {
+		globals: globals.clone(),
+		path: AssetPath::BuiltIn("gui/view/keyboard_editor.xml"),
+		extra: Default::default(),
+	}
+}
+
+impl View {
+	fn handle_func_button_click(&self, task: ViewTask) -> ButtonClickCallback {
+		let tasks = self.tasks.clone();
+		let on_update = self.on_update.clone();
+		Rc::new(move |_common, _evt| {
+			tasks.push(task.clone());
+			(*on_update)();
+			Ok(())
+		})
+	}
+
+	pub fn new(params: Params) -> anyhow::Result<Self> {
+		let tasks = ViewTasks::new();
+
+		let state = wgui::parser::parse_from_assets(&doc_params(&params.globals), params.layout, params.parent_id)?;
+
+		let slider_size = state.fetch_component_as::<ComponentSlider>("slider_size")?;
+		let slider_syncing = Rc::new(Cell::new(false));
+		slider_size.on_value_changed({
+			let tasks = tasks.clone();
+			let on_update = params.on_update.clone();
+			let slider_syncing = slider_syncing.clone();
+			Box::new(move |_common, event| {
+				if !slider_syncing.get() {
+					tasks.push(ViewTask::SetSize(event.value));
+					(*on_update)();
+				}
+				Ok(())
+			})
+		});
+
+		let res = Self {
+			id_rows: state.get_widget_id("rows")?,
+			id_picker: state.get_widget_id("picker")?,
+			id_errors: state.get_widget_id("errors")?,
+			id_label_selected: state.get_widget_id("label_selected")?,
+			id_label_picker: state.get_widget_id("label_picker")?,
+			tasks,
+			frontend_tasks: params.frontend_tasks,
+			on_update: params.on_update,
+			globals: params.globals,
+			state,
+			keyboard: params.keyboard,
+			key_names: params.key_names,
+			selected: None,
+			recording: None,
+			slider_size,
+			slider_syncing,
+		};
+
+		for (id, task) in [
+			("btn_insert_key", ViewTask::InsertKey),
+			("btn_remove_key", ViewTask::RemoveKey),
+			("btn_clear_key", ViewTask::ClearKey),
+			("btn_reset_label", ViewTask::ResetLabel),
+			("btn_insert_row", ViewTask::InsertRow),
+			("btn_remove_row", ViewTask::RemoveRow),
+			("btn_record_macro", ViewTask::ToggleRecording),
+			("btn_revert", ViewTask::Revert),
+			("btn_default", ViewTask::LoadDefault),
+			("btn_save", ViewTask::Save),
+		] {
+			let btn = res.state.fetch_component_as::<ComponentButton>(id)?;
+			btn.on_click(res.handle_func_button_click(task));
+		}
+
+		res.tasks.push(ViewTask::Remount);
+		res.tasks.push(ViewTask::RemountPicker);
+		(*res.on_update)();
+
+		Ok(res)
+	}
+
+	pub fn update<T>(
+		&mut self,
+		layout: &mut Layout,
+		interface: &mut BoxDashInterface<T>,
+		data: &mut T,
+	) -> anyhow::Result<()> {
+		while self.process_tasks(layout, interface, data)? {}
+
+		Ok(())
+	}
+
+	fn process_tasks<T>(
+		&mut self,
+		layout: &mut Layout,
+		interface: &mut BoxDashInterface<T>,
+		data: &mut T,
+	) -> anyhow::Result<bool> {
+		let tasks = self.tasks.drain();
+		if tasks.is_empty() {
+			return Ok(false);
+		}
+
+		for task in tasks {
+			match task {
+				ViewTask::Remount => self.remount(layout)?,
+				ViewTask::RemountPicker => self.remount_picker(layout)?,
+				ViewTask::Revert | ViewTask::LoadDefault => {
+					self.keyboard = interface.keyboard_layout_get(data, matches!(task, ViewTask::LoadDefault));
+					self.selected = None;
+					self.recording = None;
+					self.tasks.push(ViewTask::Remount);
+					self.tasks.push(ViewTask::RemountPicker);
+				}
+				ViewTask::Save => {
+					self.stop_recording();
+					self.tasks.push(ViewTask::Remount);
+					self.tasks.push(ViewTask::RemountPicker);
+
+					let toast = match interface.keyboard_layout_save(data, &self.keyboard) {
+						Ok(()) => Translation::from_translation_key("KEYBOARD_EDITOR.SAVED"),
+						Err(e) => Translation::from_raw_text(&format!(
+							"{}: {e}",
+							self.globals.i18n().translate("KEYBOARD_EDITOR.SAVE_FAILED")
+						)),
+					};
+					self.frontend_tasks.push(FrontendTask::PushToast(toast));
+				}
+				task => self.edit(task),
+			}
+		}
+
+		Ok(true)
+	}
+
+	/// Changes the layout and queues a remount.
+	fn edit(&mut self, task: ViewTask) {
+		let keyboard = &mut self.keyboard;
+		let selected_key = self
+			.selected
+			.and_then(|(row, col)| Some((row, col, keyboard.main_layout.get(row)?.get(col)?.clone())));
+
+		match task {
+			ViewTask::Select(row, col) => {
+				if self.recording.is_some() {
+					self.stop_recording();
+					self.tasks.push(ViewTask::RemountPicker);
+				}
+				self.selected = Some((row, col));
+			}
+			ViewTask::SetSize(size) => {
+				let Some((row, col)) = self.selected else {
+					return;
+				};
+				let Some(current) = keyboard.key_sizes.get_mut(row).and_then(|s| s.get_mut(col)) else {
+					return;
+				};
+				if (*current - size).abs() < f32::EPSILON {
+					return;
+				}
+				*current = size;
+			}
+			ViewTask::InsertKey => {
+				let Some((row, col)) = self.selected else {
+					return;
+				};
+				keyboard.insert_key(row, col + 1, None, 1.0);
+				self.selected = Some((row, col + 1));
+			}
+			ViewTask::RemoveKey => {
+				let Some((row, col)) = self.selected else {
+					return;
+				};
+				keyboard.remove_key(row, col);
+				let remaining = keyboard.main_layout.get(row).map_or(0, Vec::len);
+				self.selected = (remaining > 0).then(|| (row, col.min(remaining - 1)));
+			}
+			ViewTask::ClearKey => {
+				if let Some((row, col, _)) = selected_key {
+					keyboard.main_layout[row][col] = None;
+				}
+			}
+			ViewTask::ResetLabel => {
+				if let Some((_, _, Some(name))) = selected_key {
+					keyboard.labels.remove(&name);
+				}
+			}
+			ViewTask::InsertRow => {
+				let row = self.selected.map_or(keyboard.main_layout.len(), |(row, _)| row + 1);
+				keyboard.insert_row(row);
+				self.selected = Some((row, 0));
+			}
+			ViewTask::RemoveRow => {
+				if let Some((row, _)) = self.selected {
+					keyboard.remove_row(row);
+					self.selected = None;
+				}
+			}
+			ViewTask::Pick(name) => {
+				if let Some(recording) = &mut self.recording {
+					let verbs = keyboard.macros.entry(recording.name.clone()).or_default();
+					if MACRO_MODIFIERS.contains(&name.as_ref()) {
+						verbs.push(format!("{name} DOWN"));
+						recording.held.push(name.to_string());
+					} else {
+						verbs.push(name.to_string());
+					}
+				} else if let Some((row, col, _)) = selected_key {
+					keyboard.main_layout[row][col] = Some(name.to_string());
+				}
+			}
+			ViewTask::ToggleRecording => {
+				if self.recording.is_some() {
+					self.stop_recording();
+				} else if let Some((row, col, _)) = selected_key {
+					let name = (1..)
+						.map(|i| format!("MACRO_{i}"))
+						.find(|n| !keyboard.macros.contains_key(n))
+						.unwrap(); // infinite iterator
+					keyboard.macros.insert(name.clone(), vec![]);
+					keyboard.main_layout[row][col] = Some(name.clone());
+					self.recording = Some(MacroRecording { name, held: vec![] });
+				}
+				self.tasks.push(ViewTask::RemountPicker);
+			}
+			ViewTask::Remount | ViewTask::RemountPicker | ViewTask::Revert | ViewTask::LoadDefault | ViewTask::Save => {}
+		}
+
+		self.tasks.push(ViewTask::Remount);
+	}
+
+	/// Releases the modifiers that are still held down at the end of the macro.
+	fn stop_recording(&mut self) {
+		let Some(recording) = self.recording.take() else {
+			return;
+		};
+		let verbs = self.keyboard.macros.entry(recording.name).or_default();
+		for key in recording.held.iter().rev() {
+			verbs.push(format!("{key} UP"));
+		}
+	}
+
+	fn mount_template(
+		&mut self,
+		layout: &mut Layout,
+		template: &str,
+		parent: WidgetID,
+		params: &[(&str, &str)],
+	) -> anyhow::Result<wgui::parser::ParserData> {
+		let par = params
+			.iter()
+			.map(|(k, v)| (Rc::from(*k), Rc::from(*v)))
+			.collect::<HashMap<Rc<str>, Rc<str>>>();
+		self
+			.state
+			.parse_template(&doc_params(&self.globals), template, layout, parent, par)
+	}
+
+	fn remount(&mut self, layout: &mut Layout) -> anyhow::Result<()> {
+		layout.remove_children(self.id_rows);
+		layout.remove_children(self.id_errors);
+
+		let unit = if self.keyboard.row_size > 0.0 {
+			(PREVIEW_WIDTH / self.keyboard.row_size).min(MAX_KEY_UNIT)
+		} else {
+			MAX_KEY_UNIT
+		};
+
+		let mut selected_btn = None;
+		let keyboard = self.keyboard.clone();
+		for (row, keys) in keyboard.main_layout.iter().enumerate() {
+			let data = self.mount_template(layout, "Row", self.id_rows, &[("id", "row")])?;
+			let id_row = data.get_widget_id("row")?;
+
+			for (col, key) in keys.iter().enumerate() {
+				let size = keyboard
+					.key_sizes
+					.get(row)
+					.and_then(|s| s.get(col))
+					.copied()
+					.unwrap_or(1.0);
+				let width = (size * unit - 2.0).max(4.0).to_string();
+				let data = if let Some(key) = key {
+					let text = keyboard.label_for_key(key).join(" ");
+					self.mount_template(
+						layout,
+						"Key",
+						id_row,
+						&[("id", "key"), ("text", &text), ("width", &width)],
+					)?
+				} else {
+					self.mount_template(layout, "Gap", id_row, &[("id", "key"), ("width", &width)])?
+				};
+
+				let btn = data.fetch_component_as::<ComponentButton>("key")?;
+				btn.on_click(self.handle_func_button_click(ViewTask::Select(row, col)));
+				if self.selected == Some((row, col)) {
+					selected_btn = Some(btn);
+				}
+			}
+		}
+
+		let errors = keyboard.validate(|key| self.key_names.iter().any(|n| n == key));
+		if errors.is_empty() {
+			self.mount_template(layout, "NoErrors", self.id_errors, &[])?;
+		}
+		for e in &errors {
+			self.mount_template(layout, "Error", self.id_errors, &[("text", &e.to_string())])?;
+		}
+
+		let mut c = layout.start_common();
+		let mut common = c.common();
+
+		if let Some(btn) = selected_btn {
+			btn.set_color(&mut common, self.globals.defaults().accent_color);
+		}
+
+		let selected_text = match (&self.recording, self.selected) {
+			(Some(recording), _) => {
+				let verbs = keyboard
+					.macros
+					.get(&recording.name)
+					.map(|v| v.join(", "))
+					.unwrap_or_default();
+				Translation::from_translation_key(&format!("KEYBOARD_EDITOR.RECORDING;{};{verbs}", recording.name))
+			}
+			(None, Some((row, col))) => {
+				let key = keyboard
+					.main_layout
+					.get(row)
+					.and_then(|r| r.get(col))
+					.and_then(Option::as_deref)
+					.unwrap_or("-");
+				let size = keyboard
+					.key_sizes
+					.get(row)
+					.and_then(|s| s.get(col))
+					.copied()
+					.unwrap_or(1.0);
+				self.slider_syncing.set(true);
+				self.slider_size.set_value(&mut common, size);
+				self.slider_syncing.set(false);
+				Translation::from_translation_key(&format!(
+					"KEYBOARD_EDITOR.SELECTED_KEY;{};{};{key};{size}",
+					row + 1,
+					col + 1
+				))
+			}
+			(None, None) => Translation::from_translation_key("KEYBOARD_EDITOR.NOTHING_SELECTED"),
+		};
+
+		{
+			let mut label = common.state.widgets.cast_as::<WidgetLabel>(self.id_label_selected)?;
+			label.set_text(&mut common, selected_text);
+		}
+
+		c.finish()?;
+		Ok(())
+	}
+
+	/// Keys that can be assigned to the selected key, or added to the macro being recorded.
+	fn remount_picker(&mut self, layout: &mut Layout) -> anyhow::Result<()> {
+		layout.remove_children(self.id_picker);
+
+		let recording = self.recording.is_some();
+		let mut names: Vec<String> = vec![];
+		if !recording {
+			names.extend(self.keyboard.macros.keys().cloned());
+			names.extend(self.keyboard.exec_commands.keys().cloned());
+		}
+		names.extend(self.key_names.iter().cloned());
+
+		for name in names {
+			let data = self.mount_template(layout, "PickerKey", self.id_picker, &[("id", "key"), ("text", &name)])?;
+			let btn = data.fetch_component_as::<ComponentButton>("key")?;
+			btn.on_click(self.handle_func_button_click(ViewTask::Pick(name.into())));
+		}
+
+		let mut c = layout.start_common();
+		let mut common = c.common();
+		{
+			let mut label = common.state.widgets.cast_as::<WidgetLabel>(self.id_label_picker)?;
+			label.set_text(
+				&mut common,
+				Translation::from_translation_key(if recording {
+					"KEYBOARD_EDITOR.PICK_MACRO_STEP"
+				} else {
+					"KEYBOARD_EDITOR.ASSIGN_KEY"
+				}),
+			);
+		}
+		c.finish()?;
+		Ok(())
+	}
+}
@@ -3,4 +3,5 @@ pub mod audio_settings;
 pub mod game_cover;
 pub mod game_launcher;
 pub mod game_list;
+pub mod keyboard_editor;
 pub mod running_games_list;
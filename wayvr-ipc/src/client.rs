@@ -171,6 +171,8 @@ impl WayVRClient {
 					}
 					e = client_runner(client.clone()) => {
 							log::info!("IPC Runner failed: {:?}", e);
+							// no more signals are going to arrive
+							client.lock().await.on_signal = None;
 					}
 			}
 		});
@@ -223,7 +225,7 @@ impl WayVRClient {
 				);
 			}
 
-			if let PacketServer::WvrStateChanged(_) = &packet {
+			if let PacketServer::WvrStateChanged(_) | PacketServer::WlxEvent(_) = &packet {
 				if let Some(on_signal) = &mut client.on_signal {
 					if (*on_signal)(&packet) {
 						// Signal consumed
@@ -420,6 +422,248 @@ impl WayVRClient {
 		Ok(())
 	}
 
+	pub async fn fn_wlx_overlay_list(
+		client: WayVRClientMutex,
+		serial: Serial,
+	) -> anyhow::Result<Vec<packet_server::WlxOverlay>> {
+		Ok(send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxOverlayList(serial),
+			WlxOverlayListResponse
+		))
+	}
+
+	pub async fn fn_wlx_overlay_get(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+	) -> anyhow::Result<Option<packet_server::WlxOverlay>> {
+		Ok(send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxOverlayGet(serial, name),
+			WlxOverlayGetResponse
+		))
+	}
+
+	pub async fn fn_wlx_overlay_set(
+		client: WayVRClientMutex,
+		serial: Serial,
+		params: packet_client::WlxOverlaySetParams,
+	) -> anyhow::Result<()> {
+		send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxOverlaySet(serial, params),
+			WlxOverlaySetResponse
+		)
+		.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_set_list(
+		client: WayVRClientMutex,
+		serial: Serial,
+	) -> anyhow::Result<Vec<packet_server::WlxSet>> {
+		Ok(send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxSetList(serial),
+			WlxSetListResponse
+		))
+	}
+
+	pub async fn fn_wlx_set_create(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: Option<String>,
+	) -> anyhow::Result<usize> {
+		send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxSetCreate(serial, name),
+			WlxSetCreateResponse
+		)
+		.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	async fn wlx_set_modify(
+		client: WayVRClientMutex,
+		serial: Serial,
+		packet: PacketClient,
+	) -> anyhow::Result<()> {
+		send_and_wait!(client, serial, &packet, WlxSetModifyResponse)
+			.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_set_delete(
+		client: WayVRClientMutex,
+		serial: Serial,
+		set: usize,
+	) -> anyhow::Result<()> {
+		Self::wlx_set_modify(client, serial, PacketClient::WlxSetDelete(serial, set)).await
+	}
+
+	pub async fn fn_wlx_set_rename(
+		client: WayVRClientMutex,
+		serial: Serial,
+		set: usize,
+		name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_set_modify(
+			client,
+			serial,
+			PacketClient::WlxSetRename(serial, set, name),
+		)
+		.await
+	}
+
+	pub async fn fn_wlx_set_add_overlay(
+		client: WayVRClientMutex,
+		serial: Serial,
+		set: usize,
+		overlay_name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_set_modify(
+			client,
+			serial,
+			PacketClient::WlxSetAddOverlay(serial, set, overlay_name),
+		)
+		.await
+	}
+
+	pub async fn fn_wlx_set_remove_overlay(
+		client: WayVRClientMutex,
+		serial: Serial,
+		set: usize,
+		overlay_name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_set_modify(
+			client,
+			serial,
+			PacketClient::WlxSetRemoveOverlay(serial, set, overlay_name),
+		)
+		.await
+	}
+
+	pub async fn fn_wlx_profile_list(
+		client: WayVRClientMutex,
+		serial: Serial,
+	) -> anyhow::Result<Vec<String>> {
+		Ok(send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxProfileList(serial),
+			WlxProfileListResponse
+		))
+	}
+
+	async fn wlx_profile(
+		client: WayVRClientMutex,
+		serial: Serial,
+		packet: PacketClient,
+	) -> anyhow::Result<()> {
+		send_and_wait!(client, serial, &packet, WlxProfileResponse)
+			.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_profile_save(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_profile(client, serial, PacketClient::WlxProfileSave(serial, name)).await
+	}
+
+	pub async fn fn_wlx_profile_load(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_profile(client, serial, PacketClient::WlxProfileLoad(serial, name)).await
+	}
+
+	pub async fn fn_wlx_profile_delete(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_profile(client, serial, PacketClient::WlxProfileDelete(serial, name)).await
+	}
+
+	pub async fn fn_wlx_profile_import(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+		data: String,
+	) -> anyhow::Result<()> {
+		Self::wlx_profile(
+			client,
+			serial,
+			PacketClient::WlxProfileImport(serial, name, data),
+		)
+		.await
+	}
+
+	pub async fn fn_wlx_profile_export(
+		client: WayVRClientMutex,
+		serial: Serial,
+		name: String,
+	) -> anyhow::Result<String> {
+		send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxProfileExport(serial, name),
+			WlxProfileExportResponse
+		)
+		.map_err(|e| anyhow::anyhow!("{}", e))
+	}
+
+	pub async fn fn_wlx_var_list(
+		client: WayVRClientMutex,
+		serial: Serial,
+	) -> anyhow::Result<Vec<(String, String)>> {
+		Ok(send_and_wait!(
+			client,
+			serial,
+			&PacketClient::WlxVarList(serial),
+			WlxVarListResponse
+		))
+	}
+
+	pub async fn fn_wlx_var_set(
+		client: WayVRClientMutex,
+		key: String,
+		value: Option<String>,
+	) -> anyhow::Result<()> {
+		send_only!(client, &PacketClient::WlxVarSet(key, value));
+		Ok(())
+	}
+
+	pub async fn fn_wlx_screens_arrange(
+		client: WayVRClientMutex,
+		arrangement: packet_client::WlxScreenArrangement,
+	) -> anyhow::Result<()> {
+		send_only!(client, &PacketClient::WlxScreensArrange(arrangement));
+		Ok(())
+	}
+
+	pub async fn fn_wlx_do_not_disturb_set(
+		client: WayVRClientMutex,
+		enabled: Option<bool>,
+	) -> anyhow::Result<()> {
+		send_only!(client, &PacketClient::WlxDoNotDisturbSet(enabled));
+		Ok(())
+	}
+
+	pub async fn fn_wlx_subscribe(
+		client: WayVRClientMutex,
+		topics: Vec<packet_server::WlxEventTopic>,
+	) -> anyhow::Result<()> {
+		send_only!(client, &PacketClient::WlxSubscribe(topics));
+		Ok(())
+	}
+
 	pub async fn fn_wlx_modify_panel(
 		client: WayVRClientMutex,
 		params: packet_client::WlxModifyPanelParams,
@@ -55,7 +55,34 @@ pub struct WlxModifyPanelParams {
 	pub command: WlxModifyPanelCommand,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Fields left as None are not modified.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WlxOverlaySetParams {
+	pub name: String,
+	pub visible: Option<bool>,
+	pub pos: Option<[f32; 3]>,
+	pub rot: Option<[f32; 4]>,
+	pub scale: Option<f32>,
+	pub alpha: Option<f32>,
+	pub curvature: Option<f32>, // 0 for flat
+	pub positioning: Option<packet_server::WlxPositioning>,
+	pub grabbable: Option<bool>,
+	pub interactable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum WlxScreenArrangement {
+	/// Side by side, facing the same way
+	Row,
+	/// Side by side, curved around the user. `None` uses `screen_arc_radius` from the config.
+	Arc { radius: Option<f32> },
+	/// On top of each other
+	Stack,
+	/// Same layout as on the desktop
+	Desktop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PacketClient {
 	Handshake(Handshake),
 	WvrWindowList(Serial),
@@ -69,4 +96,33 @@ pub enum PacketClient {
 	WlxDeviceHaptics(usize, WlxHapticsParams),
 	WlxShowHide,
 	WlxSwitchSet(Option<usize>),
+	/// Replaces the set of event topics pushed to this connection. Empty to unsubscribe.
+	WlxSubscribe(Vec<packet_server::WlxEventTopic>),
+	WlxOverlayList(Serial),
+	WlxOverlayGet(Serial, String),
+	WlxOverlaySet(Serial, WlxOverlaySetParams),
+	WlxSetList(Serial),
+	/// Appends a new set with only the keyboard in it, optionally named. Does not switch to it.
+	WlxSetCreate(Serial, Option<String>),
+	// set indices are 0-based
+	WlxSetDelete(Serial, usize),
+	WlxSetRename(Serial, usize, String),
+	WlxSetAddOverlay(Serial, usize, String),
+	WlxSetRemoveOverlay(Serial, usize, String),
+	WlxProfileList(Serial),
+	/// Saves the current layout under the given name, overwriting any existing profile.
+	WlxProfileSave(Serial, String),
+	WlxProfileLoad(Serial, String),
+	WlxProfileDelete(Serial, String),
+	/// Returns the contents of the profile, to be imported with `WlxProfileImport`.
+	WlxProfileExport(Serial, String),
+	/// Name and contents of the profile.
+	WlxProfileImport(Serial, String, String),
+	WlxVarList(Serial),
+	/// Sets a shared panel variable. `None` removes it.
+	WlxVarSet(String, Option<String>),
+	/// Turns do-not-disturb on or off. `None` toggles it.
+	WlxDoNotDisturbSet(Option<bool>),
+	/// Moves all visible screens in front of the user.
+	WlxScreensArrange(WlxScreenArrangement),
 }
@@ -93,6 +93,136 @@ pub struct WlxInputState {
 	pub right: WlxInputStatePointer,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WlxEventTopic {
+	OverlayVisibility,
+	SetSwitched,
+	EditMode,
+	KeyboardFocus,
+	Toast,
+	Variables,
+	DoNotDisturb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WlxKeyboardFocus {
+	PhysicalScreen,
+	WayVR,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum WlxEvent {
+	OverlayShown {
+		name: String,
+	},
+	OverlayHidden {
+		name: String,
+	},
+	SetSwitched {
+		set: Option<usize>,
+	},
+	EditModeChanged {
+		enabled: bool,
+	},
+	KeyboardFocusChanged {
+		focus: WlxKeyboardFocus,
+	},
+	ToastPosted {
+		topic: String,
+		title: String,
+		body: String,
+	},
+	/// `value` is `None` if the variable was removed.
+	VariableChanged {
+		key: String,
+		value: Option<String>,
+	},
+	DoNotDisturbChanged {
+		active: bool,
+	},
+}
+
+impl WlxEvent {
+	pub const fn topic(&self) -> WlxEventTopic {
+		match self {
+			WlxEvent::OverlayShown { .. } | WlxEvent::OverlayHidden { .. } => {
+				WlxEventTopic::OverlayVisibility
+			}
+			WlxEvent::SetSwitched { .. } => WlxEventTopic::SetSwitched,
+			WlxEvent::EditModeChanged { .. } => WlxEventTopic::EditMode,
+			WlxEvent::KeyboardFocusChanged { .. } => WlxEventTopic::KeyboardFocus,
+			WlxEvent::ToastPosted { .. } => WlxEventTopic::Toast,
+			WlxEvent::VariableChanged { .. } => WlxEventTopic::Variables,
+			WlxEvent::DoNotDisturbChanged { .. } => WlxEventTopic::DoNotDisturb,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WlxOverlayCategory {
+	Internal,
+	Keyboard,
+	Dashboard,
+	Panel,
+	Screen,
+	Mirror,
+	WayVR,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WlxHand {
+	Left,
+	Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WlxPositioning {
+	Floating,
+	Anchored,
+	Static,
+	FollowHead {
+		lerp: f32,
+	},
+	FollowHand {
+		hand: WlxHand,
+		lerp: f32,
+		align_to_hmd: bool,
+	},
+}
+
+/// Transform in stage space. Rotation is a quaternion in XYZW order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WlxTransform {
+	pub pos: [f32; 3],
+	pub rot: [f32; 4],
+	pub scale: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WlxOverlayState {
+	pub transform: WlxTransform,
+	pub alpha: f32,
+	pub curvature: Option<f32>,
+	pub positioning: WlxPositioning,
+	pub grabbable: bool,
+	pub interactable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WlxOverlay {
+	pub name: String,
+	pub category: WlxOverlayCategory,
+	pub visible: bool,
+	pub state: Option<WlxOverlayState>, // None if the overlay is not visible
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WlxSet {
+	pub name: String,
+	pub active: bool,
+	pub overlays: Vec<String>,
+}
+
 // "Wvr" prefixes are WayVR-specific
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,11 +230,23 @@ pub enum PacketServer {
 	Disconnect(Disconnect),
 	HandshakeSuccess(HandshakeSuccess),
 	WlxInputStateResponse(Serial, WlxInputState),
+	WlxOverlayListResponse(Serial, Vec<WlxOverlay>),
+	WlxOverlayGetResponse(Serial, Option<WlxOverlay>),
+	WlxOverlaySetResponse(Serial, Result<(), String>),
+	WlxSetListResponse(Serial, Vec<WlxSet>),
+	WlxSetCreateResponse(Serial, Result<usize, String>),
+	WlxSetModifyResponse(Serial, Result<(), String>),
+	WlxProfileListResponse(Serial, Vec<String>),
+	WlxProfileResponse(Serial, Result<(), String>),
+	WlxProfileExportResponse(Serial, Result<String, String>),
+	/// Key and value pairs, sorted by key.
+	WlxVarListResponse(Serial, Vec<(String, String)>),
 	WvrWindowListResponse(Serial, Option<WvrWindowList>),
 	WvrProcessGetResponse(Serial, Option<WvrProcess>),
 	WvrProcessLaunchResponse(Serial, Result<WvrProcessHandle, String>),
 	WvrProcessListResponse(Serial, WvrProcessList),
 	WvrStateChanged(WvrStateChanged),
+	WlxEvent(WlxEvent),
 }
 
 impl PacketServer {
@@ -113,11 +255,22 @@ impl PacketServer {
 			PacketServer::Disconnect(_) => None,
 			PacketServer::HandshakeSuccess(_) => None,
 			PacketServer::WlxInputStateResponse(serial, _) => Some(serial),
+			PacketServer::WlxOverlayListResponse(serial, _) => Some(serial),
+			PacketServer::WlxOverlayGetResponse(serial, _) => Some(serial),
+			PacketServer::WlxOverlaySetResponse(serial, _) => Some(serial),
+			PacketServer::WlxSetListResponse(serial, _) => Some(serial),
+			PacketServer::WlxSetCreateResponse(serial, _) => Some(serial),
+			PacketServer::WlxSetModifyResponse(serial, _) => Some(serial),
+			PacketServer::WlxProfileListResponse(serial, _) => Some(serial),
+			PacketServer::WlxProfileResponse(serial, _) => Some(serial),
+			PacketServer::WlxProfileExportResponse(serial, _) => Some(serial),
+			PacketServer::WlxVarListResponse(serial, _) => Some(serial),
 			PacketServer::WvrWindowListResponse(serial, _) => Some(serial),
 			PacketServer::WvrProcessGetResponse(serial, _) => Some(serial),
 			PacketServer::WvrProcessLaunchResponse(serial, _) => Some(serial),
 			PacketServer::WvrProcessListResponse(serial, _) => Some(serial),
 			PacketServer::WvrStateChanged(_) => None,
+			PacketServer::WlxEvent(_) => None,
 		}
 	}
 }
@@ -0,0 +1,304 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use glam::Vec3A;
+use wlx_common::{
+    common::LeftRight,
+    config::{GestureAction, GestureBinding},
+};
+
+use crate::{
+    backend::input::{Haptics, InputState, Pointer, PointerMode},
+    state::AppState,
+    windowing::{OverlayID, manager::OverlayWindowManager, window},
+};
+
+/// How long pose samples are kept for flick detection
+const HISTORY: Duration = Duration::from_millis(100);
+const DOUBLE_TAP_TIME: Duration = Duration::from_millis(400);
+/// Max distance between the two taps of a double tap, in meters
+const TAP_SLOP: f32 = 0.03;
+const FLICK_SPEED: f32 = 2.0; // m/s
+const FLICK_COOLDOWN: Duration = Duration::from_millis(600);
+/// How long a pinch is held back before it's forwarded as a click
+const PRESS_DELAY: Duration = Duration::from_millis(150);
+/// Vertical movement that turns a pinch into a scroll, in meters
+const SCROLL_SLOP: f32 = 0.02;
+/// Scroll units per meter of hand movement
+const SCROLL_SPEED: f32 = 150.0;
+
+const HAPTICS_GESTURE: Haptics = Haptics {
+    intensity: 0.25,
+    duration: 0.05,
+    frequency: 0.1,
+};
+
+#[derive(Clone, Copy, Default)]
+enum PinchState {
+    /// Clicks are passed through
+    #[default]
+    Idle,
+    /// Held back until it's clear whether this is a click, a scroll or a zoom
+    Pending {
+        since: Instant,
+        origin: Vec3A,
+    },
+    Click,
+    Scroll {
+        last: Vec3A,
+    },
+    Zoom,
+    /// Swallowed until released, e.g. the second tap of a double tap
+    Consumed,
+}
+
+#[derive(Default)]
+pub struct GestureState {
+    pinch: PinchState,
+    /// Click as reported by the backend, before any filtering
+    raw_click: bool,
+    samples: VecDeque<(Instant, Vec3A)>,
+    last_tap: Option<(Instant, Vec3A)>,
+    last_flick: Option<Instant>,
+    /// Mode for the next click, set by a gesture
+    latched_mode: Option<PointerMode>,
+    /// Hide the hovered overlay
+    pub dismiss: bool,
+}
+
+impl GestureState {
+    pub const fn latched_mode(&self) -> Option<PointerMode> {
+        self.latched_mode
+    }
+
+    const fn is_zooming(&self) -> bool {
+        matches!(self.pinch, PinchState::Zoom)
+    }
+}
+
+/// Overlay being resized with both hands, and the distance between them on the last frame.
+#[derive(Clone, Copy)]
+pub struct ZoomState {
+    overlay: OverlayID,
+    dist: f32,
+}
+
+fn find_action(
+    gestures: &[GestureBinding],
+    side: LeftRight,
+    double_tap: bool,
+) -> Option<GestureAction> {
+    gestures.iter().find_map(|g| match g {
+        GestureBinding::DoubleTap { action, .. } if double_tap && g.applies_to(side) => {
+            Some(*action)
+        }
+        GestureBinding::Flick { action, .. } if !double_tap && g.applies_to(side) => Some(*action),
+        _ => None,
+    })
+}
+
+fn trigger(hand: &mut Pointer, action: GestureAction) {
+    log::debug!("Hand {}: gesture {action:?}", hand.idx);
+    match action {
+        GestureAction::RightClick => hand.gesture.latched_mode = Some(PointerMode::Right),
+        GestureAction::MiddleClick => hand.gesture.latched_mode = Some(PointerMode::Middle),
+        GestureAction::Dismiss => hand.gesture.dismiss = true,
+        GestureAction::ShowHide => hand.now.show_hide = true,
+        GestureAction::ToggleDashboard => hand.now.toggle_dashboard = true,
+    }
+    hand.pending_haptics = Some(HAPTICS_GESTURE);
+}
+
+/// Recognizes gestures from the pose and click history, then rewrites `PointerState` to match.
+/// Must run right after the backend has updated the pointers.
+pub fn update(input: &mut InputState, gestures: &[GestureBinding]) {
+    let now = Instant::now();
+    let hmd_up = input.hmd.transform_vector3a(Vec3A::Y);
+
+    let zoom_enabled = gestures
+        .iter()
+        .any(|g| matches!(g, GestureBinding::PinchZoom));
+    let both_pinching = zoom_enabled
+        && input
+            .pointers
+            .iter()
+            .all(|p| p.tracked && p.now.click && p.interaction.grabbed.is_none());
+
+    for hand in &mut input.pointers {
+        let Some(side) = hand.hand() else {
+            continue;
+        };
+        let pos = hand.pose.translation;
+        let grabbing = hand.interaction.grabbed.is_some();
+
+        let gesture = &mut hand.gesture;
+        gesture.dismiss = false;
+
+        gesture.samples.push_back((now, pos));
+        while gesture
+            .samples
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > HISTORY)
+        {
+            gesture.samples.pop_front();
+        }
+
+        let raw_click = hand.now.click;
+        let pressed = raw_click && !gesture.raw_click;
+        gesture.raw_click = raw_click;
+
+        // the latched mode only applies to a single click
+        if !raw_click && hand.before.click {
+            gesture.latched_mode = None;
+        }
+
+        if !hand.tracked {
+            continue;
+        }
+
+        if pressed && let Some(action) = find_action(gestures, side, true) {
+            if gesture
+                .last_tap
+                .is_some_and(|(at, p)| now - at < DOUBLE_TAP_TIME && p.distance(pos) < TAP_SLOP)
+            {
+                gesture.last_tap = None;
+                gesture.pinch = PinchState::Consumed;
+                trigger(hand, action);
+            } else {
+                gesture.last_tap = Some((now, pos));
+            }
+        }
+
+        let gesture = &mut hand.gesture;
+        if !raw_click
+            && !grabbing
+            && gesture
+                .last_flick
+                .is_none_or(|at| now - at > FLICK_COOLDOWN)
+            && let Some((at, oldest)) = gesture.samples.front().copied()
+            && let Some(action) = find_action(gestures, side, false)
+        {
+            let dt = now.duration_since(at).as_secs_f32();
+            if dt > HISTORY.as_secs_f32() * 0.5 && pos.distance(oldest) / dt > FLICK_SPEED {
+                gesture.last_flick = Some(now);
+                gesture.samples.clear();
+                trigger(hand, action);
+            }
+        }
+
+        let scroll_enabled = gestures
+            .iter()
+            .any(|g| matches!(g, GestureBinding::PinchScroll { .. }) && g.applies_to(side));
+
+        let gesture = &mut hand.gesture;
+        match gesture.pinch {
+            PinchState::Idle => {
+                if pressed && !grabbing && (scroll_enabled || zoom_enabled) {
+                    gesture.pinch = PinchState::Pending {
+                        since: now,
+                        origin: pos,
+                    };
+                    hand.now.click = false;
+                }
+            }
+            PinchState::Pending { since, origin } => {
+                if !raw_click {
+                    // a quick tap: press now, release on the next frame
+                    hand.now.click = true;
+                    gesture.pinch = PinchState::Idle;
+                } else if both_pinching {
+                    gesture.pinch = PinchState::Zoom;
+                    hand.now.click = false;
+                } else if scroll_enabled && (pos - origin).dot(hmd_up).abs() > SCROLL_SLOP {
+                    gesture.pinch = PinchState::Scroll { last: pos };
+                    hand.now.click = false;
+                    hand.pending_haptics = Some(HAPTICS_GESTURE);
+                } else if now - since > PRESS_DELAY {
+                    gesture.pinch = PinchState::Click;
+                } else {
+                    hand.now.click = false;
+                }
+            }
+            PinchState::Scroll { last } => {
+                hand.now.click = false;
+                if raw_click {
+                    // moving the hand up moves the content up
+                    let scroll_y = (last - pos).dot(hmd_up) * SCROLL_SPEED;
+                    // small movements add up until they're large enough to not be ignored
+                    if scroll_y.abs() > 0.1 {
+                        hand.now.scroll_y += scroll_y;
+                        gesture.pinch = PinchState::Scroll { last: pos };
+                    }
+                } else {
+                    gesture.pinch = PinchState::Idle;
+                }
+            }
+            PinchState::Click | PinchState::Zoom | PinchState::Consumed => {
+                if raw_click {
+                    if !matches!(gesture.pinch, PinchState::Click) {
+                        hand.now.click = false;
+                    }
+                } else {
+                    gesture.pinch = PinchState::Idle;
+                }
+            }
+        }
+    }
+}
+
+/// Resizes the overlay that both hands are pinching, by how far apart they moved.
+pub fn handle_zoom<O>(overlays: &mut OverlayWindowManager<O>, app: &mut AppState)
+where
+    O: Default,
+{
+    let [left, right] = &app.input_state.pointers;
+    let zooming = left.gesture.is_zooming() && right.gesture.is_zooming();
+    let target = left
+        .interaction
+        .hovered_id
+        .filter(|id| right.interaction.hovered_id == Some(*id));
+    let dist = left.pose.translation.distance(right.pose.translation);
+
+    let Some(overlay) = target.filter(|_| zooming) else {
+        // zoom ended
+        if let Some(zoom) = app.input_state.zoom.take()
+            && let Some(o) = overlays.mut_by_id(zoom.overlay)
+            && let Some(state) = o.config.active_state.as_mut()
+        {
+            window::save_transform(state, app);
+        }
+        return;
+    };
+
+    let Some(o) = overlays.mut_by_id(overlay) else {
+        return;
+    };
+    let Some(state) = o.config.active_state.as_mut() else {
+        return;
+    };
+    if !state.grabbable {
+        return;
+    }
+
+    let last = app.input_state.zoom.replace(ZoomState { overlay, dist });
+    let Some(zoom) = last.filter(|z| z.overlay == overlay) else {
+        for pointer in &mut app.input_state.pointers {
+            pointer.pending_haptics = Some(HAPTICS_GESTURE);
+        }
+        return;
+    };
+
+    if zoom.dist <= f32::EPSILON {
+        return;
+    }
+
+    let factor = dist / zoom.dist;
+    let cur_scale = state.transform.x_axis.length();
+    if (cur_scale < 0.1 && factor < 1.0) || (cur_scale > 20. && factor > 1.0) {
+        return;
+    }
+    state.transform.matrix3 = state.transform.matrix3.mul_scalar(factor);
+    o.config.dirty = true;
+}
@@ -3,15 +3,19 @@ use std::process::{Child, Command};
 use std::sync::Arc;
 use std::time::Instant;
 
-use glam::{Affine3A, Vec2, Vec3A, Vec3Swizzles};
+use glam::{Affine3A, Quat, Vec2, Vec3, Vec3A, Vec3Swizzles};
 
 use idmap_derive::IntegerId;
 use smallvec::{SmallVec, smallvec};
 use strum::AsRefStr;
+use wayvr_ipc::packet_server::{WlxEvent, WlxKeyboardFocus};
 use wlx_common::common::LeftRight;
 use wlx_common::windowing::{OverlayWindowState, Positioning};
 
-use crate::backend::task::{InputTask, OverlayTask};
+use crate::backend::gesture::{self, GestureState, ZoomState};
+use crate::backend::snap::{self, GuideLine};
+use crate::backend::task::{InputTask, OverlayTask, ToggleMode};
+use crate::ipc::signal::WayVRSignal;
 use crate::overlays::anchor::{ANCHOR_NAME, GRAB_HELP_NAME};
 use crate::overlays::keyboard::KEYBOARD_NAME;
 use crate::overlays::watch::WATCH_NAME;
@@ -21,7 +25,7 @@ use crate::subsystem::input::KeyboardFocus;
 use crate::windowing::backend::OverlayEventData;
 use crate::windowing::manager::OverlayWindowManager;
 use crate::windowing::window::{self, OverlayWindowData, realign};
-use crate::windowing::{OverlayID, OverlaySelector};
+use crate::windowing::{OverlayID, OverlaySelector, snap_upright};
 
 use super::task::TaskType;
 
@@ -53,6 +57,7 @@ pub struct InputState {
     pub ipd: f32,
     pub pointers: [Pointer; 2],
     pub devices: Vec<TrackedDevice>,
+    pub(super) zoom: Option<ZoomState>,
     processes: Vec<Child>,
 }
 
@@ -63,6 +68,7 @@ impl InputState {
             ipd: 0.0,
             pointers: [Pointer::new(0), Pointer::new(1)],
             devices: Vec::new(),
+            zoom: None,
             processes: Vec::new(),
         }
     }
@@ -85,6 +91,8 @@ impl InputState {
     }
 
     pub fn post_update(&mut self, session: &AppSession) {
+        gesture::update(self, &session.config.gestures);
+
         for hand in &mut self.pointers {
             #[cfg(debug_assertions)]
             debug_print_hand(hand);
@@ -95,6 +103,11 @@ impl InputState {
 
             // Prevent the mode from changing during a click
             if !hand.before.click {
+                if let Some(mode) = hand.gesture.latched_mode() {
+                    hand.interaction.mode = mode;
+                    continue;
+                }
+
                 if hand.now.click_modifier_right {
                     hand.interaction.mode = PointerMode::Right;
                     continue;
@@ -238,6 +251,7 @@ pub struct Pointer {
     pub last_click: Instant,
     pub pending_haptics: Option<Haptics>,
     pub(super) interaction: InteractionState,
+    pub(super) gesture: GestureState,
     pub tracked: bool,
     pub handsfree: bool,
 }
@@ -254,6 +268,7 @@ impl Pointer {
             last_click: Instant::now(),
             pending_haptics: None,
             interaction: InteractionState::default(),
+            gesture: GestureState::default(),
             tracked: false,
             handsfree: false,
         }
@@ -315,6 +330,63 @@ pub struct GrabData {
     pub offset: Affine3A,
     pub grabbed_id: OverlayID,
     pub grab_anchor: bool,
+    /// Set on the hand that grabbed first, while the other hand holds the same overlay
+    pub two_handed: Option<TwoHandedGrab>,
+}
+
+/// Both hands hold the same overlay: scale by hand distance, rotate around the midpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct TwoHandedGrab {
+    /// Of the grabbed overlay, or the anchor, when the second hand joined
+    start_transform: Affine3A,
+    start_mid: Vec3A,
+    /// From the first hand to the second hand
+    start_span: Vec3A,
+}
+
+impl TwoHandedGrab {
+    fn new(start_transform: Affine3A, first: Vec3A, second: Vec3A) -> Self {
+        Self {
+            start_transform,
+            start_mid: (first + second) * 0.5,
+            start_span: second - first,
+        }
+    }
+
+    /// Without `free_rotation`, only rotates around the vertical axis and keeps the overlay upright.
+    fn transform(&self, first: Vec3A, second: Vec3A, free_rotation: bool) -> Affine3A {
+        let span = second - first;
+        let start_len = self.start_span.length();
+        if start_len < f32::EPSILON || span.length() < f32::EPSILON {
+            return self.start_transform;
+        }
+
+        // same limits as handle_scale
+        let cur_scale = self.start_transform.x_axis.length();
+        let scale = (span.length() / start_len).clamp(0.1 / cur_scale, 20. / cur_scale);
+
+        let rotation = if free_rotation {
+            Quat::from_rotation_arc(
+                Vec3::from(self.start_span.normalize()),
+                Vec3::from(span.normalize()),
+            )
+        } else {
+            let angle = |v: Vec3A| v.z.atan2(v.x);
+            Quat::from_rotation_y(angle(self.start_span) - angle(span))
+        };
+
+        let mid = (first + second) * 0.5;
+        let transform = Affine3A::from_translation(mid.into())
+            * Affine3A::from_scale_rotation_translation(Vec3::splat(scale), rotation, Vec3::ZERO)
+            * Affine3A::from_translation((-self.start_mid).into())
+            * self.start_transform;
+
+        if free_rotation {
+            transform
+        } else {
+            snap_upright(transform, Vec3A::Y)
+        }
+    }
 }
 
 #[repr(u8)]
@@ -368,12 +440,20 @@ fn populate_lines(
     }
 }
 
-fn update_focus(focus: &mut KeyboardFocus, overlay_keyboard_focus: Option<KeyboardFocus>) {
-    if let Some(f) = &overlay_keyboard_focus
-        && *focus != *f
+fn update_focus(app: &mut AppState, overlay_keyboard_focus: Option<KeyboardFocus>) {
+    if let Some(f) = overlay_keyboard_focus
+        && app.hid_provider.keyboard_focus != f
     {
-        log::debug!("Setting keyboard focus to {:?}", *f);
-        *focus = *f;
+        log::debug!("Setting keyboard focus to {f:?}");
+        app.hid_provider.keyboard_focus = f;
+
+        let focus = match f {
+            KeyboardFocus::PhysicalScreen => WlxKeyboardFocus::PhysicalScreen,
+            KeyboardFocus::WayVR => WlxKeyboardFocus::WayVR,
+        };
+        app.wayvr_signals.send(WayVRSignal::BroadcastEvent(
+            WlxEvent::KeyboardFocusChanged { focus },
+        ));
     }
 }
 
@@ -381,10 +461,13 @@ pub fn interact<O>(
     overlays: &mut OverlayWindowManager<O>,
     app: &mut AppState,
     lines: &mut Vec<PointerLine>,
+    guides: &mut Vec<GuideLine>,
 ) -> [Option<Haptics>; 2]
 where
     O: Default,
 {
+    gesture::handle_zoom(overlays, app);
+
     let hits = if app.input_state.pointers[1].last_click > app.input_state.pointers[0].last_click {
         let right = interact_hand(1, overlays, app);
         let left = interact_hand(0, overlays, app);
@@ -395,6 +478,10 @@ where
         [left, right]
     };
 
+    for idx in 0..2 {
+        snap::snap_grabbed(idx, overlays, app, guides);
+    }
+
     for (idx, hit) in hits.iter().enumerate() {
         populate_lines(
             lines,
@@ -466,6 +553,15 @@ where
     pointer.interaction.hovered_id = Some(hit.overlay);
     hovered.hover_pointers[idx] = true;
 
+    if pointer.gesture.dismiss && hovered.config.name.as_ref() != WATCH_NAME {
+        log::debug!("Hand {idx}: dismissed {}", hovered.config.name);
+        app.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::ToggleOverlay(
+                OverlaySelector::Id(hit.overlay),
+                ToggleMode::EnsureOff,
+            )));
+    }
+
     if let Some(primary_pointer) = hovered.primary_pointer {
         if hit.pointer <= primary_pointer {
             hovered.primary_pointer = Some(hit.pointer);
@@ -496,10 +592,7 @@ where
 
     // grab
     if pointer.now.grab && !pointer.before.grab && hovered_state.grabbable {
-        update_focus(
-            &mut app.hid_provider.keyboard_focus,
-            hovered.config.keyboard_focus,
-        );
+        update_focus(app, hovered.config.keyboard_focus);
         start_grab(
             idx,
             hit.overlay,
@@ -526,10 +619,7 @@ where
     let pointer = &mut app.input_state.pointers[hit.pointer];
     if pointer.now.click && !pointer.before.click {
         pointer.interaction.clicked_id = Some(hit.overlay);
-        update_focus(
-            &mut app.hid_provider.keyboard_focus,
-            hovered.config.keyboard_focus,
-        );
+        update_focus(app, hovered.config.keyboard_focus);
         hovered.config.backend.on_pointer(app, &hit, true);
     } else if !pointer.now.click && pointer.before.click {
         // send release event to overlay that was originally clicked
@@ -720,6 +810,33 @@ fn start_grab(
     app: &mut AppState,
     edit_mode: bool,
 ) {
+    // second hand on the same overlay
+    let pose = app.input_state.pointers[idx].pose;
+    let other = &mut app.input_state.pointers[1 - idx];
+    if let Some(other_grab) = other.interaction.grabbed.as_mut()
+        && other_grab.grabbed_id == id
+    {
+        let target = if other_grab.grab_anchor {
+            app.anchor
+        } else {
+            state.transform
+        };
+        other_grab.two_handed = Some(TwoHandedGrab::new(
+            target,
+            other.pose.translation,
+            pose.translation,
+        ));
+
+        let grab_anchor = other_grab.grab_anchor;
+        app.input_state.pointers[idx].interaction.grabbed = Some(GrabData {
+            offset: pose.inverse() * target,
+            grabbed_id: id,
+            grab_anchor,
+            two_handed: None,
+        });
+        return;
+    }
+
     let pointer = &mut app.input_state.pointers[idx];
 
     // Grab anchor if:
@@ -743,6 +860,7 @@ fn start_grab(
         offset,
         grabbed_id: id,
         grab_anchor,
+        two_handed: None,
     });
 
     // Show anchor
@@ -796,12 +914,27 @@ fn handle_grabbed<O>(idx: usize, overlay: &mut OverlayWindowData<O>, app: &mut A
 where
     O: Default,
 {
+    let other = &app.input_state.pointers[1 - idx];
+    let other_pose = other.pose;
+    let other_grab = other.interaction.grabbed.filter(|_| other.now.grab);
+    let other_free_rotation = other.now.click_modifier_right;
+
     let pointer = &mut app.input_state.pointers[idx];
     let Some(grab_data) = pointer.interaction.grabbed.as_mut() else {
         log::error!("Grabbed overlay does not exist");
         return;
     };
     let grab_anchor = grab_data.grab_anchor;
+    let other_holds = other_grab.is_some_and(|g| g.grabbed_id == grab_data.grabbed_id);
+
+    // the hand that grabbed first does the moving while both hands hold the overlay
+    if other_grab.is_some_and(|g| g.grabbed_id == grab_data.grabbed_id && g.two_handed.is_some()) {
+        if !pointer.now.grab {
+            pointer.interaction.grabbed = None;
+            log::debug!("Hand {}: let go of {}", idx, overlay.config.name);
+        }
+        return;
+    }
 
     if pointer.now.grab {
         let Some(overlay_state) = overlay.config.active_state.as_mut() else {
@@ -810,6 +943,33 @@ where
             return;
         };
 
+        if let Some(two_handed) = grab_data.two_handed {
+            if other_holds {
+                let transform = two_handed.transform(
+                    pointer.pose.translation,
+                    other_pose.translation,
+                    pointer.now.click_modifier_right || other_free_rotation,
+                );
+                if grab_anchor {
+                    app.anchor = transform;
+                } else {
+                    overlay_state.transform = transform;
+                    overlay.config.pause_movement = true;
+                    overlay.config.dirty = true;
+                }
+                return;
+            }
+
+            // the other hand let go, carry on with just this one
+            let target = if grab_anchor {
+                app.anchor
+            } else {
+                overlay_state.transform
+            };
+            grab_data.offset = pointer.pose.inverse() * target;
+            grab_data.two_handed = None;
+        }
+
         if grab_anchor {
             if pointer.now.click {
                 pointer.interaction.mode = PointerMode::Special;
@@ -857,6 +1017,26 @@ where
         }
     } else {
         // not now.grab
+        if grab_data.two_handed.is_some() && other_holds {
+            // hand the grab over to the other hand
+            pointer.interaction.grabbed = None;
+            let target = if grab_anchor {
+                Some(app.anchor)
+            } else {
+                overlay.config.active_state.as_ref().map(|s| s.transform)
+            };
+            if let Some(target) = target
+                && let Some(other_grab) = app.input_state.pointers[1 - idx]
+                    .interaction
+                    .grabbed
+                    .as_mut()
+            {
+                other_grab.offset = other_pose.inverse() * target;
+            }
+            log::debug!("Hand {}: let go of {}", idx, overlay.config.name);
+            return;
+        }
+
         pointer.interaction.grabbed = None;
         if grab_anchor {
             app.anchor_grabbed = false;
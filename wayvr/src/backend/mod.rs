@@ -1,3 +1,4 @@
+mod gesture;
 pub mod input;
 
 #[cfg(feature = "openvr")]
@@ -10,6 +11,8 @@ pub mod wayvr;
 
 pub mod set;
 
+pub mod snap;
+
 pub mod task;
 
 use thiserror::Error;
@@ -26,13 +26,14 @@ use crate::{
             manifest::{install_manifest, uninstall_manifest},
             overlay::OpenVrOverlayData,
         },
+        snap::MAX_GUIDES,
         task::{OpenVrTask, OverlayTask, TaskType},
     },
     config::{save_settings, save_state},
     graphics::{GpuFutures, init_openvr_graphics},
-    overlays::toast::Toast,
+    overlays::toast::{self, Toast},
     state::AppState,
-    subsystem::notifications::NotificationManager,
+    subsystem::{do_not_disturb, notifications::NotificationManager},
     windowing::{
         backend::{RenderResources, RenderTarget, ShouldRender},
         manager::OverlayWindowManager,
@@ -140,9 +141,13 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
     let mut lines = LinePool::new(app.gfx.clone())?;
     let pointer_lines = [lines.allocate(), lines.allocate()];
     let mut current_lines = Vec::with_capacity(2);
+    let guide_lines: Vec<_> = (0..MAX_GUIDES).map(|_| lines.allocate()).collect();
+    let mut current_guides = Vec::with_capacity(MAX_GUIDES);
 
     'main_loop: loop {
+        app.frame_stats.end_frame();
         let _ = overlay_mgr.wait_frame_sync(frame_timeout);
+        app.frame_stats.begin_frame();
 
         if !RUNNING.load(Ordering::Relaxed) {
             log::warn!("Received shutdown signal.");
@@ -207,6 +212,8 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
 
         app.dbus.tick();
         notifications.submit_pending(&mut app);
+        do_not_disturb::update(&mut app);
+        toast::update(&mut app);
 
         app.tasks.retrieve_due(&mut due_tasks);
 
@@ -266,8 +273,14 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
         playspace.update(&mut chaperone_mgr, &mut overlays, &app);
 
         current_lines.clear();
+        current_guides.clear();
 
-        let haptics = interact(&mut overlays, &mut app, &mut current_lines);
+        let haptics = interact(
+            &mut overlays,
+            &mut app,
+            &mut current_lines,
+            &mut current_guides,
+        );
         for (idx, haptics) in haptics.iter().enumerate() {
             if let Some(haptics) = haptics {
                 input_source.haptics(&mut input_mgr, idx, haptics);
@@ -282,6 +295,9 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
                 &app.input_state.hmd,
             );
         }
+        for (id, guide) in guide_lines.iter().zip(&current_guides) {
+            lines.draw_between(*id, guide.a, guide.b, 0, &app.input_state.hmd);
+        }
 
         app.hid_provider.inner.commit();
         let mut futures = GpuFutures::default();
@@ -297,6 +313,13 @@ pub fn openvr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
             let _ = sender.send_params(&overlays, &app.input_state.devices);
         }
 
+        #[cfg(feature = "osc")]
+        if let Some(ref mut receiver) = app.osc_receiver {
+            for msg in receiver.receive() {
+                crate::subsystem::osc::handle_osc_message(&mut app, msg);
+            }
+        }
+
         if let Err(e) =
             crate::ipc::events::tick_events::<OpenVrOverlayData>(&mut app, &mut overlays)
         {
@@ -18,13 +18,17 @@ use crate::{
         BackendError, XrBackend,
         input::interact,
         openxr::{lines::LinePool, overlay::OpenXrOverlayData},
+        snap::MAX_GUIDES,
         task::{OpenXrTask, OverlayTask, TaskType},
     },
     config::{save_settings, save_state},
     graphics::{GpuFutures, init_openxr_graphics},
-    overlays::{toast::Toast, watch::WATCH_NAME},
+    overlays::{
+        toast::{self, Toast},
+        watch::WATCH_NAME,
+    },
     state::AppState,
-    subsystem::notifications::NotificationManager,
+    subsystem::{do_not_disturb, notifications::NotificationManager},
     windowing::{
         backend::{RenderResources, RenderTarget, ShouldRender},
         manager::OverlayWindowManager,
@@ -78,6 +82,7 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
     let mut overlays = OverlayWindowManager::<OpenXrOverlayData>::new(&mut app, headless)?;
     let mut lines = LinePool::new(&app)?;
     let mut current_lines = Vec::with_capacity(2);
+    let mut current_guides = Vec::with_capacity(MAX_GUIDES);
 
     let mut notifications = NotificationManager::new();
     notifications.run_dbus(&mut app.dbus);
@@ -130,6 +135,9 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
         lines.allocate(&xr_state, &app)?,
         lines.allocate(&xr_state, &app)?,
     ];
+    let guide_lines = (0..MAX_GUIDES)
+        .map(|_| lines.allocate(&xr_state, &app))
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
     let watch_id = overlays.lookup(WATCH_NAME).unwrap(); // want panic
 
@@ -147,6 +155,7 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
     let mut environment_blend_mode = modes[0];
 
     'main_loop: loop {
+        app.frame_stats.end_frame();
         let cur_frame = FRAME_COUNTER.fetch_add(1, Ordering::Relaxed);
 
         if !RUNNING.load(Ordering::Relaxed) {
@@ -229,6 +238,7 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
         let xr_frame_state = frame_wait.wait()?;
         log::trace!("xrBeginFrame");
         frame_stream.begin()?;
+        app.frame_stats.begin_frame();
 
         xr_state.predicted_display_time = xr_frame_state.predicted_display_time;
         xr_state.fps = {
@@ -299,6 +309,13 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
             let _ = sender.send_params(&overlays, &app.input_state.devices);
         }
 
+        #[cfg(feature = "osc")]
+        if let Some(ref mut receiver) = app.osc_receiver {
+            for msg in receiver.receive() {
+                crate::subsystem::osc::handle_osc_message(&mut app, msg);
+            }
+        }
+
         let (_, views) = xr_state.session.locate_views(
             VIEW_TYPE,
             xr_frame_state.predicted_display_time,
@@ -316,8 +333,14 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
         overlays.values_mut().for_each(|o| o.config.tick(&mut app));
 
         current_lines.clear();
+        current_guides.clear();
 
-        let haptics = interact(&mut overlays, &mut app, &mut current_lines);
+        let haptics = interact(
+            &mut overlays,
+            &mut app,
+            &mut current_lines,
+            &mut current_guides,
+        );
         for (idx, haptics) in haptics.iter().enumerate() {
             if let Some(haptics) = haptics {
                 input_source.haptics(&xr_state, idx, haptics);
@@ -332,6 +355,9 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
                 app.input_state.hmd,
             );
         }
+        for (id, guide) in guide_lines.iter().zip(&current_guides) {
+            lines.draw_between(*id, guide.a, guide.b, 0, app.input_state.hmd);
+        }
 
         app.hid_provider.inner.commit();
 
@@ -456,6 +482,8 @@ pub fn openxr_run(show_by_default: bool, headless: bool) -> Result<(), BackendEr
 
         app.dbus.tick();
         notifications.submit_pending(&mut app);
+        do_not_disturb::update(&mut app);
+        toast::update(&mut app);
 
         app.tasks.retrieve_due(&mut due_tasks);
         while let Some(task) = due_tasks.pop_front() {
@@ -0,0 +1,284 @@
+use std::f32::consts::PI;
+
+use glam::{Vec2, Vec3A, vec2, vec3a};
+
+use crate::{
+    state::AppState,
+    windowing::{
+        OverlayID,
+        manager::OverlayWindowManager,
+        window::{OverlayCategory, OverlayWindowData, realign},
+    },
+};
+
+/// Guide lines drawn per frame at most, on top of the pointer lines.
+/// Each hand may show the arc, the angle ray and one guide per axis.
+pub const MAX_GUIDES: usize = 2 * (ARC_SEGMENTS + 3);
+
+/// Max distance to another overlay's edge or center to snap to it, in meters
+const EDGE_SNAP: f32 = 0.03;
+/// Overlays further apart than this along the grabbed overlay's normal are ignored
+const MAX_DEPTH: f32 = 0.3;
+/// Max distance to the snap cylinder, in meters
+const CYLINDER_SNAP: f32 = 0.1;
+/// Max angle to the nearest `snap_angle` step, in degrees
+const ANGLE_SNAP: f32 = 2.0;
+/// Width of the arc drawn along the snap cylinder, in radians
+const ARC_SPAN: f32 = PI / 12.0;
+const ARC_SEGMENTS: usize = 3;
+/// The angle guide starts this far from the HMD, in meters
+const RAY_START: f32 = 0.3;
+
+/// Left edge, center, right edge (or bottom, center, top)
+const SIDES: [i8; 3] = [-1, 0, 1];
+
+/// Shown while a grabbed overlay is snapped to something.
+pub struct GuideLine {
+    pub a: Vec3A,
+    pub b: Vec3A,
+}
+
+/// Where an overlay's quad is in the world. `right` and `up` are unit vectors.
+struct Quad {
+    center: Vec3A,
+    right: Vec3A,
+    up: Vec3A,
+    half: Vec2,
+}
+
+impl Quad {
+    fn new<O>(overlay: &mut OverlayWindowData<O>) -> Option<Self> {
+        let (transform, size) = overlay.quad()?;
+        Some(Self {
+            center: transform.translation,
+            right: transform.matrix3.x_axis.normalize_or_zero(),
+            up: transform.matrix3.y_axis.normalize_or_zero(),
+            half: size * 0.5,
+        })
+    }
+
+    fn normal(&self) -> Vec3A {
+        self.right.cross(self.up)
+    }
+
+    /// Half of the other quad's size along our axes
+    fn projected_half(&self, other: &Self) -> Vec2 {
+        vec2(
+            other.half.x * other.right.dot(self.right).abs()
+                + other.half.y * other.up.dot(self.right).abs(),
+            other.half.x * other.right.dot(self.up).abs()
+                + other.half.y * other.up.dot(self.up).abs(),
+        )
+    }
+}
+
+/// Closest edge or center alignment along one axis of the grabbed quad.
+#[derive(Clone, Copy)]
+struct Alignment {
+    /// How far the grabbed quad needs to move
+    delta: f32,
+    /// Position of the line both quads are aligned on
+    at: f32,
+    /// Extent of the other quad along the other axis, for drawing the guide
+    other_min: f32,
+    other_max: f32,
+}
+
+fn find_alignment(
+    own_half: f32,
+    other_center: f32,
+    other_half: f32,
+    cross_center: f32,
+    cross_half: f32,
+    best: &mut Option<Alignment>,
+) {
+    for own in SIDES {
+        for theirs in SIDES {
+            // same edges, both centers, or opposite edges to sit side by side
+            if own != theirs && own * theirs >= 0 {
+                continue;
+            }
+            let at = other_center + f32::from(theirs) * other_half;
+            let delta = at - f32::from(own) * own_half;
+            if delta.abs() < EDGE_SNAP && best.is_none_or(|b| delta.abs() < b.delta.abs()) {
+                *best = Some(Alignment {
+                    delta,
+                    at,
+                    other_min: cross_center - cross_half,
+                    other_max: cross_center + cross_half,
+                });
+            }
+        }
+    }
+}
+
+/// Snaps the overlay grabbed by this hand to the cylinder and angle steps around the HMD,
+/// then to the edges and centers of other visible overlays. Only done in edit mode.
+pub fn snap_grabbed<O>(
+    idx: usize,
+    overlays: &mut OverlayWindowManager<O>,
+    app: &AppState,
+    guides: &mut Vec<GuideLine>,
+) where
+    O: Default,
+{
+    if !app.session.config.snap_overlays || !overlays.get_edit_mode() {
+        return;
+    }
+
+    let pointer = &app.input_state.pointers[idx];
+    let other = &app.input_state.pointers[1 - idx];
+    let Some(grab) = pointer
+        .interaction
+        .grabbed
+        .filter(|g| pointer.now.grab && !g.grab_anchor)
+    else {
+        return;
+    };
+    // two-handed grabs place the overlay freely
+    if other
+        .interaction
+        .grabbed
+        .is_some_and(|g| g.grabbed_id == grab.grabbed_id)
+    {
+        return;
+    }
+    let free_rotation = pointer.now.click_modifier_right;
+
+    snap_around_hmd(grab.grabbed_id, overlays, app, free_rotation, guides);
+    snap_to_overlays(grab.grabbed_id, overlays, guides);
+}
+
+fn snap_around_hmd<O>(
+    id: OverlayID,
+    overlays: &mut OverlayWindowManager<O>,
+    app: &AppState,
+    free_rotation: bool,
+    guides: &mut Vec<GuideLine>,
+) where
+    O: Default,
+{
+    let config = &app.session.config;
+    let hmd = &app.input_state.hmd;
+    let Some(state) = overlays
+        .mut_by_id(id)
+        .and_then(|o| o.config.active_state.as_mut())
+    else {
+        return;
+    };
+
+    let pos = state.transform.translation;
+    let rel = pos - hmd.translation;
+    let mut dist = vec2(rel.x, rel.z).length();
+    if dist < f32::EPSILON {
+        return;
+    }
+    let mut yaw = rel.x.atan2(rel.z);
+    let mut on_cylinder = false;
+    let mut on_step = false;
+
+    if let Some(radius) = config.snap_cylinder_radius
+        && (dist - radius).abs() < CYLINDER_SNAP
+    {
+        dist = radius;
+        on_cylinder = true;
+    }
+
+    if config.snap_angle > f32::EPSILON {
+        let step = config.snap_angle.to_radians();
+        let snapped = (yaw / step).round() * step;
+        if (snapped - yaw).abs() < ANGLE_SNAP.to_radians() {
+            yaw = snapped;
+            on_step = true;
+        }
+    }
+
+    if !on_cylinder && !on_step {
+        return;
+    }
+
+    let around =
+        |yaw: f32, dist: f32| hmd.translation + vec3a(yaw.sin() * dist, rel.y, yaw.cos() * dist);
+    state.transform.translation = around(yaw, dist);
+    if !free_rotation {
+        realign(&mut state.transform, hmd);
+    }
+
+    if on_cylinder {
+        let start = yaw - ARC_SPAN * 0.5;
+        let seg = ARC_SPAN / ARC_SEGMENTS as f32;
+        for i in 0..ARC_SEGMENTS {
+            guides.push(GuideLine {
+                a: around(start + seg * i as f32, dist),
+                b: around(start + seg * (i + 1) as f32, dist),
+            });
+        }
+    }
+    if on_step {
+        guides.push(GuideLine {
+            a: around(yaw, RAY_START.min(dist)),
+            b: state.transform.translation,
+        });
+    }
+}
+
+fn snap_to_overlays<O>(
+    id: OverlayID,
+    overlays: &mut OverlayWindowManager<O>,
+    guides: &mut Vec<GuideLine>,
+) where
+    O: Default,
+{
+    let others: Vec<Quad> = overlays
+        .iter_mut()
+        .filter(|(other_id, o)| *other_id != id && o.config.category != OverlayCategory::Internal)
+        .filter_map(|(_, o)| Quad::new(o))
+        .collect();
+
+    let Some(overlay) = overlays.mut_by_id(id) else {
+        return;
+    };
+    let Some(quad) = Quad::new(overlay) else {
+        return;
+    };
+
+    let mut best_x: Option<Alignment> = None;
+    let mut best_y: Option<Alignment> = None;
+    for other in &others {
+        let offset = other.center - quad.center;
+        if offset.dot(quad.normal()).abs() > MAX_DEPTH {
+            continue;
+        }
+        let x = offset.dot(quad.right);
+        let y = offset.dot(quad.up);
+        let half = quad.projected_half(other);
+        find_alignment(quad.half.x, x, half.x, y, half.y, &mut best_x);
+        find_alignment(quad.half.y, y, half.y, x, half.x, &mut best_y);
+    }
+
+    if best_x.is_none() && best_y.is_none() {
+        return;
+    }
+
+    let dx = best_x.map_or(0.0, |a| a.delta);
+    let dy = best_y.map_or(0.0, |a| a.delta);
+    let Some(state) = overlay.config.active_state.as_mut() else {
+        return;
+    };
+    state.transform.translation += quad.right * dx + quad.up * dy;
+
+    // guides span both quads, along the shared edge
+    let point = |x: f32, y: f32| quad.center + quad.right * x + quad.up * y;
+    if let Some(a) = best_x {
+        guides.push(GuideLine {
+            a: point(a.at, a.other_min.min(dy - quad.half.y)),
+            b: point(a.at, a.other_max.max(dy + quad.half.y)),
+        });
+    }
+    if let Some(a) = best_y {
+        guides.push(GuideLine {
+            a: point(a.other_min.min(dx - quad.half.x), a.at),
+            b: point(a.other_max.max(dx + quad.half.x), a.at),
+        });
+    }
+}
@@ -1,11 +1,15 @@
 use std::{
     cmp,
     collections::{BinaryHeap, VecDeque},
-    sync::atomic::{self, AtomicUsize},
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    },
     time::Instant,
 };
 
 use serde::Deserialize;
+use wayvr_ipc::packet_client::WlxScreenArrangement;
 
 use crate::{
     backend::input,
@@ -98,10 +102,13 @@ pub enum OverlayTask {
     ToggleOverlay(OverlaySelector, ToggleMode),
     ResetOverlay(OverlaySelector),
     DeleteActiveSet,
+    SaveProfile(Arc<str>),
+    LoadProfile(Arc<str>),
     ToggleEditMode,
     ToggleDashboard,
     ShowHide,
     CleanupMirrors,
+    ArrangeScreens(WlxScreenArrangement),
     SettingsChanged,
     KeyboardChanged,
     Modify(OverlaySelector, Box<ModifyOverlayTask>),
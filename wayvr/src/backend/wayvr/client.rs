@@ -1,9 +1,9 @@
-use std::{io::Read, os::unix::net::UnixStream, path::PathBuf, sync::Arc};
+use std::{collections::HashSet, io::Read, os::unix::net::UnixStream, path::PathBuf, sync::Arc};
 
 use anyhow::Context;
 use smithay::{
     backend::input::Keycode,
-    input::{keyboard::KeyboardHandle, pointer::PointerHandle},
+    input::{Seat, keyboard::KeyboardHandle, pointer::PointerHandle},
     reexports::wayland_server,
     utils::SerialCounter,
 };
@@ -14,6 +14,7 @@ use crate::backend::wayvr::{ExternalProcessRequest, WayVRTask};
 use super::{
     ProcessWayVREnv,
     comp::{self, ClientState},
+    ime::{ImeKey, InternalIme},
     process,
 };
 
@@ -24,6 +25,7 @@ pub struct WayVRClient {
 
 pub struct WayVRCompositor {
     pub state: comp::Application,
+    pub seat: Seat<comp::Application>,
     pub seat_keyboard: KeyboardHandle<comp::Application>,
     pub seat_pointer: PointerHandle<comp::Application>,
     pub serial_counter: SerialCounter,
@@ -35,6 +37,11 @@ pub struct WayVRCompositor {
     toplevel_surf_count: u32, // for logging purposes
 
     pub clients: Vec<WayVRClient>,
+
+    ime: Option<InternalIme>,
+    ime_unavailable: bool,
+    /// Keys taken by the input method, their release is not sent to the app either
+    ime_keys: HashSet<u32>,
 }
 
 fn get_wayvr_env_from_pid(pid: i32) -> anyhow::Result<ProcessWayVREnv> {
@@ -66,6 +73,7 @@ impl WayVRCompositor {
     pub fn new(
         state: comp::Application,
         display: wayland_server::Display<comp::Application>,
+        seat: Seat<comp::Application>,
         seat_keyboard: KeyboardHandle<comp::Application>,
         seat_pointer: PointerHandle<comp::Application>,
     ) -> anyhow::Result<Self> {
@@ -74,6 +82,7 @@ impl WayVRCompositor {
         Ok(Self {
             state,
             display,
+            seat,
             seat_keyboard,
             seat_pointer,
             listener,
@@ -81,6 +90,9 @@ impl WayVRCompositor {
             serial_counter: SerialCounter::new(),
             clients: Vec::new(),
             toplevel_surf_count: 0,
+            ime: None,
+            ime_unavailable: false,
+            ime_keys: HashSet::new(),
         })
     }
 
@@ -102,6 +114,33 @@ impl WayVRCompositor {
         });
     }
 
+    /// Starts or stops the input method that composes text typed on the keyboard overlay
+    pub fn set_ime_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            // disconnecting lets the apps know that it's gone
+            self.ime = None;
+            self.ime_keys.clear();
+            return;
+        }
+        if self.ime.is_some() || self.ime_unavailable {
+            return;
+        }
+
+        let started = InternalIme::new().and_then(|(ime, stream)| {
+            self.display
+                .handle()
+                .insert_client(stream, Arc::new(ClientState::internal_ime()))?;
+            Ok(ime)
+        });
+        match started {
+            Ok(ime) => self.ime = Some(ime),
+            Err(e) => {
+                log::error!("Could not start the input method: {e:?}");
+                self.ime_unavailable = true;
+            }
+        }
+    }
+
     pub fn cleanup_handles(&mut self) {
         self.state.cleanup();
     }
@@ -171,8 +210,14 @@ impl WayVRCompositor {
             log::error!("accept_connections failed: {e}");
         }
 
+        if let Some(ime) = self.ime.as_ref() {
+            ime.flush();
+        }
         self.display.dispatch_clients(&mut self.state)?;
         self.display.flush_clients()?;
+        if let Some(ime) = self.ime.as_mut() {
+            ime.dispatch();
+        }
 
         let surf_count = self.state.xdg_shell.toplevel_surfaces().len() as u32;
         if surf_count != self.toplevel_surf_count {
@@ -189,15 +234,50 @@ impl WayVRCompositor {
         } else {
             smithay::backend::input::KeyState::Released
         };
+        let keycode = Keycode::new(virtual_key);
+
+        let ime = self.ime.as_ref().filter(|ime| ime.is_active());
+        let ime_active = ime.is_some();
+        let composing = ime.is_some_and(InternalIme::is_composing);
 
-        self.seat_keyboard.input::<(), _>(
+        let (ime_key, mods_changed) = self.seat_keyboard.input_intercept(
             &mut self.state,
-            Keycode::new(virtual_key),
+            keycode,
             state,
-            self.serial_counter.next_serial(),
-            0,
-            |_, _, _| smithay::input::keyboard::FilterResult::Forward,
+            |_, mods, keysym| {
+                if ime_active && down {
+                    ImeKey::from_keysym(keysym.modified_sym(), mods, composing)
+                } else {
+                    None
+                }
+            },
         );
+
+        let mut forward = down || !self.ime_keys.remove(&virtual_key);
+        if let Some(ime_key) = ime_key
+            && let Some(ime) = self.ime.as_mut()
+        {
+            forward = ime.apply(ime_key);
+            if !forward {
+                self.ime_keys.insert(virtual_key);
+            }
+            // the app gets the composed text before the key that ended composing
+            ime.flush();
+            if let Err(e) = self.display.dispatch_clients(&mut self.state) {
+                log::warn!("dispatch_clients failed: {e:?}");
+            }
+        }
+
+        if forward {
+            self.seat_keyboard.input_forward(
+                &mut self.state,
+                keycode,
+                state,
+                self.serial_counter.next_serial(),
+                0,
+                mods_changed,
+            );
+        }
     }
 
     pub fn set_keymap(&mut self, keymap: &xkb::Keymap) -> anyhow::Result<()> {
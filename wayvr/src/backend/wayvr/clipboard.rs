@@ -0,0 +1,496 @@
+use std::{
+    io::{Read, Write},
+    os::fd::{AsFd, BorrowedFd, OwnedFd},
+    sync::{Arc, Mutex, mpsc},
+};
+
+use anyhow::Context;
+use smithay::{
+    input::Seat,
+    reexports::wayland_server::DisplayHandle,
+    wayland::selection::data_device::{
+        request_data_device_client_selection, set_data_device_selection,
+    },
+};
+use wlx_capture::wayland::{
+    protocols_wlr::data_control::v1::client::{
+        zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+        zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+        zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+        zwlr_data_control_source_v1::{self, ZwlrDataControlSourceV1},
+    },
+    wayland_client::{
+        Connection, Dispatch, EventQueue, Proxy, QueueHandle, event_created_child,
+        globals::{GlobalListContents, registry_queue_init},
+        protocol::{wl_registry::WlRegistry, wl_seat::WlSeat},
+    },
+    wayland_protocols::ext::data_control::v1::client::{
+        ext_data_control_device_v1::{self, ExtDataControlDeviceV1},
+        ext_data_control_manager_v1::ExtDataControlManagerV1,
+        ext_data_control_offer_v1::{self, ExtDataControlOfferV1},
+        ext_data_control_source_v1::{self, ExtDataControlSourceV1},
+    },
+};
+
+use wlx_common::config::GeneralConfig;
+
+use super::comp::Application;
+
+/// Offered along with our own selections on the desktop, so that we don't copy them back.
+const MARKER_MIME: &str = "application/x-wayvr-clipboard";
+
+/// Picks what to copy out of the offered MIME types, using `clipboard_mime_groups` from the config.
+/// Anything outside of those groups (rich text, file lists, app-specific formats) is not synced.
+/// Returns the MIME type to request and the group it belongs to.
+fn pick_mime(offered: &[String], config: &GeneralConfig) -> Option<(String, Arc<[String]>)> {
+    if offered.iter().any(|m| m == MARKER_MIME) {
+        return None;
+    }
+    config.clipboard_mime_groups.iter().find_map(|group| {
+        group
+            .iter()
+            .find(|m| offered.contains(m))
+            .map(|m| (m.clone(), group.as_slice().into()))
+    })
+}
+
+/// Larger clipboard contents are not synced, in bytes
+fn max_size(config: &GeneralConfig) -> usize {
+    config.clipboard_max_size_mb as usize * 1024 * 1024
+}
+
+enum Transfer {
+    FromHost(Arc<[String]>, Arc<[u8]>),
+    FromWayVR(Arc<[String]>, Arc<[u8]>),
+}
+
+/// Reads a clipboard transfer to the end in the background, up to `max_size` bytes.
+fn read_in_background(
+    reader: impl Read + Send + 'static,
+    tx: mpsc::Sender<Transfer>,
+    make: fn(Arc<[String]>, Arc<[u8]>) -> Transfer,
+    group: Arc<[String]>,
+    max_size: usize,
+) {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Err(e) = reader.take(max_size as u64 + 1).read_to_end(&mut buf) {
+            log::warn!("Clipboard: could not read selection: {e:?}");
+            return;
+        }
+        if buf.len() > max_size {
+            log::info!("Clipboard: not syncing, contents are over {max_size} bytes");
+            return;
+        }
+        let _ = tx.send(make(group, buf.into()));
+    });
+}
+
+fn write_in_background(fd: OwnedFd, data: Arc<[u8]>) {
+    std::thread::spawn(move || {
+        if let Err(e) = std::fs::File::from(fd).write_all(&data) {
+            log::warn!("Clipboard: could not write selection: {e:?}");
+        }
+    });
+}
+
+enum Manager {
+    Ext(ExtDataControlManagerV1),
+    Wlr(ZwlrDataControlManagerV1),
+}
+
+enum Device {
+    Ext(ExtDataControlDeviceV1),
+    Wlr(ZwlrDataControlDeviceV1),
+}
+
+enum Offer {
+    Ext(ExtDataControlOfferV1),
+    Wlr(ZwlrDataControlOfferV1),
+}
+
+impl Offer {
+    fn mime_types(&self) -> Vec<String> {
+        let data = match self {
+            Self::Ext(o) => o.data::<OfferMimes>(),
+            Self::Wlr(o) => o.data::<OfferMimes>(),
+        };
+        data.map(|d| d.0.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    fn receive(&self, mime_type: String, fd: BorrowedFd<'_>) {
+        match self {
+            Self::Ext(o) => o.receive(mime_type, fd),
+            Self::Wlr(o) => o.receive(mime_type, fd),
+        }
+    }
+
+    fn destroy(&self) {
+        match self {
+            Self::Ext(o) => o.destroy(),
+            Self::Wlr(o) => o.destroy(),
+        }
+    }
+}
+
+enum Source {
+    Ext(ExtDataControlSourceV1),
+    Wlr(ZwlrDataControlSourceV1),
+}
+
+impl Source {
+    fn destroy(&self) {
+        match self {
+            Self::Ext(s) => s.destroy(),
+            Self::Wlr(s) => s.destroy(),
+        }
+    }
+}
+
+/// MIME types announced for an offer, before it becomes the selection
+#[derive(Default)]
+struct OfferMimes(Mutex<Vec<String>>);
+
+/// Our side of the desktop's data-control connection
+struct HostState {
+    seat: WlSeat,
+    manager: Manager,
+    device: Device,
+    /// Current selection on the desktop
+    offer: Option<Offer>,
+    /// Our own selection on the desktop
+    source: Option<Source>,
+    /// Set on `selection` events, taken on the next tick
+    selection_changed: bool,
+    /// The compositor took the device away, e.g. because of a new seat
+    finished: bool,
+}
+
+impl HostState {
+    fn set_selection(&mut self, qh: &QueueHandle<Self>, group: &[String], data: Arc<[u8]>) {
+        let mimes = group.iter().map(String::as_str).chain([MARKER_MIME]);
+        let source = match (&self.manager, &self.device) {
+            (Manager::Ext(manager), Device::Ext(device)) => {
+                let source = manager.create_data_source(qh, data);
+                mimes.for_each(|m| source.offer(m.to_string()));
+                device.set_selection(Some(&source));
+                Source::Ext(source)
+            }
+            (Manager::Wlr(manager), Device::Wlr(device)) => {
+                let source = manager.create_data_source(qh, data);
+                mimes.for_each(|m| source.offer(m.to_string()));
+                device.set_selection(Some(&source));
+                Source::Wlr(source)
+            }
+            _ => unreachable!("device is created from the manager"),
+        };
+        if let Some(old) = self.source.replace(source) {
+            old.destroy();
+        }
+    }
+}
+
+impl Drop for HostState {
+    fn drop(&mut self) {
+        if let Some(offer) = self.offer.take() {
+            offer.destroy();
+        }
+        if let Some(source) = self.source.take() {
+            source.destroy();
+        }
+        match &self.device {
+            Device::Ext(d) => d.destroy(),
+            Device::Wlr(d) => d.destroy(),
+        }
+        match &self.manager {
+            Manager::Ext(m) => m.destroy(),
+            Manager::Wlr(m) => m.destroy(),
+        }
+        if self.seat.version() >= 5 {
+            self.seat.release();
+        }
+    }
+}
+
+/// Two-way sync between the clipboard of the desktop and the one of WayVR apps.
+/// Needs `ext-data-control-v1` or `wlr-data-control-unstable-v1` on the desktop.
+pub struct ClipboardBridge {
+    connection: Connection,
+    queue: EventQueue<HostState>,
+    host: HostState,
+    tx: mpsc::Sender<Transfer>,
+    rx: mpsc::Receiver<Transfer>,
+    /// Last contents synced in either direction, to avoid copying them back and forth
+    last: Option<Arc<[u8]>>,
+}
+
+impl ClipboardBridge {
+    pub fn new() -> anyhow::Result<Self> {
+        let connection = Connection::connect_to_env()?;
+        let (globals, queue) = registry_queue_init::<HostState>(&connection)?;
+        let qh = queue.handle();
+
+        let seat: WlSeat = globals
+            .bind(&qh, 1..=9, ())
+            .context(WlSeat::interface().name)?;
+
+        let (manager, device) =
+            if let Ok(manager) = globals.bind::<ExtDataControlManagerV1, _, _>(&qh, 1..=1, ()) {
+                let device = manager.get_data_device(&seat, &qh, ());
+                (Manager::Ext(manager), Device::Ext(device))
+            } else {
+                let manager = globals
+                    .bind::<ZwlrDataControlManagerV1, _, _>(&qh, 1..=2, ())
+                    .context("Neither ext-data-control nor wlr-data-control is supported")?;
+                let device = manager.get_data_device(&seat, &qh, ());
+                (Manager::Wlr(manager), Device::Wlr(device))
+            };
+
+        let (tx, rx) = mpsc::channel();
+
+        Ok(Self {
+            connection,
+            queue,
+            host: HostState {
+                seat,
+                manager,
+                device,
+                offer: None,
+                source: None,
+                selection_changed: false,
+                finished: false,
+            },
+            tx,
+            rx,
+            last: None,
+        })
+    }
+
+    /// A WayVR app set the clipboard.
+    pub fn wayvr_selection(
+        &self,
+        seat: &Seat<Application>,
+        mime_types: &[String],
+        config: &GeneralConfig,
+    ) {
+        let Some((mime, group)) = pick_mime(mime_types, config) else {
+            log::debug!("Clipboard: nothing to sync in {mime_types:?}");
+            return;
+        };
+        let (reader, writer) = match std::io::pipe() {
+            Ok(p) => p,
+            Err(e) => {
+                log::warn!("Clipboard: could not create pipe: {e:?}");
+                return;
+            }
+        };
+        if let Err(e) = request_data_device_client_selection(seat, mime, OwnedFd::from(writer)) {
+            log::warn!("Clipboard: could not request selection: {e:?}");
+            return;
+        }
+        read_in_background(
+            reader,
+            self.tx.clone(),
+            Transfer::FromWayVR,
+            group,
+            max_size(config),
+        );
+    }
+
+    /// Exchanges clipboard contents that are ready. Returns `false` once the bridge stopped working.
+    pub fn tick(
+        &mut self,
+        dh: &DisplayHandle,
+        seat: &Seat<Application>,
+        config: &GeneralConfig,
+    ) -> bool {
+        if let Some(reader) = self.queue.prepare_read() {
+            // nothing to read is not an error, the socket is non-blocking
+            let _ = reader.read();
+        }
+        if let Err(e) = self.queue.dispatch_pending(&mut self.host) {
+            log::warn!("Clipboard: desktop connection failed: {e:?}");
+            return false;
+        }
+        if self.host.finished {
+            return false;
+        }
+
+        if std::mem::take(&mut self.host.selection_changed)
+            && let Some(offer) = self.host.offer.as_ref()
+            && let Some((mime, group)) = pick_mime(&offer.mime_types(), config)
+        {
+            match std::io::pipe() {
+                Ok((reader, writer)) => {
+                    offer.receive(mime, writer.as_fd());
+                    read_in_background(
+                        reader,
+                        self.tx.clone(),
+                        Transfer::FromHost,
+                        group,
+                        max_size(config),
+                    );
+                }
+                Err(e) => log::warn!("Clipboard: could not create pipe: {e:?}"),
+            }
+        }
+
+        let qh = self.queue.handle();
+        while let Ok(transfer) = self.rx.try_recv() {
+            let (Transfer::FromHost(_, data) | Transfer::FromWayVR(_, data)) = &transfer;
+            if data.is_empty() || self.last.as_ref() == Some(data) {
+                continue;
+            }
+            self.last = Some(data.clone());
+
+            match transfer {
+                Transfer::FromHost(group, data) => {
+                    log::debug!("Clipboard: {} bytes from the desktop", data.len());
+                    let mimes = group.to_vec();
+                    set_data_device_selection(dh, seat, mimes, data);
+                }
+                Transfer::FromWayVR(group, data) => {
+                    log::debug!("Clipboard: {} bytes from WayVR", data.len());
+                    self.host.set_selection(&qh, &group, data);
+                }
+            }
+        }
+
+        if let Err(e) = self.connection.flush() {
+            log::warn!("Clipboard: desktop connection failed: {e:?}");
+            return false;
+        }
+        true
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for HostState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegistry,
+        _event: <WlRegistry as Proxy>::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for HostState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+/// Both protocols have the same requests and events, only the types differ.
+macro_rules! impl_data_control {
+    ($variant:ident, $manager:ty, $device_mod:ident, $device:ty, $offer_mod:ident, $offer:ty, $source_mod:ident, $source:ty) => {
+        impl Dispatch<$manager, ()> for HostState {
+            fn event(
+                _state: &mut Self,
+                _proxy: &$manager,
+                _event: <$manager as Proxy>::Event,
+                _data: &(),
+                _conn: &Connection,
+                _qhandle: &QueueHandle<Self>,
+            ) {
+            }
+        }
+
+        impl Dispatch<$device, ()> for HostState {
+            fn event(
+                state: &mut Self,
+                _proxy: &$device,
+                event: <$device as Proxy>::Event,
+                _data: &(),
+                _conn: &Connection,
+                _qhandle: &QueueHandle<Self>,
+            ) {
+                match event {
+                    $device_mod::Event::Selection { id } => {
+                        if let Some(old) = state.offer.take() {
+                            old.destroy();
+                        }
+                        state.offer = id.map(Offer::$variant);
+                        state.selection_changed = true;
+                    }
+                    // only the clipboard is synced
+                    $device_mod::Event::PrimarySelection { id: Some(offer) } => offer.destroy(),
+                    $device_mod::Event::Finished => state.finished = true,
+                    _ => {}
+                }
+            }
+
+            event_created_child!(HostState, $device, [
+                $device_mod::EVT_DATA_OFFER_OPCODE => ($offer, OfferMimes::default()),
+            ]);
+        }
+
+        impl Dispatch<$offer, OfferMimes> for HostState {
+            fn event(
+                _state: &mut Self,
+                _proxy: &$offer,
+                event: <$offer as Proxy>::Event,
+                data: &OfferMimes,
+                _conn: &Connection,
+                _qhandle: &QueueHandle<Self>,
+            ) {
+                if let $offer_mod::Event::Offer { mime_type } = event {
+                    data.0.lock().unwrap().push(mime_type);
+                }
+            }
+        }
+
+        impl Dispatch<$source, Arc<[u8]>> for HostState {
+            fn event(
+                state: &mut Self,
+                proxy: &$source,
+                event: <$source as Proxy>::Event,
+                data: &Arc<[u8]>,
+                _conn: &Connection,
+                _qhandle: &QueueHandle<Self>,
+            ) {
+                match event {
+                    $source_mod::Event::Send { fd, .. } => write_in_background(fd, data.clone()),
+                    $source_mod::Event::Cancelled => {
+                        // something else was copied on the desktop
+                        if matches!(&state.source, Some(Source::$variant(s)) if s == proxy) {
+                            state.source = None;
+                        }
+                        proxy.destroy();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+impl_data_control!(
+    Ext,
+    ExtDataControlManagerV1,
+    ext_data_control_device_v1,
+    ExtDataControlDeviceV1,
+    ext_data_control_offer_v1,
+    ExtDataControlOfferV1,
+    ext_data_control_source_v1,
+    ExtDataControlSourceV1
+);
+
+impl_data_control!(
+    Wlr,
+    ZwlrDataControlManagerV1,
+    zwlr_data_control_device_v1,
+    ZwlrDataControlDeviceV1,
+    zwlr_data_control_offer_v1,
+    ZwlrDataControlOfferV1,
+    zwlr_data_control_source_v1,
+    ZwlrDataControlSourceV1
+);
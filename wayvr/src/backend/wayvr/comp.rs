@@ -1,21 +1,31 @@
 use anyhow::Context;
 use smithay::backend::allocator::dmabuf::Dmabuf;
 use smithay::backend::renderer::{BufferType, buffer_type};
-use smithay::desktop::{PopupKind, PopupManager};
+use smithay::desktop::{PopupKind, PopupManager, find_popup_root_surface};
+use smithay::input::pointer::PointerHandle;
 use smithay::input::{Seat, SeatHandler, SeatState};
 use smithay::reexports::rustix::fs::{OFlags, fcntl_setfl};
 use smithay::reexports::wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1;
 use smithay::reexports::wayland_protocols::xdg::shell::server::xdg_toplevel;
 use smithay::reexports::wayland_protocols_misc::server_decoration::server::org_kde_kwin_server_decoration;
 use smithay::reexports::wayland_server::Resource;
-use smithay::reexports::wayland_server::protocol::{wl_buffer, wl_output, wl_seat};
+use smithay::reexports::wayland_server::protocol::{wl_buffer, wl_data_source, wl_output, wl_seat};
 use smithay::reexports::wayland_server::{self, DisplayHandle};
 use smithay::wayland::buffer::BufferHandler;
 use smithay::wayland::dmabuf::{
     DmabufFeedback, DmabufGlobal, DmabufHandler, DmabufState, ImportNotifier, get_dmabuf,
 };
-use smithay::wayland::fractional_scale::with_fractional_scale;
+use smithay::wayland::fractional_scale::{
+    FractionalScaleHandler, FractionalScaleManagerState, with_fractional_scale,
+};
+use smithay::wayland::input_method::{
+    InputMethodHandler, InputMethodManagerState, PopupSurface as InputMethodPopupSurface,
+};
 use smithay::wayland::output::OutputHandler;
+use smithay::wayland::pointer_constraints::{
+    PointerConstraintsHandler, PointerConstraintsState, with_pointer_constraint,
+};
+use smithay::wayland::relative_pointer::RelativePointerManagerState;
 use smithay::wayland::selection::{
     ext_data_control as selection_ext,
     primary_selection::{PrimarySelectionHandler, PrimarySelectionState, set_primary_focus},
@@ -25,10 +35,14 @@ use smithay::wayland::shell::kde::decoration::{KdeDecorationHandler, KdeDecorati
 use smithay::wayland::shell::xdg::decoration::{XdgDecorationHandler, XdgDecorationState};
 use smithay::wayland::shm::{ShmHandler, ShmState, with_buffer_contents};
 use smithay::wayland::single_pixel_buffer::get_single_pixel_buffer;
+use smithay::wayland::text_input::TextInputManagerState;
+use smithay::wayland::viewporter::ViewporterState;
 use smithay::{
     delegate_compositor, delegate_data_control, delegate_data_device, delegate_dmabuf,
-    delegate_ext_data_control, delegate_kde_decoration, delegate_output,
-    delegate_primary_selection, delegate_seat, delegate_shm, delegate_single_pixel_buffer,
+    delegate_ext_data_control, delegate_fractional_scale, delegate_input_method_manager,
+    delegate_kde_decoration, delegate_output, delegate_pointer_constraints,
+    delegate_primary_selection, delegate_relative_pointer, delegate_seat, delegate_shm,
+    delegate_single_pixel_buffer, delegate_text_input_manager, delegate_viewporter,
     delegate_xdg_decoration, delegate_xdg_shell,
 };
 use std::collections::HashSet;
@@ -37,8 +51,8 @@ use std::io::Write;
 use std::os::fd::OwnedFd;
 use std::sync::{Arc, Mutex};
 
-use smithay::utils::Serial;
-use smithay::wayland::compositor::{self, BufferAssignment, SurfaceAttributes, send_surface_state};
+use smithay::utils::{Logical, Point, Rectangle, Serial};
+use smithay::wayland::compositor::{self, BufferAssignment, SurfaceAttributes};
 
 use smithay::wayland::selection::data_device::{
     ClientDndGrabHandler, DataDeviceHandler, DataDeviceState, ServerDndGrabHandler,
@@ -46,13 +60,15 @@ use smithay::wayland::selection::data_device::{
 };
 use smithay::wayland::selection::{self, SelectionHandler};
 use smithay::wayland::shell::xdg::{
-    PopupSurface, PositionerState, ToplevelSurface, XdgShellHandler, XdgShellState,
+    PopupSurface, PositionerState, SurfaceCachedState, ToplevelSurface, XdgShellHandler,
+    XdgShellState,
 };
 use wayland_server::Client;
 use wayland_server::backend::{ClientData, ClientId, DisconnectReason};
 use wayland_server::protocol::wl_surface::WlSurface;
 
 use crate::backend::wayvr::image_importer::ImageImporter;
+use crate::backend::wayvr::window::{WindowScale, send_preferred_scale};
 use crate::backend::wayvr::{SurfaceBufWithImage, time};
 use crate::ipc::event_queue::SyncEventQueue;
 
@@ -71,10 +87,18 @@ pub struct Application {
     pub wlr_data_control_state: selection_wlr::DataControlState,
     pub xdg_decoration_state: XdgDecorationState,
     pub kde_decoration_state: KdeDecorationState,
+    pub text_input_state: TextInputManagerState,
+    pub input_method_state: InputMethodManagerState,
+    pub pointer_constraints_state: PointerConstraintsState,
+    pub relative_pointer_state: RelativePointerManagerState,
+    pub fractional_scale_state: FractionalScaleManagerState,
+    pub viewporter_state: ViewporterState,
     pub wayvr_tasks: SyncEventQueue<WayVRTask>,
     pub redraw_requests: HashSet<wayland_server::backend::ObjectId>,
     pub popup_manager: PopupManager,
     pub display_handle: DisplayHandle,
+    /// Surface drawn under the laser while a client is dragging something
+    pub dnd_icon: Option<WlSurface>,
 }
 
 impl Application {
@@ -86,29 +110,32 @@ impl Application {
         self.image_importer.cleanup();
     }
 
+    /// Toplevel surface a popup belongs to, or the surface itself
+    fn root_surface(&self, surface: &WlSurface) -> WlSurface {
+        self.popup_manager
+            .find_popup(surface)
+            .and_then(|popup| find_popup_root_surface(&popup).ok())
+            .unwrap_or_else(|| surface.clone())
+    }
+
     fn popups_commit(&mut self, surface: &WlSurface) {
         self.popup_manager.commit(surface);
 
         if let Some(popup) = self.popup_manager.find_popup(surface) {
             match popup {
-                PopupKind::Xdg(ref popup) => {
-                    if !popup.is_initial_configure_sent() {
+                PopupKind::Xdg(ref xdg_popup) => {
+                    if !xdg_popup.is_initial_configure_sent() {
+                        let scale = WindowScale::of_surface(&self.root_surface(surface));
                         smithay::wayland::compositor::with_states(surface, |states| {
-                            send_surface_state(
-                                surface,
-                                states,
-                                1,
-                                smithay::utils::Transform::Normal,
-                            );
-                            with_fractional_scale(states, |fractional| {
-                                fractional.set_preferred_scale(1.0);
-                            });
+                            send_preferred_scale(surface, states, scale);
                         });
-                        popup.send_configure().expect("initial configure failed");
+                        xdg_popup
+                            .send_configure()
+                            .expect("initial configure failed");
                     }
                 }
                 PopupKind::InputMethod(_) => {
-                    // TODO?
+                    // placed next to the text cursor, nothing to configure
                 }
             }
         }
@@ -245,10 +272,27 @@ impl BufferHandler for Application {
     fn buffer_destroyed(&mut self, _buffer: &wl_buffer::WlBuffer) {}
 }
 
-impl ClientDndGrabHandler for Application {}
+// Pointer motion is always sent with the surface under the laser as the focus,
+// so the drag follows it across window overlays until the button is released.
+impl ClientDndGrabHandler for Application {
+    fn started(
+        &mut self,
+        _source: Option<wl_data_source::WlDataSource>,
+        icon: Option<WlSurface>,
+        _seat: Seat<Self>,
+    ) {
+        self.dnd_icon = icon;
+    }
+
+    fn dropped(&mut self, _target: Option<WlSurface>, _validated: bool, _seat: Seat<Self>) {
+        self.dnd_icon = None;
+    }
+}
 
 impl ServerDndGrabHandler for Application {
-    fn send(&mut self, _mime_type: String, _fd: OwnedFd, _seat: Seat<Self>) {}
+    fn send(&mut self, _mime_type: String, _fd: OwnedFd, _seat: Seat<Self>) {
+        // drags are only ever started by clients
+    }
 }
 
 impl DataDeviceHandler for Application {
@@ -282,10 +326,15 @@ impl SelectionHandler for Application {
 
     fn new_selection(
         &mut self,
-        _ty: selection::SelectionTarget,
-        _source: Option<selection::SelectionSource>,
+        ty: selection::SelectionTarget,
+        source: Option<selection::SelectionSource>,
         _seat: Seat<Self>,
     ) {
+        // passed on to the desktop, see `ClipboardBridge`
+        if let (selection::SelectionTarget::Clipboard, Some(source)) = (ty, source) {
+            self.wayvr_tasks
+                .send(WayVRTask::ClipboardChanged(source.mime_types()));
+        }
     }
 }
 
@@ -293,6 +342,17 @@ impl SelectionHandler for Application {
 pub struct ClientState {
     compositor_state: compositor::CompositorClientState,
     pub disconnected: Arc<Mutex<bool>>,
+    /// Our own input method, see `ime::InternalIme`
+    pub internal_ime: bool,
+}
+
+impl ClientState {
+    pub fn internal_ime() -> Self {
+        Self {
+            internal_ime: true,
+            ..Default::default()
+        }
+    }
 }
 
 impl ClientData for ClientState {
@@ -479,6 +539,72 @@ impl KdeDecorationHandler for Application {
     }
 }
 
+impl InputMethodHandler for Application {
+    fn new_popup(&mut self, surface: InputMethodPopupSurface) {
+        let _ = self
+            .popup_manager
+            .track_popup(PopupKind::InputMethod(surface))
+            .context("Could not track input method popup")
+            .inspect_err(|e| log::warn!("{e:?}"));
+    }
+
+    fn popup_repositioned(&mut self, _surface: InputMethodPopupSurface) {}
+
+    fn dismiss_popup(&mut self, surface: InputMethodPopupSurface) {
+        if let Some(parent) = surface.get_parent().map(|p| p.surface.clone()) {
+            let _ = PopupManager::dismiss_popup(&parent, &PopupKind::InputMethod(surface));
+        }
+    }
+
+    // Toplevels are drawn on their own overlay, so only the window geometry matters.
+    fn parent_geometry(&self, parent: &WlSurface) -> Rectangle<i32, Logical> {
+        smithay::wayland::compositor::with_states(parent, |states| {
+            states
+                .cached_state
+                .get::<SurfaceCachedState>()
+                .current()
+                .geometry
+        })
+        .unwrap_or_default()
+    }
+}
+
+impl PointerConstraintsHandler for Application {
+    fn new_constraint(&mut self, surface: &WlSurface, pointer: &PointerHandle<Self>) {
+        // otherwise activated once the pointer moves onto the surface
+        if pointer.current_focus().as_ref() == Some(surface) {
+            with_pointer_constraint(surface, pointer, |constraint| {
+                if let Some(constraint) = constraint {
+                    constraint.activate();
+                }
+            });
+        }
+    }
+
+    fn cursor_position_hint(
+        &mut self,
+        surface: &WlSurface,
+        pointer: &PointerHandle<Self>,
+        location: Point<f64, Logical>,
+    ) {
+        // windows are not offset, surface-local is the same as global
+        if with_pointer_constraint(surface, pointer, |c| c.is_some_and(|c| c.is_active())) {
+            pointer.set_location(location);
+        }
+    }
+}
+
+impl FractionalScaleHandler for Application {
+    fn new_fractional_scale(&mut self, surface: WlSurface) {
+        let scale = WindowScale::of_surface(&self.root_surface(&surface));
+        compositor::with_states(&surface, |states| {
+            with_fractional_scale(states, |fractional| {
+                fractional.set_preferred_scale(scale);
+            });
+        });
+    }
+}
+
 delegate_dmabuf!(Application);
 delegate_xdg_shell!(Application);
 delegate_compositor!(Application);
@@ -492,6 +618,12 @@ delegate_ext_data_control!(Application);
 delegate_xdg_decoration!(Application);
 delegate_kde_decoration!(Application);
 delegate_single_pixel_buffer!(Application);
+delegate_text_input_manager!(Application);
+delegate_input_method_manager!(Application);
+delegate_pointer_constraints!(Application);
+delegate_relative_pointer!(Application);
+delegate_fractional_scale!(Application);
+delegate_viewporter!(Application);
 
 const fn wl_transform_to_frame_transform(
     transform: wl_output::Transform,
@@ -0,0 +1,252 @@
+use std::os::unix::net::UnixStream;
+
+use smithay::input::keyboard::{Keysym, ModifiersState};
+use wlx_capture::wayland::{
+    protocols_misc::zwp_input_method_v2::client::{
+        zwp_input_method_manager_v2::ZwpInputMethodManagerV2,
+        zwp_input_method_v2::{self, ZwpInputMethodV2},
+    },
+    wayland_client::{
+        Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+        protocol::{
+            wl_registry::{self, WlRegistry},
+            wl_seat::WlSeat,
+        },
+    },
+};
+
+/// What a key press does while the input method is active
+#[derive(Debug, Clone, Copy)]
+pub enum ImeKey {
+    Char(char),
+    Backspace,
+    /// Inserts the composed text, followed by the given string
+    Commit(&'static str),
+    Cancel,
+    /// Inserts the composed text, then the key goes to the app as usual
+    Flush,
+}
+
+impl ImeKey {
+    /// `None` sends the key to the app without touching the composed text.
+    pub fn from_keysym(sym: Keysym, mods: &ModifiersState, composing: bool) -> Option<Self> {
+        if mods.ctrl || mods.alt || mods.logo {
+            return composing.then_some(Self::Flush);
+        }
+        match sym {
+            Keysym::BackSpace if composing => Some(Self::Backspace),
+            Keysym::Return | Keysym::KP_Enter if composing => Some(Self::Commit("")),
+            Keysym::space if composing => Some(Self::Commit(" ")),
+            Keysym::Escape if composing => Some(Self::Cancel),
+            Keysym::BackSpace
+            | Keysym::Return
+            | Keysym::KP_Enter
+            | Keysym::space
+            | Keysym::Escape => None,
+            _ => match sym.key_char() {
+                Some(c) if !c.is_control() => Some(Self::Char(c)),
+                _ => composing.then_some(Self::Flush),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct ImeState {
+    seat: Option<WlSeat>,
+    manager: Option<ZwpInputMethodManagerV2>,
+    input_method: Option<ZwpInputMethodV2>,
+    pending_active: bool,
+    active: bool,
+    /// Number of `done` events so far, sent back with each commit
+    serial: u32,
+}
+
+/// Input method built into WayVR, so that keys from the keyboard overlay are composed into
+/// words shown in the focused text field until committed. It connects to our own compositor
+/// over a socket pair, and is the only client allowed to bind `zwp_input_method_v2`.
+pub struct InternalIme {
+    connection: Connection,
+    queue: EventQueue<ImeState>,
+    state: ImeState,
+    /// Text typed so far, not yet committed to the app
+    preedit: String,
+}
+
+impl InternalIme {
+    /// Returns the compositor's end of the connection, to be inserted as a client.
+    pub fn new() -> anyhow::Result<(Self, UnixStream)> {
+        let (ime_end, compositor_end) = UnixStream::pair()?;
+        // both ends live on the same thread, reading must never wait for the other one
+        ime_end.set_nonblocking(true)?;
+        let connection = Connection::from_socket(ime_end)?;
+        let queue = connection.new_event_queue();
+        connection.display().get_registry(&queue.handle(), ());
+
+        Ok((
+            Self {
+                connection,
+                queue,
+                state: ImeState::default(),
+                preedit: String::new(),
+            },
+            compositor_end,
+        ))
+    }
+
+    /// Sends our requests, before the compositor dispatches its clients.
+    pub fn flush(&self) {
+        if let Err(e) = self.connection.flush() {
+            log::warn!("Input method: could not send requests: {e:?}");
+        }
+    }
+
+    /// Handles the compositor's events, after it flushed its clients.
+    pub fn dispatch(&mut self) {
+        if let Some(reader) = self.queue.prepare_read() {
+            // nothing to read is not an error, the socket is non-blocking
+            let _ = reader.read();
+        }
+        if let Err(e) = self.queue.dispatch_pending(&mut self.state) {
+            log::warn!("Input method: connection failed: {e:?}");
+        }
+        if !self.state.active {
+            self.preedit.clear();
+        }
+    }
+
+    /// Whether the focused app has a text field that accepts input
+    pub const fn is_active(&self) -> bool {
+        self.state.active && self.state.input_method.is_some()
+    }
+
+    pub const fn is_composing(&self) -> bool {
+        !self.preedit.is_empty()
+    }
+
+    /// Returns whether the key should still be sent to the app.
+    pub fn apply(&mut self, key: ImeKey) -> bool {
+        match key {
+            ImeKey::Char(c) => {
+                self.preedit.push(c);
+                self.send_preedit();
+            }
+            ImeKey::Backspace => {
+                self.preedit.pop();
+                self.send_preedit();
+            }
+            ImeKey::Commit(suffix) => self.commit(suffix),
+            ImeKey::Cancel => {
+                self.preedit.clear();
+                self.send_preedit();
+            }
+            ImeKey::Flush => {
+                self.commit("");
+                return true;
+            }
+        }
+        false
+    }
+
+    fn send_preedit(&self) {
+        let Some(input_method) = self.state.input_method.as_ref() else {
+            return;
+        };
+        // cursor at the end, in bytes
+        let cursor = self.preedit.len() as i32;
+        input_method.set_preedit_string(self.preedit.clone(), cursor, cursor);
+        input_method.commit(self.state.serial);
+    }
+
+    fn commit(&mut self, suffix: &str) {
+        let Some(input_method) = self.state.input_method.as_ref() else {
+            return;
+        };
+        let mut text = std::mem::take(&mut self.preedit);
+        text.push_str(suffix);
+        // the preedit is cleared by committing without one
+        input_method.commit_string(text);
+        input_method.commit(self.state.serial);
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for ImeState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qhandle: &QueueHandle<Self>,
+    ) {
+        let wl_registry::Event::Global {
+            name, interface, ..
+        } = event
+        else {
+            return;
+        };
+        if interface == WlSeat::interface().name && state.seat.is_none() {
+            state.seat = Some(registry.bind(name, 1, qhandle, ()));
+        } else if interface == ZwpInputMethodManagerV2::interface().name {
+            state.manager = Some(registry.bind(name, 1, qhandle, ()));
+        }
+
+        if state.input_method.is_none()
+            && let (Some(seat), Some(manager)) = (&state.seat, &state.manager)
+        {
+            state.input_method = Some(manager.get_input_method(seat, qhandle, ()));
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for ImeState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpInputMethodManagerV2, ()> for ImeState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpInputMethodManagerV2,
+        _event: <ZwpInputMethodManagerV2 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpInputMethodV2, ()> for ImeState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwpInputMethodV2,
+        event: zwp_input_method_v2::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwp_input_method_v2::Event::Activate => state.pending_active = true,
+            zwp_input_method_v2::Event::Deactivate => state.pending_active = false,
+            zwp_input_method_v2::Event::Done => {
+                state.serial += 1;
+                state.active = state.pending_active;
+            }
+            zwp_input_method_v2::Event::Unavailable => {
+                log::warn!("Input method: another input method is already bound");
+                state.active = false;
+                if let Some(input_method) = state.input_method.take() {
+                    input_method.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+}
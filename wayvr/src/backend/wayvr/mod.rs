@@ -1,7 +1,9 @@
 pub mod client;
+mod clipboard;
 mod comp;
 mod handle;
 mod image_importer;
+mod ime;
 pub mod process;
 mod time;
 pub mod window;
@@ -22,6 +24,10 @@ use smithay::{
     wayland::{
         compositor::{self, SurfaceData, with_states},
         dmabuf::{DmabufFeedbackBuilder, DmabufState},
+        fractional_scale::FractionalScaleManagerState,
+        input_method::InputMethodManagerState,
+        pointer_constraints::PointerConstraintsState,
+        relative_pointer::RelativePointerManagerState,
         selection::{
             data_device::DataDeviceState, ext_data_control as selection_ext,
             primary_selection::PrimarySelectionState, wlr_data_control as selection_wlr,
@@ -34,6 +40,8 @@ use smithay::{
             },
         },
         shm::ShmState,
+        text_input::TextInputManagerState,
+        viewporter::{ViewportCachedState, ViewporterState},
     },
 };
 use std::{
@@ -48,7 +56,7 @@ use vulkano::image::view::ImageView;
 use wayvr_ipc::{packet_client::PositionMode, packet_server};
 use wgui::gfx::WGfx;
 use wlx_capture::frame::Transform;
-use wlx_common::desktop_finder::DesktopFinder;
+use wlx_common::{config::GeneralConfig, desktop_finder::DesktopFinder};
 use xkbcommon::xkb;
 
 use crate::{
@@ -60,7 +68,7 @@ use crate::{
             window::Window,
         },
     },
-    graphics::WGfxExtras,
+    graphics::{ExtentExt, WGfxExtras},
     ipc::{event_queue::SyncEventQueue, ipc_server, signal::WayVRSignal},
     overlays::wayvr::create_wl_window_overlay,
     state::AppState,
@@ -103,6 +111,8 @@ pub enum WayVRTask {
     NewExternalProcess(ExternalProcessRequest),
     ProcessTerminationRequest(process::ProcessHandle, KillSignal),
     CloseWindowRequest(window::WindowHandle),
+    /// An app set the clipboard, with these MIME types
+    ClipboardChanged(Vec<String>),
 }
 
 pub enum BlitMethod {
@@ -132,6 +142,9 @@ pub struct WvrServerState {
     mouse_freeze: Instant,
     window_to_overlay: HashMap<window::WindowHandle, OverlayID>,
     overlay_to_window: SecondaryMap<OverlayID, window::WindowHandle>,
+    clipboard: Option<clipboard::ClipboardBridge>,
+    /// The desktop can't share its clipboard, don't try again
+    clipboard_unavailable: bool,
 }
 
 pub enum MouseIndex {
@@ -182,6 +195,23 @@ impl WvrServerState {
         let kde_decoration_state =
             KdeDecorationState::new::<Application>(&dh, kde_decoration::Mode::Server);
 
+        // Lets the keyboard overlay compose text for the focused window, see `ime::InternalIme`.
+        // An input method can read and change the text of every app, so only ours may bind it.
+        let text_input_state = TextInputManagerState::new::<Application>(&dh);
+        let input_method_state = InputMethodManagerState::new::<Application, _>(&dh, |client| {
+            client
+                .get_data::<comp::ClientState>()
+                .is_some_and(|data| data.internal_ime)
+        });
+
+        // For games and 3D tools that grab the mouse
+        let pointer_constraints_state = PointerConstraintsState::new::<Application>(&dh);
+        let relative_pointer_state = RelativePointerManagerState::new::<Application>(&dh);
+
+        // Apps render at the scale picked for their window, see `Window::set_scale`
+        let fractional_scale_state = FractionalScaleManagerState::new::<Application>(&dh);
+        let viewporter_state = ViewporterState::new::<Application>(&dh);
+
         let dummy_milli_hz = 60000; /* refresh rate in millihertz */
 
         let output = Output::new(
@@ -257,17 +287,30 @@ impl WvrServerState {
             ext_data_control_state,
             xdg_decoration_state,
             kde_decoration_state,
+            text_input_state,
+            input_method_state,
+            pointer_constraints_state,
+            relative_pointer_state,
+            fractional_scale_state,
+            viewporter_state,
             wayvr_tasks: tasks.clone(),
             redraw_requests: HashSet::new(),
             dmabuf_state,
             popup_manager: PopupManager::default(),
+            dnd_icon: None,
         };
 
         let time_start = get_millis();
 
         Ok(Self {
             time_start,
-            manager: client::WayVRCompositor::new(state, display, seat_keyboard, seat_pointer)?,
+            manager: client::WayVRCompositor::new(
+                state,
+                display,
+                seat,
+                seat_keyboard,
+                seat_pointer,
+            )?,
             processes: ProcessVec::new(),
             wm: window::WindowManager::new(),
             ticks: 0,
@@ -277,6 +320,8 @@ impl WvrServerState {
             mouse_freeze: Instant::now(),
             window_to_overlay: HashMap::new(),
             overlay_to_window: SecondaryMap::new(),
+            clipboard: None,
+            clipboard_unavailable: false,
         })
     }
 
@@ -531,9 +576,22 @@ impl WvrServerState {
                         );
                     }
                 }
+                WayVRTask::ClipboardChanged(mime_types) => {
+                    if let Some(clipboard) = wvr_server.clipboard.as_ref() {
+                        clipboard.wayvr_selection(
+                            &wvr_server.manager.seat,
+                            &mime_types,
+                            &app.session.config,
+                        );
+                    }
+                }
             }
         }
 
+        wvr_server.tick_clipboard(&app.session.config);
+        wvr_server
+            .manager
+            .set_ime_enabled(app.session.config.keyboard_ime);
         wvr_server.manager.tick_wayland(&mut wvr_server.processes)?;
 
         if wvr_server.ticks.is_multiple_of(200) {
@@ -546,6 +604,38 @@ impl WvrServerState {
         Ok(tasks)
     }
 
+    fn tick_clipboard(&mut self, config: &GeneralConfig) {
+        if !config.clipboard_sync {
+            self.clipboard = None;
+            return;
+        }
+
+        if self.clipboard.is_none() && !self.clipboard_unavailable {
+            match clipboard::ClipboardBridge::new() {
+                Ok(clipboard) => {
+                    log::info!("Syncing the clipboard with the desktop");
+                    self.clipboard = Some(clipboard);
+                }
+                Err(e) => {
+                    log::warn!("Clipboard can't be synced with the desktop: {e:?}");
+                    self.clipboard_unavailable = true;
+                }
+            }
+        }
+
+        if let Some(clipboard) = self.clipboard.as_mut()
+            && !clipboard.tick(
+                &self.manager.state.display_handle,
+                &self.manager.seat,
+                config,
+            )
+        {
+            log::warn!("Clipboard sync with the desktop stopped");
+            self.clipboard = None;
+            self.clipboard_unavailable = true;
+        }
+    }
+
     pub fn terminate_process(
         &mut self,
         process_handle: process::ProcessHandle,
@@ -560,6 +650,15 @@ impl WvrServerState {
             .send(WayVRTask::CloseWindowRequest(window_handle));
     }
 
+    /// Raises or lowers the window's scale by `steps` of `window::SCALE_STEP`
+    pub fn step_window_scale(&mut self, window_handle: window::WindowHandle, steps: i32) {
+        if let Some(window) = self.wm.windows.get_mut(&window_handle) {
+            let scale = window.scale() + f64::from(steps) * window::SCALE_STEP;
+            window.set_scale(scale);
+            log::debug!("{window_handle:?}: scale {}", window.scale());
+        }
+    }
+
     pub fn overlay_added(&mut self, oid: OverlayID, window: window::WindowHandle) {
         self.overlay_to_window.insert(oid, window);
         self.window_to_overlay.insert(window, oid);
@@ -598,8 +697,14 @@ impl WvrServerState {
         if self.mouse_freeze > Instant::now() {
             return;
         }
+        let last = self
+            .wm
+            .mouse
+            .as_ref()
+            .filter(|m| m.hover_window == handle)
+            .map(|m| (m.x, m.y));
         if let Some(window) = self.wm.windows.get_mut(&handle) {
-            window.send_mouse_move(&mut self.manager, x, y);
+            window.send_mouse_move(&mut self.manager, x, y, last);
         } else {
             return;
         }
@@ -629,6 +734,17 @@ impl WvrServerState {
     }
 
     pub fn send_mouse_scroll(&mut self, delta: WheelDelta) {
+        // with a locked pointer, the thumbstick looks around instead of scrolling
+        if let Some(window) = self
+            .wm
+            .mouse
+            .as_ref()
+            .and_then(|m| self.wm.windows.get(&m.hover_window))
+            && window.is_pointer_locked(&self.manager)
+        {
+            window.send_stick_look(&mut self.manager, delta);
+            return;
+        }
         Window::send_mouse_scroll(&mut self.manager, delta);
     }
 
@@ -786,6 +902,18 @@ impl SurfaceBufWithImage {
         }
     }
 
+    /// Size of the surface in the app's coordinates, which pointer positions are in.
+    /// Smaller than the image when the app renders at a scale.
+    pub fn logical_size(&self, surface_data: &SurfaceData) -> [u32; 2] {
+        let mut viewport = surface_data.cached_state.get::<ViewportCachedState>();
+        if let Some(size) = viewport.current().size() {
+            return [size.w.max(1) as u32, size.h.max(1) as u32];
+        }
+        let scale = self.scale.max(1) as u32;
+        let [width, height] = self.image.extent_u32arr();
+        [(width / scale).max(1), (height / scale).max(1)]
+    }
+
     pub fn get_from_surface(surface_data: &SurfaceData) -> Option<Self> {
         surface_data
             .data_map
@@ -1,19 +1,59 @@
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 use smithay::backend::input::{AxisRelativeDirection, AxisSource};
 use smithay::{
+    desktop::PopupManager,
     input,
-    utils::{Logical, Point},
-    wayland::shell::xdg::ToplevelSurface,
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    utils::{Logical, Point, Transform},
+    wayland::{
+        compositor::{SurfaceData, send_surface_state, with_states},
+        fractional_scale::with_fractional_scale,
+        pointer_constraints::{PointerConstraint, with_pointer_constraint},
+        shell::xdg::ToplevelSurface,
+    },
 };
 use wayvr_ipc::packet_server;
 
 use crate::{
-    backend::wayvr::{client::WayVRCompositor, process},
+    backend::wayvr::{client::WayVRCompositor, process, time},
     gen_id,
     subsystem::hid::WheelDelta,
 };
 
+/// Pixels of relative motion per unit of thumbstick scroll, while the pointer is locked
+const STICK_LOOK_SPEED: f32 = 8.0;
+
+/// Range of the scale that can be picked from the window bar
+const MIN_SCALE: f64 = 1.0;
+const MAX_SCALE: f64 = 3.0;
+/// Multiple of 1/120, so `wp_fractional_scale_v1` can represent it exactly
+pub const SCALE_STEP: f64 = 0.25;
+
+/// Scale picked for a window, kept on its toplevel surface so that
+/// popups and `wp_fractional_scale_v1` objects created later follow it.
+pub struct WindowScale(Cell<f64>);
+
+impl WindowScale {
+    pub fn of_surface(surface: &WlSurface) -> f64 {
+        with_states(surface, |states| {
+            states
+                .data_map
+                .get::<Self>()
+                .map_or(1.0, |scale| scale.0.get())
+        })
+    }
+}
+
+/// Tells the app which scale to render `surface` at.
+/// Apps without fractional scaling get the next integer scale.
+pub fn send_preferred_scale(surface: &WlSurface, states: &SurfaceData, scale: f64) {
+    send_surface_state(surface, states, scale.ceil() as i32, Transform::Normal);
+    with_fractional_scale(states, |fractional| {
+        fractional.set_preferred_scale(scale);
+    });
+}
+
 #[derive(Debug)]
 pub struct Window {
     pub size_x: u32,
@@ -45,21 +85,120 @@ impl Window {
         self.size_y = size_y;
     }
 
-    pub(super) fn send_mouse_move(&self, manager: &mut WayVRCompositor, x: u32, y: u32) {
+    pub fn scale(&self) -> f64 {
+        WindowScale::of_surface(self.toplevel.wl_surface())
+    }
+
+    /// The window keeps its logical size, so its overlay stays the same size in VR
+    /// while the app draws more pixels into it.
+    pub fn set_scale(&mut self, scale: f64) {
+        let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        let surface = self.toplevel.wl_surface();
+        with_states(surface, |states| {
+            states
+                .data_map
+                .insert_if_missing(|| WindowScale(Cell::new(1.0)));
+            if let Some(window_scale) = states.data_map.get::<WindowScale>() {
+                window_scale.0.set(scale);
+            }
+        });
+
+        let popups = PopupManager::popups_for_surface(surface)
+            .map(|(popup, _)| popup.wl_surface().clone())
+            .collect::<Vec<_>>();
+        for surface in std::iter::once(surface).chain(&popups) {
+            with_states(surface, |states| {
+                send_preferred_scale(surface, states, scale);
+            });
+        }
+        self.toplevel.send_configure();
+    }
+
+    /// `last` is where the pointer was on this window before, to send relative motion.
+    pub(super) fn send_mouse_move(
+        &self,
+        manager: &mut WayVRCompositor,
+        x: u32,
+        y: u32,
+        last: Option<(u32, u32)>,
+    ) {
         let surf = self.toplevel.wl_surface().clone();
         let point = Point::<f64, Logical>::from((f64::from(x as i32), f64::from(y as i32)));
 
-        manager.seat_pointer.motion(
+        let pointer = manager.seat_pointer.clone();
+        let move_pointer = with_pointer_constraint(&surf, &pointer, |constraint| {
+            let Some(constraint) = constraint else {
+                return true;
+            };
+            if !constraint.is_active() && pointer.current_focus().as_ref() == Some(&surf) {
+                constraint.activate();
+            }
+            if !constraint.is_active() {
+                return true;
+            }
+            match &*constraint {
+                PointerConstraint::Locked(_) => false,
+                PointerConstraint::Confined(_) => constraint
+                    .region()
+                    .is_none_or(|region| region.contains(point.to_i32_round())),
+            }
+        });
+
+        if let Some((last_x, last_y)) = last {
+            let delta = Point::from((
+                f64::from(x as i32 - last_x as i32),
+                f64::from(y as i32 - last_y as i32),
+            ));
+            Self::send_relative_motion(manager, &surf, delta);
+        }
+
+        if move_pointer {
+            manager.seat_pointer.motion(
+                &mut manager.state,
+                Some((surf, Point::from((0.0, 0.0)))),
+                &input::pointer::MotionEvent {
+                    serial: manager.serial_counter.next_serial(),
+                    time: 0,
+                    location: point,
+                },
+            );
+        }
+
+        manager.seat_pointer.frame(&mut manager.state);
+    }
+
+    /// Whether the app grabbed the pointer for mouse-look, see `zwp_locked_pointer_v1`
+    pub(super) fn is_pointer_locked(&self, manager: &WayVRCompositor) -> bool {
+        with_pointer_constraint(self.toplevel.wl_surface(), &manager.seat_pointer, |c| {
+            c.is_some_and(|c| c.is_active() && matches!(&*c, PointerConstraint::Locked(_)))
+        })
+    }
+
+    /// Moves the view of a mouse-look app with the thumbstick
+    pub(super) fn send_stick_look(&self, manager: &mut WayVRCompositor, delta: WheelDelta) {
+        let surf = self.toplevel.wl_surface().clone();
+        let delta = Point::from((
+            f64::from(delta.x * STICK_LOOK_SPEED),
+            f64::from(-delta.y * STICK_LOOK_SPEED),
+        ));
+        Self::send_relative_motion(manager, &surf, delta);
+        manager.seat_pointer.frame(&mut manager.state);
+    }
+
+    fn send_relative_motion(
+        manager: &mut WayVRCompositor,
+        surf: &WlSurface,
+        delta: Point<f64, Logical>,
+    ) {
+        manager.seat_pointer.relative_motion(
             &mut manager.state,
-            Some((surf, Point::from((0.0, 0.0)))),
-            &input::pointer::MotionEvent {
-                serial: manager.serial_counter.next_serial(),
-                time: 0,
-                location: point,
+            Some((surf.clone(), Point::from((0.0, 0.0)))),
+            &input::pointer::RelativeMotionEvent {
+                delta,
+                delta_unaccel: delta,
+                utime: time::get_millis() * 1000,
             },
         );
-
-        manager.seat_pointer.frame(&mut manager.state);
     }
 
     const fn get_mouse_index_number(index: super::MouseIndex) -> u32 {
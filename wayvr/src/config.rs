@@ -1,15 +1,17 @@
+use anyhow::Context;
 use config::{Config, File};
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use wayvr_ipc::packet_client::WvrProcessLaunchParams;
 use wlx_common::{
     astr_containers::AStrMap,
     config::{
-        AltModifier, CaptureMethod, GeneralConfig, HandsfreePointer, SerializedWindowSet,
-        SerializedWindowStates,
+        AltModifier, CaptureMethod, GeneralConfig, HandsfreePointer, NotificationRule,
+        SerializedWindowSet, SerializedWindowStates,
     },
     config_io,
+    keyboard_layout::KeyboardLayout,
     locale::Language,
     overlays::BackendAttribValue,
 };
@@ -48,6 +50,31 @@ where
     panic!("No usable config found.");
 }
 
+/// Parses the built-in version of a config file, ignoring the user's.
+pub fn load_default_yaml<T>(config_type: ConfigType) -> T
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_yaml::from_str(FALLBACKS[config_type as usize]).unwrap() // want panic
+}
+
+/// Writes `keyboard.yaml` to the config directory. An existing one is kept as `keyboard.yaml.bak`.
+pub fn save_keyboard_layout(layout: &KeyboardLayout) -> anyhow::Result<()> {
+    let file_name = FILES[ConfigType::Keyboard as usize];
+    let path = config_io::get_config_file_path(file_name);
+    let yaml = serde_yaml::to_string(layout)?;
+
+    std::fs::create_dir_all(config_io::get_config_root())?;
+    if path.exists() {
+        std::fs::copy(&path, path.with_extension("yaml.bak"))
+            .with_context(|| format!("Could not back up {}", path.display()))?;
+    }
+    std::fs::write(&path, yaml).with_context(|| format!("Could not write {}", path.display()))?;
+
+    log::info!("Saved keyboard layout to {}", path.display());
+    Ok(())
+}
+
 pub fn load_config_with_conf_d<ConfigData>(
     root_config_filename: &str,
     ctype: config_io::ConfigRoot,
@@ -115,6 +142,8 @@ pub struct AutoSettings {
     pub long_press_duration: f32,
     pub notifications_enabled: bool,
     pub notifications_sound_enabled: bool,
+    pub notification_rules: Vec<NotificationRule>,
+    pub do_not_disturb_apps: Vec<String>,
     pub keyboard_sound_enabled: bool,
     pub upright_screen_fix: bool,
     pub double_cursor_fix: bool,
@@ -123,6 +152,10 @@ pub struct AutoSettings {
     pub xr_click_sensitivity: f32,
     pub xr_click_sensitivity_release: f32,
     pub allow_sliding: bool,
+    pub snap_overlays: bool,
+    pub snap_angle: f32,
+    pub snap_cylinder_radius: Option<f32>,
+    pub screen_arc_radius: f32,
     pub focus_follows_mouse_mode: bool,
     pub left_handed_mouse: bool,
     pub block_game_input: bool,
@@ -139,6 +172,8 @@ pub struct AutoSettings {
     pub hide_username: bool,
     pub opaque_background: bool,
     pub xwayland_by_default: bool,
+    pub clipboard_sync: bool,
+    pub keyboard_ime: bool,
     pub context_menu_hold_and_release: bool,
     pub capture_method: CaptureMethod,
     pub keyboard_middle_click_mode: AltModifier,
@@ -165,6 +200,8 @@ pub fn save_settings(config: &GeneralConfig) -> anyhow::Result<()> {
         long_press_duration: config.long_press_duration,
         notifications_enabled: config.notifications_enabled,
         notifications_sound_enabled: config.notifications_sound_enabled,
+        notification_rules: config.notification_rules.clone(),
+        do_not_disturb_apps: config.do_not_disturb_apps.clone(),
         keyboard_sound_enabled: config.keyboard_sound_enabled,
         upright_screen_fix: config.upright_screen_fix,
         double_cursor_fix: config.double_cursor_fix,
@@ -173,6 +210,10 @@ pub fn save_settings(config: &GeneralConfig) -> anyhow::Result<()> {
         xr_click_sensitivity: config.xr_click_sensitivity,
         xr_click_sensitivity_release: config.xr_click_sensitivity_release,
         allow_sliding: config.allow_sliding,
+        snap_overlays: config.snap_overlays,
+        snap_angle: config.snap_angle,
+        snap_cylinder_radius: config.snap_cylinder_radius,
+        screen_arc_radius: config.screen_arc_radius,
         focus_follows_mouse_mode: config.focus_follows_mouse_mode,
         left_handed_mouse: config.left_handed_mouse,
         block_game_input: config.block_game_input,
@@ -189,6 +230,8 @@ pub fn save_settings(config: &GeneralConfig) -> anyhow::Result<()> {
         hide_username: config.hide_username,
         opaque_background: config.opaque_background,
         xwayland_by_default: config.xwayland_by_default,
+        clipboard_sync: config.clipboard_sync,
+        keyboard_ime: config.keyboard_ime,
         context_menu_hold_and_release: config.context_menu_hold_and_release,
         capture_method: config.capture_method,
         keyboard_middle_click_mode: config.keyboard_middle_click_mode,
@@ -234,3 +277,138 @@ pub fn save_state(config: &GeneralConfig) -> anyhow::Result<()> {
     log::info!("State was saved successfully.");
     Ok(())
 }
+
+// Named layout profiles. These live outside of conf.d, so that they are not merged into the config.
+
+#[derive(Serialize, Deserialize)]
+pub struct LayoutProfile {
+    pub sets: Vec<SerializedWindowSet>,
+    #[serde(default)]
+    pub global_set: SerializedWindowStates,
+    #[serde(default)]
+    pub attribs: AStrMap<Vec<BackendAttribValue>>,
+}
+
+const PROFILE_EXT: &str = "json5";
+
+fn get_profiles_path() -> PathBuf {
+    config_io::get_config_root().join("profiles")
+}
+
+fn get_profile_path(name: &str) -> anyhow::Result<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        anyhow::bail!("Invalid profile name: '{name}'");
+    }
+    Ok(get_profiles_path().join(format!("{name}.{PROFILE_EXT}")))
+}
+
+fn get_profile_export_path(config: &GeneralConfig) -> anyhow::Result<PathBuf> {
+    let home = PathBuf::from(std::env::var("HOME").context("HOME is not set")?);
+    Ok(match &config.profile_export_path {
+        Some(path) => home.join(path),
+        None => home,
+    })
+}
+
+pub fn list_profiles() -> Vec<String> {
+    list_profiles_in(&get_profiles_path())
+}
+
+fn list_profiles_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut names: Vec<_> = entries
+        .filter_map(|r| {
+            let path = r.ok()?.path();
+            if path.extension()? != PROFILE_EXT {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+
+    names.sort();
+    names
+}
+
+pub fn save_profile(name: &str, config: &GeneralConfig) -> anyhow::Result<()> {
+    let path = get_profile_path(name)?;
+    let profile = LayoutProfile {
+        sets: config.sets.clone(),
+        global_set: config.global_set.clone(),
+        attribs: config.attribs.clone(),
+    };
+
+    let json = serde_json::to_string_pretty(&profile).unwrap(); // want panic
+    std::fs::create_dir_all(get_profiles_path())?;
+    std::fs::write(path, json)?;
+
+    log::info!("Saved layout profile '{name}'.");
+    Ok(())
+}
+
+pub fn load_profile(name: &str) -> anyhow::Result<LayoutProfile> {
+    let path = get_profile_path(name)?;
+    let data = std::fs::read_to_string(&path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+    parse_profile(&data)
+}
+
+fn parse_profile(data: &str) -> anyhow::Result<LayoutProfile> {
+    let profile: LayoutProfile = serde_json5::from_str(data).context("Invalid layout profile")?;
+    if profile.sets.is_empty() {
+        anyhow::bail!("Layout profile must contain at least one set");
+    }
+    Ok(profile)
+}
+
+/// Returns the profile as-is, so that it can be written to a file and imported elsewhere.
+pub fn export_profile(name: &str) -> anyhow::Result<String> {
+    let path = get_profile_path(name)?;
+    std::fs::read_to_string(&path).with_context(|| format!("Could not read {}", path.display()))
+}
+
+/// Validates and stores a profile that was exported with `export_profile`.
+pub fn import_profile(name: &str, data: &str) -> anyhow::Result<()> {
+    let path = get_profile_path(name)?;
+    parse_profile(data)?;
+    std::fs::create_dir_all(get_profiles_path())?;
+    std::fs::write(path, data)?;
+
+    log::info!("Imported layout profile '{name}'.");
+    Ok(())
+}
+
+/// Copies a profile to `profile_export_path` and returns the path of the copy.
+pub fn export_profile_file(name: &str, config: &GeneralConfig) -> anyhow::Result<PathBuf> {
+    let data = export_profile(name)?;
+    let dir = get_profile_export_path(config)?;
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("{name}.{PROFILE_EXT}"));
+    std::fs::write(&path, data).with_context(|| format!("Could not write {}", path.display()))?;
+
+    log::info!("Exported layout profile '{name}' to {}.", path.display());
+    Ok(path)
+}
+
+/// Profiles in `profile_export_path` that can be imported with `import_profile_file`.
+pub fn list_profile_files(config: &GeneralConfig) -> Vec<String> {
+    get_profile_export_path(config)
+        .map(|dir| list_profiles_in(&dir))
+        .unwrap_or_default()
+}
+
+pub fn import_profile_file(name: &str, config: &GeneralConfig) -> anyhow::Result<()> {
+    let path = get_profile_export_path(config)?.join(format!("{name}.{PROFILE_EXT}"));
+    let data = std::fs::read_to_string(&path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+    import_profile(name, &data)
+}
+
+pub fn delete_profile(name: &str) -> anyhow::Result<()> {
+    let path = get_profile_path(name)?;
+    std::fs::remove_file(&path).with_context(|| format!("Could not remove {}", path.display()))
+}
@@ -0,0 +1,317 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use wgui::{
+    event::{CallbackData, CallbackDataCommon, EventCallback, EventListenerKind},
+    layout::Layout,
+    parser::ParserState,
+    widget::EventResult,
+};
+
+use crate::{
+    gui::panel::{
+        button::{CommandContext, build_command},
+        log_cmd_invalid_arg, log_cmd_missing_arg,
+    },
+    state::AppState,
+};
+
+const TAG: &str = "Button";
+
+const KEYWORDS: [&str; 4] = ["::If", "::Else", "::EndIf", "::Delay"];
+
+/// Evaluated against `AppState::overlay_status` and `AppState::variables`
+/// at the time the `::If` is reached.
+enum Condition {
+    /// `visible <overlay>`
+    Visible(Arc<str>),
+    /// `set <index>` or `set none`
+    ActiveSet(Option<usize>),
+    /// `edit_mode`
+    EditMode,
+    /// `var <key>`: the variable is set and not `""`, `"0"` or `"false"`
+    VarTruthy(Arc<str>),
+    /// `var <key> <value>`
+    VarEquals(Arc<str>, Arc<str>),
+    /// `eq <a> <b>`, already evaluated since variables are resolved at load time
+    Const(bool),
+    /// `not <condition>`
+    Not(Box<Self>),
+}
+
+impl Condition {
+    fn parse(tokens: &[&str]) -> Result<Self, String> {
+        match tokens {
+            ["not", rest @ ..] => Ok(Self::Not(Box::new(Self::parse(rest)?))),
+            ["visible", overlay @ ..] if !overlay.is_empty() => {
+                Ok(Self::Visible(overlay.join(" ").into()))
+            }
+            ["set", "none"] => Ok(Self::ActiveSet(None)),
+            ["set", idx] => idx
+                .parse()
+                .map(|idx| Self::ActiveSet(Some(idx)))
+                .map_err(|_| format!("expected integer, found \"{idx}\"")),
+            ["edit_mode"] => Ok(Self::EditMode),
+            ["var", key] => Ok(Self::VarTruthy((*key).into())),
+            ["var", key, value @ ..] => Ok(Self::VarEquals((*key).into(), value.join(" ").into())),
+            ["eq", a, b] => Ok(Self::Const(a == b)),
+            _ => Err(format!("unknown condition \"{}\"", tokens.join(" "))),
+        }
+    }
+
+    fn eval(&self, app: &AppState) -> bool {
+        let status = &app.overlay_status;
+        match self {
+            Self::Visible(name) => status.visible.contains(name),
+            Self::ActiveSet(set) => status.current_set == *set,
+            Self::EditMode => status.edit_mode,
+            Self::VarTruthy(key) => app.variables.is_truthy(key),
+            Self::VarEquals(key, value) => app.variables.get(key) == Some(value.as_ref()),
+            Self::Const(val) => *val,
+            Self::Not(inner) => !inner.eval(app),
+        }
+    }
+}
+
+enum Step<S> {
+    Command(EventCallback<AppState, S>),
+    Delay(Duration),
+    If {
+        condition: Condition,
+        then: Rc<[Step<S>]>,
+        otherwise: Rc<[Step<S>]>,
+    },
+}
+
+/// Steps left to run, innermost block last.
+type Stack<S> = Vec<(Rc<[Step<S>]>, usize)>;
+
+struct Script<S> {
+    steps: Rc<[Step<S>]>,
+    /// Continuations of scripts that are waiting on a `::Delay`.
+    pending: RefCell<Vec<(Instant, Stack<S>)>>,
+}
+
+impl<S> Script<S> {
+    fn run(
+        &self,
+        mut stack: Stack<S>,
+        common: &mut CallbackDataCommon,
+        data: &mut CallbackData,
+        app: &mut AppState,
+        state: &mut S,
+    ) -> anyhow::Result<()> {
+        while let Some((steps, idx)) = stack.last_mut() {
+            let Some(step) = steps.get(*idx) else {
+                stack.pop();
+                continue;
+            };
+            *idx += 1;
+
+            match step {
+                Step::Command(callback) => {
+                    callback(common, data, app, state)?;
+                }
+                Step::Delay(delay) => {
+                    let until = Instant::now() + *delay;
+                    self.pending.borrow_mut().push((until, stack));
+                    return Ok(());
+                }
+                Step::If {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if condition.eval(app) { then } else { otherwise };
+                    let branch = branch.clone();
+                    stack.push((branch, 0));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_tick(
+        &self,
+        common: &mut CallbackDataCommon,
+        data: &mut CallbackData,
+        app: &mut AppState,
+        state: &mut S,
+    ) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let due: Vec<_> = {
+            let mut pending = self.pending.borrow_mut();
+            let (due, waiting) = pending.drain(..).partition(|(until, _)| *until <= now);
+            *pending = waiting;
+            due
+        };
+
+        for (_, stack) in due {
+            self.run(stack, common, data, app, state)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a button action attribute, which is either a single `::Command`
+/// or a script of `;`-separated statements using `::If`, `::Else`, `::EndIf` and `::Delay`.
+pub(super) fn parse_action<S: 'static>(
+    layout: &mut Layout,
+    ctx: &CommandContext,
+    action: &str,
+) -> Option<EventCallback<AppState, S>> {
+    let statements = split_statements(ctx.parser_state, action);
+
+    // plain commands don't need a script
+    if let [tokens] = statements.as_slice()
+        && !KEYWORDS.contains(&tokens[0].as_str())
+    {
+        return build_command(
+            layout,
+            ctx,
+            &tokens[0],
+            tokens[1..].iter().map(String::as_str),
+        );
+    }
+
+    let (steps, _) = parse_block(layout, ctx, &mut statements.into_iter(), &[])?;
+    let script = Rc::new(Script {
+        steps: steps.into(),
+        pending: RefCell::new(vec![]),
+    });
+
+    layout.add_event_listener::<AppState, S>(
+        ctx.attribs.widget_id,
+        EventListenerKind::InternalStateChange,
+        Box::new({
+            let script = script.clone();
+            move |common, data, app, state| {
+                script.on_tick(common, data, app, state)?;
+                Ok(EventResult::Pass)
+            }
+        }),
+    );
+
+    Some(Box::new(move |common, data, app, state| {
+        let stack = vec![(script.steps.clone(), 0)];
+        script.run(stack, common, data, app, state)?;
+        Ok(EventResult::Consumed)
+    }))
+}
+
+/// Parses statements until one of `end` is reached, which is returned alongside the steps.
+fn parse_block<S: 'static>(
+    layout: &mut Layout,
+    ctx: &CommandContext,
+    statements: &mut std::vec::IntoIter<Vec<String>>,
+    end: &[&str],
+) -> Option<(Vec<Step<S>>, Option<String>)> {
+    let mut steps = vec![];
+
+    while let Some(tokens) = statements.next() {
+        let command = tokens[0].as_str();
+        let args: Vec<&str> = tokens[1..].iter().map(String::as_str).collect();
+
+        if end.contains(&command) {
+            return Some((steps, Some(tokens[0].clone())));
+        }
+
+        match command {
+            "::Delay" => {
+                let arg = args.first().copied().unwrap_or_default();
+                let Ok(millis) = arg.parse() else {
+                    let msg = format!("expected milliseconds, found \"{arg}\"");
+                    log_cmd_invalid_arg(ctx.parser_state, TAG, ctx.name, command, &msg);
+                    return None;
+                };
+                steps.push(Step::Delay(Duration::from_millis(millis)));
+            }
+            "::If" => {
+                if args.is_empty() {
+                    log_cmd_missing_arg(ctx.parser_state, TAG, ctx.name, command);
+                    return None;
+                }
+
+                let condition = match Condition::parse(&args) {
+                    Ok(condition) => condition,
+                    Err(msg) => {
+                        log_cmd_invalid_arg(ctx.parser_state, TAG, ctx.name, command, &msg);
+                        return None;
+                    }
+                };
+
+                let (then, mut terminator) =
+                    parse_block(layout, ctx, statements, &["::Else", "::EndIf"])?;
+
+                let otherwise = if terminator.as_deref() == Some("::Else") {
+                    let (otherwise, t) = parse_block(layout, ctx, statements, &["::EndIf"])?;
+                    terminator = t;
+                    otherwise
+                } else {
+                    vec![]
+                };
+
+                if terminator.is_none() {
+                    let msg = "missing ::EndIf";
+                    log_cmd_invalid_arg(ctx.parser_state, TAG, ctx.name, command, msg);
+                    return None;
+                }
+
+                steps.push(Step::If {
+                    condition,
+                    then: then.into(),
+                    otherwise: otherwise.into(),
+                });
+            }
+            "::Else" | "::EndIf" => {
+                let msg = "not inside ::If";
+                log_cmd_invalid_arg(ctx.parser_state, TAG, ctx.name, command, msg);
+                return None;
+            }
+            _ => steps.push(Step::Command(build_command(
+                layout,
+                ctx,
+                command,
+                args.into_iter(),
+            )?)),
+        }
+    }
+
+    Some((steps, None))
+}
+
+/// Splits the action into whitespace-separated tokens per statement, resolving `~variables`.
+/// Only a `;` followed by a `::Command` starts a new statement, so that `::ShellExec` may still
+/// contain `;`.
+fn split_statements(parser_state: &ParserState, action: &str) -> Vec<Vec<String>> {
+    let mut statements: Vec<String> = vec![];
+    for part in action.split(';') {
+        match statements.last_mut() {
+            Some(last) if !part.trim_start().starts_with("::") => {
+                last.push(';');
+                last.push_str(part);
+            }
+            _ => statements.push(part.to_string()),
+        }
+    }
+
+    statements
+        .iter()
+        .map(|statement| {
+            statement
+                .split_whitespace()
+                .map(|token| {
+                    token
+                        .strip_prefix('~')
+                        .and_then(|var| parser_state.data.var_map.get(var))
+                        .map_or_else(|| token.to_string(), ToString::to_string)
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|tokens| !tokens.is_empty())
+        .collect()
+}
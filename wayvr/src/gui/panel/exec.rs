@@ -0,0 +1,125 @@
+use std::{
+    io::{BufRead, BufReader},
+    os::unix::process::CommandExt,
+    process::{Child, Command, Stdio},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+const BACKOFF_MIN: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// A long-running `sh -c` process whose stdout is consumed line by line.
+/// Restarted with exponential backoff whenever it exits, like waybar's custom modules.
+/// The process and everything it spawned is killed when this is dropped.
+pub(super) struct ExecProcess {
+    exec: String,
+    child: Option<Child>,
+    lines: Option<mpsc::Receiver<String>>,
+    started_at: Instant,
+    restart_at: Option<Instant>,
+    backoff: Duration,
+}
+
+impl ExecProcess {
+    pub fn new(exec: String) -> Self {
+        Self {
+            exec,
+            child: None,
+            lines: None,
+            started_at: Instant::now(),
+            restart_at: Some(Instant::now()),
+            backoff: BACKOFF_MIN,
+        }
+    }
+
+    /// Spawns or restarts the process if due, then returns all lines printed since the last call.
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut lines = vec![];
+
+        if let Some(rx) = self.lines.as_ref() {
+            lines.extend(rx.try_iter());
+        }
+
+        // lines printed right before exiting are still picked up on the next polls,
+        // as the receiver is only replaced once the process restarts
+        if let Some(child) = self.child.as_mut()
+            && !matches!(child.try_wait(), Ok(None))
+        {
+            self.child = None;
+            self.schedule_restart();
+        }
+
+        if self.restart_at.is_some_and(|at| at <= Instant::now()) {
+            self.restart_at = None;
+            if let Err(e) = self.spawn() {
+                log::error!("{e:?}");
+                self.schedule_restart();
+            }
+        }
+
+        lines
+    }
+
+    fn schedule_restart(&mut self) {
+        // a process that ran for a while is considered healthy again
+        if self.started_at.elapsed() > BACKOFF_MAX {
+            self.backoff = BACKOFF_MIN;
+        }
+
+        log::warn!(
+            "Exec label process exited, restarting in {}s: '{}'",
+            self.backoff.as_secs(),
+            self.exec
+        );
+        self.restart_at = Some(Instant::now() + self.backoff);
+        self.backoff = (self.backoff * 2).min(BACKOFF_MAX);
+    }
+
+    fn spawn(&mut self) -> anyhow::Result<()> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.exec)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            // own process group, so that pipelines and loops are stopped along with the shell
+            .process_group(0)
+            .spawn()
+            .with_context(|| format!("Failed to run exec label script: '{}'", self.exec))?;
+
+        let stdout = child.stdout.take().unwrap(); // safe: piped above
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::Builder::new()
+            .name("exec-label".into())
+            .spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        self.child = Some(child);
+        self.lines = Some(rx);
+        self.started_at = Instant::now();
+        Ok(())
+    }
+}
+
+impl Drop for ExecProcess {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            unsafe {
+                // the group id is the pid of the shell
+                libc::kill(-(child.id() as i32), libc::SIGTERM);
+            }
+            let _ = child.wait();
+        }
+    }
+}
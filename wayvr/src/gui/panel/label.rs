@@ -1,4 +1,7 @@
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 use chrono::DateTime;
 use chrono::Local;
@@ -7,14 +10,15 @@ use wgui::{
     drawing,
     event::{self, EventCallback},
     i18n::Translation,
-    layout::Layout,
-    parser::{CustomAttribsInfoOwned, ParserState, parse_color_hex},
+    layout::{Layout, WidgetID},
+    parser::{CustomAttribsInfoOwned, Fetchable, ParserState, parse_color_hex},
     widget::{EventResult, label::WidgetLabel},
 };
 
 use crate::{
-    gui::panel::{log_invalid_attrib, log_missing_attrib},
+    gui::panel::{exec::ExecProcess, log_invalid_attrib, log_missing_attrib},
     state::AppState,
+    subsystem::{metrics::Metric, variables::VariableStore},
 };
 
 #[allow(clippy::too_many_lines)]
@@ -154,6 +158,86 @@ pub(super) fn setup_custom_label<S: 'static>(
             ipd_on_tick(common, data, app);
             Ok(EventResult::Pass)
         }),
+        "variable" => {
+            let Some(format) = attribs.get_value("_format") else {
+                log_missing_attrib(parser_state, TAG, "_format");
+                return;
+            };
+
+            let state = VariableLabelState {
+                format: format.into(),
+                generation: Cell::new(None),
+                text: RefCell::new(None),
+            };
+
+            Box::new(move |common, data, app, _| {
+                variable_on_tick(&state, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        "system" => {
+            let Some(format) = attribs.get_value("_format") else {
+                log_missing_attrib(parser_state, TAG, "_format");
+                return;
+            };
+
+            let segments = match parse_system_format(format) {
+                Ok(segments) => segments,
+                Err(msg) => {
+                    log_invalid_attrib(parser_state, TAG, "_format", &msg);
+                    return;
+                }
+            };
+
+            let state = SystemLabelState {
+                metrics: segments
+                    .iter()
+                    .filter_map(|s| match s {
+                        SystemSegment::Metric(metric) => Some(*metric),
+                        SystemSegment::Text(_) => None,
+                    })
+                    .collect(),
+                segments,
+                generation: Cell::new(None),
+                text: RefCell::new(None),
+            };
+
+            Box::new(move |common, data, app, _| {
+                system_on_tick(&state, common, data, app);
+                Ok(EventResult::Pass)
+            })
+        }
+        "exec" => {
+            let Some(exec) = attribs.get_value("_exec") else {
+                log_missing_attrib(parser_state, TAG, "_exec");
+                return;
+            };
+
+            let mut elements = vec![];
+            for element in attribs
+                .get_value("_elements")
+                .unwrap_or_default()
+                .split_whitespace()
+            {
+                let Ok(widget_id) = parser_state.get_widget_id(element) else {
+                    let msg = format!("no element with id \"{element}\"");
+                    log_invalid_attrib(parser_state, TAG, "_elements", &msg);
+                    return;
+                };
+                elements.push((element.into(), widget_id));
+            }
+
+            let state = ExecLabelState {
+                process: RefCell::new(ExecProcess::new(exec.to_string())),
+                self_id: attribs.widget_id,
+                elements,
+            };
+
+            Box::new(move |common, data, _, _| {
+                exec_on_tick(&state, common, data);
+                Ok(EventResult::Pass)
+            })
+        }
         unk => {
             log_invalid_attrib(parser_state, TAG, "_source", unk);
             return;
@@ -262,3 +346,171 @@ fn ipd_on_tick(
     let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
     label.set_text(common, Translation::from_raw_text(&text));
 }
+
+struct VariableLabelState {
+    format: Rc<str>,
+    /// `VariableStore::generation` as of the last update
+    generation: Cell<Option<u64>>,
+    text: RefCell<Option<String>>,
+}
+
+fn variable_on_tick(
+    state: &VariableLabelState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &AppState,
+) {
+    let generation = app.variables.generation();
+    if state.generation.replace(Some(generation)) == Some(generation) {
+        return;
+    }
+
+    // only re-render if one of our variables has changed
+    let text = format_variables(&state.format, &app.variables);
+    if state.text.borrow().as_ref() == Some(&text) {
+        return;
+    }
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(common, Translation::from_raw_text(&text));
+    *state.text.borrow_mut() = Some(text);
+}
+
+/// Replaces `{key}` with the value of the variable, or nothing if it's not set.
+fn format_variables(format: &str, variables: &VariableStore) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut rest = format;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(
+            variables
+                .get(&rest[start + 1..start + len])
+                .unwrap_or_default(),
+        );
+        rest = &rest[start + len + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+enum SystemSegment {
+    Text(String),
+    Metric(Metric),
+}
+
+struct SystemLabelState {
+    segments: Vec<SystemSegment>,
+    metrics: Vec<Metric>,
+    /// `SystemMetrics` generation as of the last update
+    generation: Cell<Option<u64>>,
+    text: RefCell<Option<String>>,
+}
+
+fn system_on_tick(
+    state: &SystemLabelState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+    app: &mut AppState,
+) {
+    let generation = app.system_metrics.request(&state.metrics);
+    if state.generation.replace(Some(generation)) == Some(generation) {
+        return;
+    }
+
+    let mut text = String::new();
+    for segment in &state.segments {
+        match segment {
+            SystemSegment::Text(t) => text.push_str(t),
+            SystemSegment::Metric(metric) => {
+                text.push_str(&app.system_metrics.format(*metric, &app.frame_stats));
+            }
+        }
+    }
+
+    if state.text.borrow().as_ref() == Some(&text) {
+        return;
+    }
+
+    let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+    label.set_text(common, Translation::from_raw_text(&text));
+    *state.text.borrow_mut() = Some(text);
+}
+
+/// Splits `_format` into literal text and `{metric}` placeholders.
+fn parse_system_format(format: &str) -> Result<Vec<SystemSegment>, String> {
+    let mut segments = vec![];
+    let mut rest = format;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err("unclosed {".into());
+        };
+        if start > 0 {
+            segments.push(SystemSegment::Text(rest[..start].to_string()));
+        }
+        let name = &rest[start + 1..start + len];
+        let Some(metric) = Metric::from_name(name) else {
+            return Err(format!("unknown metric \"{name}\""));
+        };
+        segments.push(SystemSegment::Metric(metric));
+        rest = &rest[start + len + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(SystemSegment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+struct ExecLabelState {
+    process: RefCell<ExecProcess>,
+    self_id: WidgetID,
+    /// If set, each line is `<element> <text>` and updates the given element instead.
+    elements: Vec<(Rc<str>, WidgetID)>,
+}
+
+fn exec_on_tick(
+    state: &ExecLabelState,
+    common: &mut event::CallbackDataCommon,
+    data: &mut event::CallbackData,
+) {
+    let lines = state.process.borrow_mut().poll();
+
+    if state.elements.is_empty() {
+        // only the latest line is of interest
+        if let Some(line) = lines.last() {
+            let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+            label.set_text(common, Translation::from_raw_text(line));
+        }
+        return;
+    }
+
+    for line in &lines {
+        let (key, text) = line.split_once(' ').unwrap_or((line, ""));
+        let Some((_, widget_id)) = state.elements.iter().find(|(k, _)| &**k == key) else {
+            log::debug!("Exec label: ignoring line for unknown element \"{key}\"");
+            continue;
+        };
+
+        // our own widget is already borrowed through `data`
+        if *widget_id == state.self_id {
+            let label = data.obj.get_as_mut::<WidgetLabel>().unwrap();
+            label.set_text(common, Translation::from_raw_text(text));
+            continue;
+        }
+
+        let Some(widget) = common.state.widgets.get(*widget_id).cloned() else {
+            continue;
+        };
+        let Some(mut label) = widget.get_as::<WidgetLabel>() else {
+            log::debug!("Exec label: element \"{key}\" is not a label");
+            continue;
+        };
+        label.set_text(common, Translation::from_raw_text(text));
+    }
+}
@@ -47,8 +47,10 @@ use crate::{
 
 use super::timer::GuiTimer;
 
+mod action;
 pub mod button;
 pub mod device_list;
+mod exec;
 mod label;
 pub mod overlay_list;
 pub mod set_list;
@@ -277,10 +279,10 @@ impl<S: 'static> OverlayBackend for GuiPanel<S> {
         Ok(())
     }
 
-    fn resume(&mut self, _app: &mut AppState) -> anyhow::Result<()> {
+    fn resume(&mut self, app: &mut AppState) -> anyhow::Result<()> {
         self.layout.needs_redraw = true;
         self.timestep.reset();
-        Ok(())
+        self.notify(app, OverlayEventData::Resumed)
     }
 
     fn should_render(&mut self, app: &mut AppState) -> anyhow::Result<ShouldRender> {
@@ -7,8 +7,9 @@ use crate::{
         self,
         task::{InputTask, OverlayTask, TaskType},
     },
-    ipc::signal::WayVRSignal,
+    ipc::{requests, signal::WayVRSignal},
     state::AppState,
+    subsystem::do_not_disturb,
     windowing::{OverlaySelector, manager::OverlayWindowManager},
 };
 
@@ -32,7 +33,7 @@ fn process_tick_tasks(
 
 pub fn tick_events<O>(
     app: &mut AppState,
-    _overlays: &mut OverlayWindowManager<O>,
+    overlays: &mut OverlayWindowManager<O>,
 ) -> anyhow::Result<()>
 where
     O: Default,
@@ -43,6 +44,9 @@ where
                 app.ipc_server
                     .broadcast(packet_server::PacketServer::WvrStateChanged(packet));
             }
+            WayVRSignal::BroadcastEvent(event) => {
+                app.ipc_server.broadcast_event(event);
+            }
             WayVRSignal::DeviceHaptics(device, haptics) => {
                 app.tasks
                     .enqueue(TaskType::Input(InputTask::Haptics { device, haptics }));
@@ -64,6 +68,20 @@ where
                 app.tasks
                     .enqueue(TaskType::Overlay(OverlayTask::ModifyPanel(custom_task)));
             }
+            WayVRSignal::SetVariable(key, value) => {
+                app.variables
+                    .set(&app.wayvr_signals, &key, value.as_deref());
+            }
+            WayVRSignal::SetDoNotDisturb(enabled) => {
+                do_not_disturb::set_manual(app, enabled);
+            }
+            WayVRSignal::ArrangeScreens(arrangement) => {
+                app.tasks
+                    .enqueue(TaskType::Overlay(OverlayTask::ArrangeScreens(arrangement)));
+            }
+            WayVRSignal::OverlayRequest(conn, packet) => {
+                requests::handle_overlay_request(app, overlays, conn, packet);
+            }
         }
     }
 
@@ -2,13 +2,18 @@ use crate::backend::wayvr::{self, WvrServerState};
 
 use crate::{
     backend::input::InputState,
+    config,
     ipc::{event_queue::SyncEventQueue, signal::WayVRSignal},
 };
 use bytes::BufMut;
 use glam::Vec3A;
 use interprocess::local_socket::{self, ToNsName, traits::Listener};
+use slotmap::{SlotMap, new_key_type};
 use smallvec::SmallVec;
-use std::io::{Read, Write};
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+};
 use wayvr_ipc::{
     ipc::{self},
     packet_client::{self, PacketClient},
@@ -20,11 +25,17 @@ pub struct AuthInfo {
     pub protocol_version: u32, // client protocol version
 }
 
+new_key_type! {
+    pub struct ConnectionID;
+}
+
 pub struct Connection {
+    id: ConnectionID,
     alive: bool,
     conn: local_socket::Stream,
     next_packet: Option<u32>,
     auth: Option<AuthInfo>,
+    subscriptions: HashSet<packet_server::WlxEventTopic>,
 }
 
 pub fn send_packet(conn: &mut local_socket::Stream, data: &[u8]) -> anyhow::Result<()> {
@@ -92,12 +103,14 @@ pub fn gen_env_vec(input: &[String]) -> Vec<(&str, &str)> {
 }
 
 impl Connection {
-    const fn new(conn: local_socket::Stream) -> Self {
+    fn new(id: ConnectionID, conn: local_socket::Stream) -> Self {
         Self {
+            id,
             conn,
             alive: true,
             auth: None,
             next_packet: None,
+            subscriptions: HashSet::new(),
         }
     }
 
@@ -349,6 +362,43 @@ impl Connection {
         params.signals.send(WayVRSignal::SwitchSet(set));
     }
 
+    fn handle_wlx_profile_list(&mut self, serial: ipc::Serial) -> anyhow::Result<()> {
+        send_packet(
+            &mut self.conn,
+            &ipc::data_encode(&PacketServer::WlxProfileListResponse(
+                serial,
+                config::list_profiles(),
+            )),
+        )?;
+        Ok(())
+    }
+
+    fn handle_wlx_profile_export(&mut self, serial: ipc::Serial, name: &str) -> anyhow::Result<()> {
+        let res = config::export_profile(name).map_err(|e| format!("{e:?}"));
+        send_packet(
+            &mut self.conn,
+            &ipc::data_encode(&PacketServer::WlxProfileExportResponse(serial, res)),
+        )?;
+        Ok(())
+    }
+
+    fn send_profile_response(
+        &mut self,
+        serial: ipc::Serial,
+        res: anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let res = res.map_err(|e| format!("{e:?}"));
+        send_packet(
+            &mut self.conn,
+            &ipc::data_encode(&PacketServer::WlxProfileResponse(serial, res)),
+        )?;
+        Ok(())
+    }
+
+    fn handle_wlx_subscribe(&mut self, topics: Vec<packet_server::WlxEventTopic>) {
+        self.subscriptions = topics.into_iter().collect();
+    }
+
     fn handle_wlx_panel(
         params: &mut TickParams,
         custom_params: packet_client::WlxModifyPanelParams,
@@ -426,6 +476,51 @@ impl Connection {
             PacketClient::WlxModifyPanel(custom_params) => {
                 Self::handle_wlx_panel(params, custom_params);
             }
+            PacketClient::WlxVarSet(key, value) => {
+                params.signals.send(WayVRSignal::SetVariable(key, value));
+            }
+            PacketClient::WlxDoNotDisturbSet(enabled) => {
+                params.signals.send(WayVRSignal::SetDoNotDisturb(enabled));
+            }
+            PacketClient::WlxScreensArrange(arrangement) => {
+                params
+                    .signals
+                    .send(WayVRSignal::ArrangeScreens(arrangement));
+            }
+            PacketClient::WlxSubscribe(topics) => {
+                self.handle_wlx_subscribe(topics);
+            }
+            PacketClient::WlxProfileList(serial) => {
+                self.handle_wlx_profile_list(serial)?;
+            }
+            PacketClient::WlxProfileDelete(serial, name) => {
+                let res = config::delete_profile(&name);
+                self.send_profile_response(serial, res)?;
+            }
+            PacketClient::WlxProfileImport(serial, name, data) => {
+                let res = config::import_profile(&name, &data);
+                self.send_profile_response(serial, res)?;
+            }
+            PacketClient::WlxProfileExport(serial, name) => {
+                self.handle_wlx_profile_export(serial, &name)?;
+            }
+            packet @ (PacketClient::WlxOverlayList(_)
+            | PacketClient::WlxOverlayGet(..)
+            | PacketClient::WlxOverlaySet(..)
+            | PacketClient::WlxSetList(_)
+            | PacketClient::WlxSetCreate(..)
+            | PacketClient::WlxSetDelete(..)
+            | PacketClient::WlxSetRename(..)
+            | PacketClient::WlxSetAddOverlay(..)
+            | PacketClient::WlxSetRemoveOverlay(..)
+            | PacketClient::WlxProfileSave(..)
+            | PacketClient::WlxProfileLoad(..)
+            | PacketClient::WlxVarList(_)) => {
+                // needs access to the overlays or app state, responded to by ipc::requests
+                params
+                    .signals
+                    .send(WayVRSignal::OverlayRequest(self.id, packet));
+            }
         }
 
         Ok(())
@@ -502,7 +597,7 @@ impl Drop for Connection {
 
 pub struct WayVRServer {
     listener: local_socket::Listener,
-    connections: Vec<Connection>,
+    connections: SlotMap<ConnectionID, Connection>,
 }
 
 impl WayVRServer {
@@ -521,7 +616,7 @@ impl WayVRServer {
 
         Ok(Self {
             listener,
-            connections: Vec::new(),
+            connections: SlotMap::with_key(),
         })
     }
 
@@ -530,16 +625,17 @@ impl WayVRServer {
             return; // No new connection or other error
         };
 
-        self.connections.push(Connection::new(conn));
+        self.connections
+            .insert_with_key(|id| Connection::new(id, conn));
     }
 
     fn tick_connections(&mut self, params: &mut TickParams) {
-        for c in &mut self.connections {
+        for c in self.connections.values_mut() {
             c.tick(params);
         }
 
         // remove killed connections
-        self.connections.retain(|c| c.alive);
+        self.connections.retain(|_, c| c.alive);
     }
 
     pub fn tick(&mut self, params: &mut TickParams) {
@@ -548,10 +644,43 @@ impl WayVRServer {
     }
 
     pub fn broadcast(&mut self, packet: packet_server::PacketServer) {
-        for connection in &mut self.connections {
+        for connection in self.connections.values_mut() {
             if let Err(e) = send_packet(&mut connection.conn, &ipc::data_encode(&packet)) {
                 log::error!("failed to broadcast packet: {e:?}");
             }
         }
     }
+
+    /// Sends the event only to connections that subscribed to its topic.
+    pub fn broadcast_event(&mut self, event: packet_server::WlxEvent) {
+        let topic = event.topic();
+        if !self
+            .connections
+            .values()
+            .any(|c| c.subscriptions.contains(&topic))
+        {
+            return;
+        }
+
+        let data = ipc::data_encode(&PacketServer::WlxEvent(event));
+        for connection in self.connections.values_mut() {
+            if !connection.subscriptions.contains(&topic) {
+                continue;
+            }
+            if let Err(e) = send_packet(&mut connection.conn, &data) {
+                log::error!("failed to broadcast event: {e:?}");
+            }
+        }
+    }
+
+    /// Sends a response to a request that was deferred via WayVRSignal.
+    /// Does nothing if the connection has been closed since.
+    pub fn send_to(&mut self, id: ConnectionID, packet: &packet_server::PacketServer) {
+        let Some(connection) = self.connections.get_mut(id) else {
+            return;
+        };
+        if let Err(e) = send_packet(&mut connection.conn, &ipc::data_encode(packet)) {
+            log::error!("failed to send packet: {e:?}");
+        }
+    }
 }
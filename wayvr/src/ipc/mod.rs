@@ -1,4 +1,5 @@
 pub mod event_queue;
 pub mod events;
 pub mod ipc_server;
+pub mod requests;
 pub mod signal;
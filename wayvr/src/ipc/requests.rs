@@ -0,0 +1,280 @@
+use glam::{Affine3A, Quat, Vec3};
+use wayvr_ipc::{
+    packet_client::{PacketClient, WlxOverlaySetParams},
+    packet_server::{
+        PacketServer, WlxHand, WlxOverlay, WlxOverlayCategory, WlxOverlayState, WlxPositioning,
+        WlxSet, WlxTransform,
+    },
+};
+use wlx_common::{common::LeftRight, windowing::Positioning};
+
+use crate::{
+    backend::task::{OverlayTask, ToggleMode},
+    ipc::ipc_server::ConnectionID,
+    state::AppState,
+    windowing::{
+        OverlaySelector,
+        manager::OverlayWindowManager,
+        window::{self, OverlayCategory, OverlayWindowData},
+    },
+};
+
+/// Handles IPC requests that need access to the overlays or the app state.
+/// These are deferred from `ipc_server` via `WayVRSignal::OverlayRequest`.
+pub fn handle_overlay_request<O>(
+    app: &mut AppState,
+    overlays: &mut OverlayWindowManager<O>,
+    conn: ConnectionID,
+    packet: PacketClient,
+) where
+    O: Default,
+{
+    let response = match packet {
+        PacketClient::WlxOverlayList(serial) => {
+            let list = overlays.values().map(overlay_to_packet).collect();
+            PacketServer::WlxOverlayListResponse(serial, list)
+        }
+        PacketClient::WlxOverlayGet(serial, name) => {
+            let overlay = overlays
+                .values()
+                .find(|o| *o.config.name == *name)
+                .map(overlay_to_packet);
+            PacketServer::WlxOverlayGetResponse(serial, overlay)
+        }
+        PacketClient::WlxOverlaySet(serial, params) => {
+            PacketServer::WlxOverlaySetResponse(serial, overlay_set(app, overlays, params))
+        }
+        PacketClient::WlxSetList(serial) => {
+            let list = (0..overlays.get_total_sets())
+                .map(|idx| WlxSet {
+                    name: overlays
+                        .get_set_name(idx)
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                    active: overlays.get_current_set() == Some(idx),
+                    overlays: overlays
+                        .get_set_overlays(idx)
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                })
+                .collect();
+            PacketServer::WlxSetListResponse(serial, list)
+        }
+        PacketClient::WlxSetCreate(serial, name) => {
+            let res = overlays
+                .add_set(app, name.unwrap_or_default().into())
+                .map_err(|e| format!("{e:?}"));
+            PacketServer::WlxSetCreateResponse(serial, res)
+        }
+        PacketClient::WlxSetDelete(serial, set) => {
+            let res = overlays.delete_set(app, set);
+            PacketServer::WlxSetModifyResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxSetRename(serial, set, name) => {
+            let res = overlays.rename_set(app, set, name.into());
+            PacketServer::WlxSetModifyResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxSetAddOverlay(serial, set, name) => {
+            let res = overlays.add_overlay_to_set(app, set, &name);
+            PacketServer::WlxSetModifyResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxSetRemoveOverlay(serial, set, name) => {
+            let res = overlays.remove_overlay_from_set(app, set, &name);
+            PacketServer::WlxSetModifyResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxProfileSave(serial, name) => {
+            let res = overlays.save_profile(app, &name);
+            PacketServer::WlxProfileResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxProfileLoad(serial, name) => {
+            let res = overlays.load_profile(app, &name);
+            PacketServer::WlxProfileResponse(serial, res.map_err(|e| format!("{e:?}")))
+        }
+        PacketClient::WlxVarList(serial) => {
+            PacketServer::WlxVarListResponse(serial, app.variables.list())
+        }
+        _ => {
+            log::error!("Not an overlay request: {packet:?}");
+            return;
+        }
+    };
+
+    app.ipc_server.send_to(conn, &response);
+}
+
+fn overlay_set<O>(
+    app: &mut AppState,
+    overlays: &mut OverlayWindowManager<O>,
+    params: WlxOverlaySetParams,
+) -> Result<(), String>
+where
+    O: Default,
+{
+    let Some(id) = overlays.lookup(&params.name) else {
+        return Err(format!("Overlay not found: {}", params.name));
+    };
+
+    if let Some(visible) = params.visible {
+        let mode = if visible {
+            ToggleMode::EnsureOn
+        } else {
+            ToggleMode::EnsureOff
+        };
+        overlays
+            .handle_task(
+                app,
+                OverlayTask::ToggleOverlay(OverlaySelector::Id(id), mode),
+            )
+            .map_err(|e| format!("{e:?}"))?;
+    }
+
+    let move_requested = params.pos.is_some()
+        || params.rot.is_some()
+        || params.scale.is_some()
+        || params.positioning.is_some();
+
+    if !move_requested
+        && params.alpha.is_none()
+        && params.curvature.is_none()
+        && params.grabbable.is_none()
+        && params.interactable.is_none()
+    {
+        return Ok(());
+    }
+
+    let Some(o) = overlays.mut_by_id(id) else {
+        return Err(format!("Overlay not found: {}", params.name));
+    };
+
+    let Some(state) = o.config.active_state.as_mut() else {
+        return Err(format!("Overlay is not visible: {}", params.name));
+    };
+
+    if let Some(positioning) = params.positioning {
+        state.positioning = positioning_from_packet(positioning);
+    }
+
+    if params.pos.is_some() || params.rot.is_some() || params.scale.is_some() {
+        let (_, cur_rot, cur_pos) = state.transform.to_scale_rotation_translation();
+        let cur_scale = state.transform.x_axis.length();
+
+        let pos = params.pos.map_or(cur_pos, Vec3::from_array);
+        let rot = params
+            .rot
+            .map_or(cur_rot, |r| Quat::from_array(r).normalize());
+        let scale = params.scale.unwrap_or(cur_scale);
+
+        if !pos.is_finite() || !rot.is_finite() || !scale.is_finite() || scale <= 0. {
+            return Err("Invalid transform".into());
+        }
+
+        state.transform = Affine3A::from_scale_rotation_translation(Vec3::splat(scale), rot, pos);
+    }
+
+    if move_requested {
+        window::save_transform(state, app);
+    }
+
+    if let Some(alpha) = params.alpha {
+        state.alpha = alpha.clamp(0., 1.);
+    }
+
+    if let Some(curvature) = params.curvature {
+        state.curvature = if curvature < 0.005 {
+            None
+        } else {
+            Some(curvature)
+        };
+    }
+
+    if let Some(grabbable) = params.grabbable {
+        state.grabbable = grabbable;
+    }
+
+    if let Some(interactable) = params.interactable {
+        state.interactable = interactable;
+    }
+
+    o.config.dirty = true;
+    Ok(())
+}
+
+fn overlay_to_packet<O>(data: &OverlayWindowData<O>) -> WlxOverlay {
+    let state = data.config.active_state.as_ref().map(|state| {
+        let (_, rot, pos) = state.transform.to_scale_rotation_translation();
+        WlxOverlayState {
+            transform: WlxTransform {
+                pos: pos.to_array(),
+                rot: rot.to_array(),
+                scale: state.transform.x_axis.length(),
+            },
+            alpha: state.alpha,
+            curvature: state.curvature,
+            positioning: positioning_to_packet(state.positioning),
+            grabbable: state.grabbable,
+            interactable: state.interactable,
+        }
+    });
+
+    WlxOverlay {
+        name: data.config.name.to_string(),
+        category: category_to_packet(data.config.category),
+        visible: data.config.is_active(),
+        state,
+    }
+}
+
+const fn category_to_packet(category: OverlayCategory) -> WlxOverlayCategory {
+    match category {
+        OverlayCategory::Internal => WlxOverlayCategory::Internal,
+        OverlayCategory::Keyboard => WlxOverlayCategory::Keyboard,
+        OverlayCategory::Dashboard => WlxOverlayCategory::Dashboard,
+        OverlayCategory::Panel => WlxOverlayCategory::Panel,
+        OverlayCategory::Screen => WlxOverlayCategory::Screen,
+        OverlayCategory::Mirror => WlxOverlayCategory::Mirror,
+        OverlayCategory::WayVR => WlxOverlayCategory::WayVR,
+    }
+}
+
+const fn positioning_to_packet(positioning: Positioning) -> WlxPositioning {
+    match positioning {
+        Positioning::Floating => WlxPositioning::Floating,
+        Positioning::Anchored => WlxPositioning::Anchored,
+        Positioning::Static => WlxPositioning::Static,
+        Positioning::FollowHead { lerp } => WlxPositioning::FollowHead { lerp },
+        Positioning::FollowHand {
+            hand,
+            lerp,
+            align_to_hmd,
+        } => WlxPositioning::FollowHand {
+            hand: match hand {
+                LeftRight::Left => WlxHand::Left,
+                LeftRight::Right => WlxHand::Right,
+            },
+            lerp,
+            align_to_hmd,
+        },
+    }
+}
+
+const fn positioning_from_packet(positioning: WlxPositioning) -> Positioning {
+    match positioning {
+        WlxPositioning::Floating => Positioning::Floating,
+        WlxPositioning::Anchored => Positioning::Anchored,
+        WlxPositioning::Static => Positioning::Static,
+        WlxPositioning::FollowHead { lerp } => Positioning::FollowHead { lerp },
+        WlxPositioning::FollowHand {
+            hand,
+            lerp,
+            align_to_hmd,
+        } => Positioning::FollowHand {
+            hand: match hand {
+                WlxHand::Left => LeftRight::Left,
+                WlxHand::Right => LeftRight::Right,
+            },
+            lerp,
+            align_to_hmd,
+        },
+    }
+}
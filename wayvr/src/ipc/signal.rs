@@ -1,9 +1,17 @@
 #[derive(Clone)]
 pub enum WayVRSignal {
     BroadcastStateChanged(wayvr_ipc::packet_server::WvrStateChanged),
+    BroadcastEvent(wayvr_ipc::packet_server::WlxEvent),
     DeviceHaptics(usize, crate::backend::input::Haptics),
     DropOverlay(crate::windowing::OverlayID),
     SwitchSet(Option<usize>),
     ShowHide,
     CustomTask(crate::backend::task::ModifyPanelTask),
+    SetVariable(String, Option<String>),
+    SetDoNotDisturb(Option<bool>),
+    ArrangeScreens(wayvr_ipc::packet_client::WlxScreenArrangement),
+    OverlayRequest(
+        crate::ipc::ipc_server::ConnectionID,
+        wayvr_ipc::packet_client::PacketClient,
+    ),
 }
@@ -1,9 +1,10 @@
-use std::sync::atomic::Ordering;
+use std::{path::PathBuf, sync::atomic::Ordering};
 
 use dash_frontend::frontend::{self, FrontendTask, FrontendUpdateParams};
 use glam::{Affine2, Affine3A, Vec2, vec2, vec3};
+use strum::IntoEnumIterator;
 use wayvr_ipc::{
-    packet_client::WvrProcessLaunchParams,
+    packet_client::{WlxScreenArrangement, WvrProcessLaunchParams},
     packet_server::{WvrProcess, WvrProcessHandle, WvrWindow, WvrWindowHandle},
 };
 use wgui::{
@@ -17,8 +18,9 @@ use wgui::{
 };
 use wlx_common::{
     dash_interface::{self, DashInterface, RecenterMode},
+    keyboard_layout::KeyboardLayout,
     locale::WayVRLangProvider,
-    overlays::{BackendAttrib, BackendAttribValue},
+    overlays::{BackendAttrib, BackendAttribValue, ToastTopic},
 };
 use wlx_common::{
     timestep::Timestep,
@@ -39,10 +41,11 @@ use crate::{
             window::WindowHandle,
         },
     },
-    config::save_settings,
+    config::{self, ConfigType, save_settings},
     ipc::ipc_server::{gen_args_vec, gen_env_vec},
+    overlays::keyboard,
     state::AppState,
-    subsystem::hid::WheelDelta,
+    subsystem::hid::{VirtualKey, WheelDelta},
     windowing::{
         OverlaySelector, Z_ORDER_DASHBOARD,
         backend::{
@@ -195,7 +198,10 @@ impl OverlayBackend for DashFrontend {
         })
     }
 
-    fn notify(&mut self, _app: &mut AppState, _data: OverlayEventData) -> anyhow::Result<()> {
+    fn notify(&mut self, _app: &mut AppState, data: OverlayEventData) -> anyhow::Result<()> {
+        if let OverlayEventData::LayoutProfilesChanged = data {
+            self.inner.tasks.push(FrontendTask::RefreshLayoutProfiles);
+        }
         Ok(())
     }
 
@@ -431,6 +437,11 @@ impl DashInterface<AppState> for DashInterfaceLive {
         Ok(())
     }
 
+    fn screens_arrange(&mut self, app: &mut AppState, arrangement: WlxScreenArrangement) {
+        app.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::ArrangeScreens(arrangement)));
+    }
+
     fn desktop_finder<'a>(
         &'a mut self,
         data: &'a mut AppState,
@@ -446,7 +457,7 @@ impl DashInterface<AppState> for DashInterfaceLive {
     }
 
     fn config_changed(&mut self, data: &mut AppState) {
-        data.session.config_dirty = true;
+        data.session.config_changed();
         #[cfg(feature = "openxr")]
         {
             use crate::backend::task::OpenXrTask;
@@ -467,6 +478,76 @@ impl DashInterface<AppState> for DashInterfaceLive {
             .enqueue(TaskType::Overlay(OverlayTask::ToggleDashboard));
     }
 
+    fn layout_profile_list(&mut self, _data: &mut AppState) -> Vec<String> {
+        config::list_profiles()
+    }
+
+    fn layout_profile_save(&mut self, data: &mut AppState, name: &str) -> bool {
+        data.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::SaveProfile(name.into())));
+        false
+    }
+
+    fn layout_profile_load(&mut self, data: &mut AppState, name: &str) {
+        data.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::LoadProfile(name.into())));
+    }
+
+    fn layout_profile_delete(&mut self, _data: &mut AppState, name: &str) -> anyhow::Result<()> {
+        config::delete_profile(name)
+    }
+
+    fn layout_profile_export(
+        &mut self,
+        data: &mut AppState,
+        name: &str,
+    ) -> anyhow::Result<PathBuf> {
+        config::export_profile_file(name, &data.session.config)
+    }
+
+    fn layout_profile_import_list(&mut self, data: &mut AppState) -> Vec<String> {
+        config::list_profile_files(&data.session.config)
+    }
+
+    fn layout_profile_import(&mut self, data: &mut AppState, name: &str) -> anyhow::Result<()> {
+        config::import_profile_file(name, &data.session.config)
+    }
+
+    fn keyboard_layout_get(&mut self, _data: &mut AppState, default: bool) -> KeyboardLayout {
+        if default {
+            config::load_default_yaml(ConfigType::Keyboard)
+        } else {
+            config::load_known_yaml(ConfigType::Keyboard)
+        }
+    }
+
+    fn keyboard_layout_save(
+        &mut self,
+        data: &mut AppState,
+        layout: &KeyboardLayout,
+    ) -> anyhow::Result<()> {
+        keyboard::save_layout(data, layout)
+    }
+
+    fn keyboard_key_names(&mut self, _data: &mut AppState) -> Vec<String> {
+        VirtualKey::iter().map(|k| format!("{k:?}")).collect()
+    }
+
+    fn notification_recent_list(
+        &mut self,
+        data: &mut AppState,
+    ) -> Vec<dash_interface::RecentNotification> {
+        // rules never apply to WayVR's own toasts
+        data.notification_history
+            .iter()
+            .filter(|e| !matches!(e.topic, ToastTopic::System | ToastTopic::Error))
+            .map(|e| dash_interface::RecentNotification {
+                app_name: e.app_name.clone(),
+                summary: e.title.clone(),
+            })
+            .collect()
+    }
+
     #[cfg(feature = "openxr")]
     fn monado_client_list(
         &mut self,
@@ -1,10 +1,14 @@
-use std::{collections::HashMap, str::FromStr, sync::LazyLock};
+use std::{ops::Deref, str::FromStr};
 
-use regex::Regex;
-use serde::{Deserialize, Serialize};
+use wlx_common::{
+    keyboard_layout::{KeyboardLayout, LayoutError, parse_macro_verb},
+    overlays::ToastTopic,
+};
 
 use crate::{
-    config::{ConfigType, load_known_yaml},
+    config::{ConfigType, load_default_yaml, load_known_yaml},
+    overlays::toast::Toast,
+    state::AppState,
     subsystem::hid::{
         KEYS_TO_MODS, KeyType, META, NUM_LOCK, SHIFT, VirtualKey, XkbKeymap, get_key_type,
     },
@@ -12,52 +16,76 @@ use crate::{
 
 use super::KeyButtonData;
 
-static MACRO_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^([A-Za-z0-9_-]+)(?: +(UP|DOWN))?$").unwrap()); // want panic
-
-#[derive(Debug, Deserialize, Serialize)]
-#[allow(clippy::struct_field_names)]
-pub struct Layout {
-    pub(super) row_size: f32,
-    pub(super) key_sizes: Vec<Vec<f32>>,
-    pub(super) main_layout: Vec<Vec<Option<String>>>,
-    pub(super) exec_commands: HashMap<String, Vec<String>>,
-    pub(super) macros: HashMap<String, Vec<String>>,
-    pub(super) labels: HashMap<String, Vec<String>>,
-    pub(super) auto_labels: Option<bool>,
+/// Errors listed in the toast, the rest are only logged
+const MAX_TOAST_ERRORS: usize = 5;
+
+/// A `KeyboardLayout` that passed validation.
+#[derive(Debug)]
+pub struct Layout(KeyboardLayout);
+
+impl Deref for Layout {
+    type Target = KeyboardLayout;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub(super) fn validate(layout: &KeyboardLayout) -> Vec<LayoutError> {
+    layout.validate(|key| VirtualKey::from_str(key).is_ok())
 }
 
 impl Layout {
-    pub(super) fn load_from_disk() -> Self {
-        let mut layout = load_known_yaml::<Self>(ConfigType::Keyboard);
-        layout.post_load();
-        layout
-    }
+    /// Loads the user's `keyboard.yaml`. Errors are logged and shown in a toast.
+    /// Keys with errors are left out, unless the rows themselves don't line up,
+    /// in which case the built-in layout is used instead.
+    pub(super) fn load_from_disk(app: &mut AppState) -> Self {
+        let mut layout = load_known_yaml::<KeyboardLayout>(ConfigType::Keyboard);
+        let errors = validate(&layout);
+        if errors.is_empty() {
+            return Self(layout);
+        }
 
-    fn post_load(&mut self) {
-        for i in 0..self.key_sizes.len() {
-            let row = &self.key_sizes[i];
-            let width: f32 = row.iter().sum();
-            assert!(
-                (width - self.row_size).abs() < 0.001,
-                "Row {} has a width of {}, but the row size is {}",
-                i,
-                width,
-                self.row_size
-            );
+        for e in &errors {
+            log::error!("keyboard.yaml: {e}");
         }
 
-        for i in 0..self.main_layout.len() {
-            let row = &self.main_layout[i];
-            let width = row.len();
-            assert!(
-                (width == self.key_sizes[i].len()),
-                "Row {} has {} keys, needs to have {} according to key_sizes",
-                i,
-                width,
-                self.key_sizes[i].len()
-            );
+        if errors.iter().any(LayoutError::is_structural) {
+            Toast::new(
+                ToastTopic::System,
+                "TOAST.KEYBOARD_LAYOUT_INVALID".into(),
+                format!("TOAST.KEYBOARD_LAYOUT_ERRORS;{}", errors.len()),
+            )
+            .with_timeout(8.)
+            .with_sound(true)
+            .submit(app);
+
+            return Self(load_default_yaml(ConfigType::Keyboard));
         }
+
+        layout.skip_invalid_keys(&errors, |key| VirtualKey::from_str(key).is_ok());
+
+        let mut lines = errors
+            .iter()
+            .take(MAX_TOAST_ERRORS)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if errors.len() > MAX_TOAST_ERRORS {
+            lines.push(format!(
+                "... {} more, see the log",
+                errors.len() - MAX_TOAST_ERRORS
+            ));
+        }
+        Toast::new(
+            ToastTopic::Error,
+            "TOAST.KEYBOARD_KEYS_SKIPPED".into(),
+            lines.join("\n"),
+        )
+        .with_timeout(8.)
+        .with_sound(true)
+        .submit(app);
+
+        Self(layout)
     }
 
     pub(super) fn get_key_data(
@@ -165,59 +193,24 @@ impl Layout {
             cap_type,
         })
     }
-
-    fn label_for_key(&self, key: &str) -> Vec<String> {
-        if let Some(label) = self.labels.get(key) {
-            return label.clone();
-        }
-        if key.is_empty() {
-            return vec![];
-        }
-        if key.len() == 1 {
-            return vec![key.to_string().to_lowercase()];
-        }
-        let mut key = key;
-        if key.starts_with("KP_") {
-            key = &key[3..];
-        }
-        if key.contains('_') {
-            key = key.split('_').next().unwrap_or_else(|| {
-                log::error!("keyboard.yaml: Key '{key}' must not start or end with '_'!");
-                "???"
-            });
-        }
-        vec![format!(
-            "{}{}",
-            key.chars().next().unwrap().to_uppercase(), // safe because we checked is_empty
-            &key[1..].to_lowercase()
-        )]
-    }
 }
 
 fn key_events_for_macro(macro_verbs: &Vec<String>) -> Vec<(VirtualKey, bool)> {
     let mut key_events = vec![];
     for verb in macro_verbs {
-        if let Some(caps) = MACRO_REGEX.captures(verb) {
-            if let Ok(virtual_key) = VirtualKey::from_str(&caps[1]) {
-                if let Some(state) = caps.get(2) {
-                    if state.as_str() == "UP" {
-                        key_events.push((virtual_key, false));
-                    } else if state.as_str() == "DOWN" {
-                        key_events.push((virtual_key, true));
-                    } else {
-                        log::error!(
-                            "Unknown key state in macro: {}, looking for UP or DOWN.",
-                            state.as_str()
-                        );
-                        return vec![];
-                    }
-                } else {
-                    key_events.push((virtual_key, true));
-                    key_events.push((virtual_key, false));
-                }
-            } else {
-                log::error!("Unknown virtual key: {}", &caps[1]);
-                return vec![];
+        let Some((key, state)) = parse_macro_verb(verb) else {
+            log::error!("Invalid macro step: {verb}");
+            return vec![];
+        };
+        let Ok(virtual_key) = VirtualKey::from_str(key) else {
+            log::error!("Unknown virtual key: {key}");
+            return vec![];
+        };
+        match state {
+            Some(pressed) => key_events.push((virtual_key, pressed)),
+            None => {
+                key_events.push((virtual_key, true));
+                key_events.push((virtual_key, false));
             }
         }
     }
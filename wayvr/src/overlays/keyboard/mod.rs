@@ -11,6 +11,7 @@ use crate::{
         input::{HoverResult, PointerHit},
         task::{OverlayTask, TaskType},
     },
+    config,
     gui::panel::{GuiPanel, overlay_list::OverlayList, set_list::SetList},
     overlays::keyboard::builder::create_keyboard_panel,
     state::AppState,
@@ -22,6 +23,7 @@ use crate::{
         },
     },
     windowing::{
+        OverlaySelector,
         backend::{FrameMeta, OverlayBackend, OverlayEventData, RenderResources, ShouldRender},
         window::{OverlayCategory, OverlayWindowConfig},
     },
@@ -37,6 +39,7 @@ use wgui::{
 use wlx_common::windowing::{OverlayWindowState, Positioning};
 use wlx_common::{
     config::AltModifier,
+    keyboard_layout::KeyboardLayout,
     overlays::{BackendAttrib, BackendAttribValue},
 };
 
@@ -48,7 +51,7 @@ const AUTO_RELEASE_MODS: [KeyModifier; 5] = [SHIFT, CTRL, ALT, SUPER, META];
 const SYSTEM_LAYOUT_ALIASES: [&str; 5] = ["mozc", "pinyin", "hangul", "sayura", "unikey"];
 
 pub fn create_keyboard(app: &mut AppState, wayland: bool) -> anyhow::Result<OverlayWindowConfig> {
-    let layout = layout::Layout::load_from_disk();
+    let layout = layout::Layout::load_from_disk(app);
     let default_state = KeyboardState {
         modifiers: 0,
         alt_modifier: alt_modifier_to_key(app.session.config.keyboard_middle_click_mode),
@@ -119,6 +122,28 @@ pub fn create_keyboard(app: &mut AppState, wayland: bool) -> anyhow::Result<Over
     })
 }
 
+/// Validates and writes `keyboard.yaml`, then rebuilds the keyboard from it.
+pub fn save_layout(app: &mut AppState, layout: &KeyboardLayout) -> anyhow::Result<()> {
+    let errors = layout::validate(layout);
+    if !errors.is_empty() {
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        anyhow::bail!("Keyboard layout is invalid:\n{}", errors.join("\n"));
+    }
+
+    config::save_keyboard_layout(layout)?;
+
+    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+        OverlaySelector::Name(KEYBOARD_NAME.into()),
+        Box::new(|app, config| {
+            let _ = config
+                .backend
+                .notify(app, OverlayEventData::KeyboardLayoutChanged)
+                .inspect_err(|e| log::warn!("Could not reload keyboard: {e:?}"));
+        }),
+    )));
+    Ok(())
+}
+
 fn alt_modifier_to_key(m: AltModifier) -> KeyModifier {
     match m {
         AltModifier::Shift => SHIFT,
@@ -240,6 +265,26 @@ impl KeyboardBackend {
         self.switch_keymap(&keymap, app)
     }
 
+    /// Rebuilds the keyboard from `keyboard.yaml`, keeping the current keymap.
+    fn reload_layout(&mut self, app: &mut AppState) -> anyhow::Result<()> {
+        self.default_state = self.panel().state.take();
+        self.wlx_layout = layout::Layout::load_from_disk(app);
+        self.layout_panels.clear();
+        self.layout_ids.clear();
+
+        let keymap = self
+            .get_effective_keymap()
+            .inspect_err(|e| log::warn!("{e:?}"))
+            .ok()
+            .filter(|_| self.wlx_layout.auto_labels.unwrap_or(true));
+        self.active_layout = self.add_new_keymap(keymap.as_ref(), app)?;
+        self.panel().init(app)?;
+
+        app.tasks
+            .enqueue(TaskType::Overlay(OverlayTask::KeyboardChanged));
+        Ok(())
+    }
+
     fn panel(&mut self) -> &mut GuiPanel<KeyboardState> {
         self.layout_panels.get_mut(self.active_layout).unwrap() // want panic
     }
@@ -290,6 +335,9 @@ impl OverlayBackend for KeyboardBackend {
     }
 
     fn notify(&mut self, app: &mut AppState, event_data: OverlayEventData) -> anyhow::Result<()> {
+        if let OverlayEventData::KeyboardLayoutChanged = event_data {
+            return self.reload_layout(app);
+        }
         self.panel().notify(app, event_data)
     }
 
@@ -3,6 +3,7 @@ pub mod custom;
 pub mod dashboard;
 pub mod edit;
 pub mod keyboard;
+pub mod notification_center;
 pub mod screen;
 pub mod toast;
 pub mod watch;
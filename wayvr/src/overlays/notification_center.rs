@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use glam::{Affine3A, vec3};
+use wgui::{
+    assets::AssetPath,
+    event::{CallbackDataCommon, EventAlterables, StyleSetRequest},
+    parser::{Fetchable, ParseDocumentParams},
+    taffy,
+    widget::sprite::WidgetSprite,
+};
+use wlx_common::windowing::{OverlayWindowState, Positioning};
+
+use crate::{
+    backend::task::{OverlayTask, TaskType},
+    gui::panel::{GuiPanel, NewGuiPanelParams},
+    state::AppState,
+    subsystem::notifications::NotificationEntry,
+    windowing::{OverlaySelector, backend::OverlayEventData, window::OverlayWindowConfig},
+};
+
+pub const NOTIFICATIONS_NAME: &str = "notifications";
+
+struct NotificationCenterState {
+    /// `NotificationHistory::revision` that the list was last built from
+    revision: Option<u64>,
+}
+
+/// Persistent list of past toasts, toggled from the watch.
+pub fn create_notification_center(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
+    let xml = "gui/notifications.xml";
+    let state = NotificationCenterState { revision: None };
+
+    let mut panel = GuiPanel::new_from_template(app, xml, state, NewGuiPanelParams::default())?;
+
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn(xml),
+        extra: panel.doc_extra.take().unwrap_or_default(),
+    };
+
+    // only rebuilt while visible, as the history may change with every toast
+    panel.on_notify = Some(Box::new(move |panel, app, event_data| {
+        let (OverlayEventData::NotificationsChanged | OverlayEventData::Resumed) = event_data
+        else {
+            return Ok(());
+        };
+        let revision = app.notification_history.revision();
+        if panel.state.revision == Some(revision) {
+            return Ok(());
+        }
+        panel.state.revision = Some(revision);
+
+        let root = panel.parser_state.get_widget_id("notifications_root")?;
+        panel.layout.remove_children(root);
+
+        let time_format = if app.session.config.clock_12h {
+            "%I:%M %p"
+        } else {
+            "%H:%M"
+        };
+
+        let mut alterables = EventAlterables::default();
+
+        for entry in app.notification_history.iter() {
+            let mut params = HashMap::new();
+            params.insert("id".into(), entry.id.to_string().into());
+            params.insert(
+                "app".into(),
+                entry
+                    .app_name
+                    .as_deref()
+                    .unwrap_or(entry.topic.as_ref())
+                    .into(),
+            );
+            params.insert("title".into(), entry.title.as_str().into());
+            params.insert("body".into(), entry.body.as_str().into());
+            params.insert(
+                "time".into(),
+                entry.time.format(time_format).to_string().into(),
+            );
+            panel.parser_state.instantiate_template(
+                &doc_params,
+                "Notification",
+                &mut panel.layout,
+                root,
+                params,
+            )?;
+
+            set_icon(panel, app, entry, &mut alterables);
+
+            let actions_root = panel
+                .parser_state
+                .get_widget_id(&format!("notification_actions_{}", entry.id))?;
+            for (idx, action) in entry.actions.iter().enumerate() {
+                let mut params = HashMap::new();
+                params.insert("id".into(), entry.id.to_string().into());
+                params.insert("idx".into(), idx.to_string().into());
+                params.insert("label".into(), action.label.as_str().into());
+                panel.parser_state.instantiate_template(
+                    &doc_params,
+                    "NotificationAction",
+                    &mut panel.layout,
+                    actions_root,
+                    params,
+                )?;
+            }
+        }
+
+        let empty = panel.parser_state.get_widget_id("notifications_empty")?;
+        let display = if app.notification_history.is_empty() {
+            taffy::Display::Flex
+        } else {
+            taffy::Display::None
+        };
+        alterables.set_style(empty, StyleSetRequest::Display(display));
+        alterables.mark_redraw();
+
+        panel.process_custom_elems(app);
+        panel.layout.process_alterables(alterables)?;
+        Ok(())
+    }));
+
+    panel.update_layout(app)?;
+
+    Ok(OverlayWindowConfig {
+        name: NOTIFICATIONS_NAME.into(),
+        default_state: OverlayWindowState {
+            transform: Affine3A::from_translation(vec3(0.3, -0.1, -0.6)),
+            grabbable: true,
+            interactable: true,
+            positioning: Positioning::Floating,
+            ..OverlayWindowState::default()
+        },
+        global: true,
+        ..OverlayWindowConfig::from_backend(Box::new(panel))
+    })
+}
+
+/// Replaces the default bell with the notification's own icon or image.
+fn set_icon(
+    panel: &GuiPanel<NotificationCenterState>,
+    app: &AppState,
+    entry: &NotificationEntry,
+    alterables: &mut EventAlterables,
+) {
+    let Some(data) = entry.icon.as_ref().and_then(|icon| icon.load(app)) else {
+        return;
+    };
+
+    if let Ok(mut sprite) = panel.parser_state.fetch_widget_as::<WidgetSprite>(
+        &panel.layout.state,
+        &format!("notification_icon_{}", entry.id),
+    ) {
+        let mut com = CallbackDataCommon {
+            alterables,
+            state: &panel.layout.state,
+        };
+        sprite.set_content(&mut com, Some(data));
+    }
+}
+
+/// Lets the notification center know that `AppState::notification_history` has changed.
+/// While hidden, it catches up once it's shown again.
+pub fn history_changed(app: &mut AppState) {
+    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+        OverlaySelector::Name(NOTIFICATIONS_NAME.into()),
+        Box::new(|app, config| {
+            if config.active_state.is_none() {
+                return;
+            }
+            let _ = config
+                .backend
+                .notify(app, OverlayEventData::NotificationsChanged)
+                .inspect_err(|e| log::warn!("Could not update notification center: {e:?}"));
+        }),
+    )));
+}
@@ -0,0 +1,217 @@
+use glam::{Affine3A, Quat, Vec2, Vec3, Vec3A, vec2, vec3};
+use wayvr_ipc::packet_client::WlxScreenArrangement;
+use wlx_common::overlays::ToastTopic;
+
+use crate::{
+    overlays::toast::Toast,
+    state::AppState,
+    windowing::{
+        OverlayID,
+        manager::OverlayWindowManager,
+        snap_upright,
+        window::{self, OverlayCategory},
+    },
+};
+
+/// Space left between screens, in meters
+const GAP: f32 = 0.05;
+/// Closest that screens are placed to the user, in meters
+const MIN_DIST: f32 = 0.3;
+
+struct ScreenSlot {
+    id: OverlayID,
+    size: Vec2,
+    /// Position and size on the desktop, if known
+    logical: Option<(Vec2, Vec2)>,
+}
+
+/// Where a screen ends up, relative to the user's head.
+struct Placement {
+    pos: Vec3,
+    yaw: f32,
+    /// Multiplies the current scale
+    scale: f32,
+}
+
+/// Moves all visible screens in front of the user, laid out according to `arrangement`.
+pub fn arrange_screens<O>(
+    overlays: &mut OverlayWindowManager<O>,
+    app: &mut AppState,
+    arrangement: WlxScreenArrangement,
+) where
+    O: Default,
+{
+    let mut slots: Vec<ScreenSlot> = overlays
+        .iter_mut()
+        .filter(|(_, o)| o.config.category == OverlayCategory::Screen)
+        .filter_map(|(id, o)| {
+            let (_, size) = o.quad()?;
+            let logical = app
+                .screens
+                .iter()
+                .find(|s| s.name == o.config.name)
+                .filter(|s| s.logical_size.min_element() > 0.0)
+                .map(|s| (s.logical_pos, s.logical_size));
+            Some(ScreenSlot { id, size, logical })
+        })
+        .collect();
+
+    if slots.is_empty() {
+        Toast::new(
+            ToastTopic::System,
+            "TOAST.CANNOT_ARRANGE_SCREENS".into(),
+            "TOAST.NO_SCREENS_VISIBLE".into(),
+        )
+        .with_timeout(5.)
+        .with_sound(true)
+        .submit(app);
+        return;
+    }
+
+    // left to right, then top to bottom, as on the desktop
+    slots.sort_by(|a, b| {
+        let a = a.logical.map_or(Vec2::ZERO, |(pos, _)| pos);
+        let b = b.logical.map_or(Vec2::ZERO, |(pos, _)| pos);
+        a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+    });
+
+    let dist = clamp_dist(app.session.config.screen_arc_radius).unwrap_or_else(|| {
+        log::warn!("screen_arc_radius is not a finite number, using {MIN_DIST}.");
+        MIN_DIST
+    });
+    let placements = match arrangement {
+        WlxScreenArrangement::Row => row(&slots, dist, None),
+        WlxScreenArrangement::Arc { radius } => {
+            let radius = radius.map_or(Some(dist), clamp_dist).unwrap_or_else(|| {
+                log::warn!("Arc radius is not a finite number, using {dist}.");
+                dist
+            });
+            row(&slots, dist, Some(radius))
+        }
+        WlxScreenArrangement::Stack => stack(&slots, dist),
+        WlxScreenArrangement::Desktop => desktop(&slots, dist).unwrap_or_else(|| {
+            log::warn!("Desktop layout of screens is not known, arranging in a row.");
+            row(&slots, dist, None)
+        }),
+    };
+
+    let head = snap_upright(app.input_state.hmd, Vec3A::Y);
+    for (slot, placement) in slots.iter().zip(placements) {
+        let Some(o) = overlays.mut_by_id(slot.id) else {
+            continue;
+        };
+        // keep the rotation given by upright_screen_fix
+        let (_, roll, _) = o
+            .config
+            .default_state
+            .transform
+            .to_scale_rotation_translation();
+        let Some(state) = o.config.active_state.as_mut() else {
+            continue;
+        };
+        let scale = state.transform.x_axis.length() * placement.scale;
+
+        state.transform = head
+            * Affine3A::from_scale_rotation_translation(
+                Vec3::splat(scale),
+                Quat::from_rotation_y(placement.yaw) * roll,
+                placement.pos,
+            );
+        window::save_transform(state, app);
+        o.config.dirty = true;
+    }
+
+    log::info!("Arranged {} screens: {arrangement:?}", slots.len());
+}
+
+/// `None` for NaN and infinity, which would place the screens nowhere.
+fn clamp_dist(dist: f32) -> Option<f32> {
+    dist.is_finite().then(|| dist.max(MIN_DIST))
+}
+
+/// Side by side, centered. With a radius, bent around the user.
+fn row(slots: &[ScreenSlot], dist: f32, radius: Option<f32>) -> Vec<Placement> {
+    let total = slots.iter().map(|s| s.size.x).sum::<f32>() + GAP * (slots.len() - 1) as f32;
+    let mut x = -total * 0.5;
+
+    slots
+        .iter()
+        .map(|s| {
+            let center = x + s.size.x * 0.5;
+            x += s.size.x + GAP;
+
+            match radius {
+                Some(r) => {
+                    // the distance along the arc stays the same as in a flat row
+                    let angle = center / r;
+                    Placement {
+                        pos: vec3(angle.sin() * r, 0.0, -angle.cos() * r),
+                        yaw: -angle,
+                        scale: 1.0,
+                    }
+                }
+                None => Placement {
+                    pos: vec3(center, 0.0, -dist),
+                    yaw: 0.0,
+                    scale: 1.0,
+                },
+            }
+        })
+        .collect()
+}
+
+/// On top of each other, first one on top.
+fn stack(slots: &[ScreenSlot], dist: f32) -> Vec<Placement> {
+    let total = slots.iter().map(|s| s.size.y).sum::<f32>() + GAP * (slots.len() - 1) as f32;
+    let mut y = total * 0.5;
+
+    slots
+        .iter()
+        .map(|s| {
+            let center = y - s.size.y * 0.5;
+            y -= s.size.y + GAP;
+            Placement {
+                pos: vec3(0.0, center, -dist),
+                yaw: 0.0,
+                scale: 1.0,
+            }
+        })
+        .collect()
+}
+
+/// Same positions and relative sizes as on the desktop, centered.
+/// `None` if any of the screens doesn't know where it is on the desktop.
+fn desktop(slots: &[ScreenSlot], dist: f32) -> Option<Vec<Placement>> {
+    let logical = slots
+        .iter()
+        .map(|s| s.logical)
+        .collect::<Option<Vec<_>>>()?;
+
+    // so that the first screen keeps its size
+    let (_, first_size) = logical[0];
+    let meters_per_px = slots[0].size.x / first_size.x;
+
+    let min = logical
+        .iter()
+        .fold(Vec2::MAX, |acc, (pos, _)| acc.min(*pos));
+    let max = logical
+        .iter()
+        .fold(Vec2::MIN, |acc, (pos, size)| acc.max(*pos + *size));
+    let middle = (min + max) * 0.5;
+
+    Some(
+        slots
+            .iter()
+            .zip(&logical)
+            .map(|(s, (pos, size))| {
+                // pixel y goes down
+                let center = (*pos + *size * 0.5 - middle) * meters_per_px * vec2(1.0, -1.0);
+                Placement {
+                    pos: vec3(center.x, center.y, -dist),
+                    yaw: 0.0,
+                    scale: size.x * meters_per_px / s.size.x,
+                }
+            })
+            .collect(),
+    )
+}
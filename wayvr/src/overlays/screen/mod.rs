@@ -13,6 +13,7 @@ use crate::{
     },
 };
 
+pub mod arrange;
 pub mod backend;
 pub mod capture;
 #[cfg(feature = "wayland")]
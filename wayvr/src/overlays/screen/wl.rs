@@ -117,8 +117,10 @@ pub fn create_screens_wayland(wl: &mut WlxClient, app: &mut AppState) -> ScreenC
         if let Some(mut backend) =
             create_screen_renderer_wl(output, has_wlr_screencopy, &mut pw_tokens, app)
         {
-            backend.logical_pos = vec2(output.logical_pos.0 as f32, output.logical_pos.1 as f32);
-            backend.logical_size = vec2(output.logical_size.0 as f32, output.logical_size.1 as f32);
+            let logical_pos = vec2(output.logical_pos.0 as f32, output.logical_pos.1 as f32);
+            let logical_size = vec2(output.logical_size.0 as f32, output.logical_size.1 as f32);
+            backend.logical_pos = logical_pos;
+            backend.logical_size = logical_size;
             backend.mouse_transform_original = output.transform;
             backend.apply_mouse_transform_with_override(Transform::Undefined);
 
@@ -132,6 +134,8 @@ pub fn create_screens_wayland(wl: &mut WlxClient, app: &mut AppState) -> ScreenC
             let meta = ScreenMeta {
                 name: wl.outputs[id].name.clone(),
                 native_handle: *id,
+                logical_pos,
+                logical_size,
             };
 
             screens.push((meta, window_config));
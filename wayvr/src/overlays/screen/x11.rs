@@ -109,8 +109,10 @@ pub fn create_screens_x11pw(app: &mut AppState) -> anyhow::Result<ScreenCreateDa
                 ),
             );
 
-            backend.logical_pos = vec2(m.monitor.x() as f32, m.monitor.y() as f32);
-            backend.logical_size = vec2(m.monitor.width() as f32, m.monitor.height() as f32);
+            let logical_pos = vec2(m.monitor.x() as f32, m.monitor.y() as f32);
+            let logical_size = vec2(m.monitor.width() as f32, m.monitor.height() as f32);
+            backend.logical_pos = logical_pos;
+            backend.logical_size = logical_size;
             backend.apply_mouse_transform_with_override(Transform::Undefined);
 
             let window_data = create_screen_from_backend(
@@ -123,6 +125,8 @@ pub fn create_screens_x11pw(app: &mut AppState) -> anyhow::Result<ScreenCreateDa
             let meta = ScreenMeta {
                 name: m.name.clone(),
                 native_handle: 0,
+                logical_pos,
+                logical_size,
             };
 
             (meta, window_data)
@@ -192,8 +196,10 @@ pub fn create_screens_xshm(app: &mut AppState) -> anyhow::Result<ScreenCreateDat
                 pos,
             );
 
-            backend.logical_pos = vec2(s.monitor.x() as f32, s.monitor.y() as f32);
-            backend.logical_size = vec2(size.0 as f32, size.1 as f32);
+            let logical_pos = vec2(s.monitor.x() as f32, s.monitor.y() as f32);
+            let logical_size = vec2(size.0 as f32, size.1 as f32);
+            backend.logical_pos = logical_pos;
+            backend.logical_size = logical_size;
             backend.apply_mouse_transform_with_override(Transform::Undefined);
 
             let window_data = create_screen_from_backend(
@@ -206,6 +212,8 @@ pub fn create_screens_xshm(app: &mut AppState) -> anyhow::Result<ScreenCreateDat
             let meta = ScreenMeta {
                 name: s.name.clone(),
                 native_handle: 0,
+                logical_pos,
+                logical_size,
             };
 
             (meta, window_data)
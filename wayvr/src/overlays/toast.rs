@@ -1,29 +1,108 @@
 use std::{
-    ops::Add,
+    cmp::Reverse,
+    collections::HashMap,
     sync::{Arc, LazyLock},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
-use glam::{Affine3A, Quat, Vec3, vec3};
-use wgui::{i18n::Translation, widget::label::WidgetLabel};
+use glam::{Affine3A, Mat4, Quat, Vec3, vec3};
+use wayvr_ipc::packet_server::WlxEvent;
+use wgui::{
+    animation::{Animation, AnimationEasing},
+    assets::AssetPath,
+    event::{CallbackDataCommon, EventAlterables, StyleSetRequest},
+    i18n::Translation,
+    layout::WidgetID,
+    parser::{Fetchable, ParseDocumentParams},
+    renderer_vk::text::custom_glyph::CustomGlyphData,
+    taffy,
+    widget::{label::WidgetLabel, rectangle::WidgetRectangle, sprite::WidgetSprite},
+};
 use wlx_common::{
     common::LeftRight,
-    overlays::{ToastDisplayMethod, ToastTopic},
+    config::NotificationRule,
+    overlays::{ToastDisplayMethod, ToastTopic, ToastUrgency},
     windowing::{OverlayWindowState, Positioning},
 };
 
 use crate::{
     backend::task::{OverlayTask, TaskType},
-    gui::panel::{GuiPanel, NewGuiPanelParams, OnCustomIdFunc},
+    gui::panel::{GuiPanel, NewGuiPanelParams},
+    ipc::signal::WayVRSignal,
+    overlays::notification_center,
     state::AppState,
-    windowing::{OverlaySelector, Z_ORDER_TOAST, window::OverlayWindowConfig},
+    subsystem::notifications::{self, DbusOrigin, NotificationAction},
+    windowing::{
+        OverlaySelector, Z_ORDER_TOAST, backend::OverlayEventData, window::OverlayWindowConfig,
+    },
 };
 
 const FONT_SIZE: isize = 16;
 const PADDING: (f32, f32) = (25., 7.);
 const PIXELS_TO_METERS: f32 = 1. / 2000.;
+/// As in toast.xml
+const TOAST_WIDTH: f32 = 500.;
+const SLIDE_TICKS: f32 = 15.;
+const TICKS_PER_SECOND: f32 = 60.;
+/// Toasts waiting for a slot, beyond which the least important ones are dropped
+const MAX_PENDING: usize = 20;
 static TOAST_NAME: LazyLock<Arc<str>> = LazyLock::new(|| "toast".into());
+static TOAST_WATCH_NAME: LazyLock<Arc<str>> = LazyLock::new(|| "toast-watch".into());
+
+/// Image shown next to the toast title, in place of the default bell.
+#[derive(Clone)]
+pub enum ToastIcon {
+    /// Theme icon name, absolute path or `file://` URI
+    Name(String),
+    /// Tightly packed RGBA pixels
+    Rgba {
+        width: u32,
+        height: u32,
+        data: Arc<[u8]>,
+    },
+    /// Contents of an image file, such as PNG
+    Encoded(Arc<[u8]>),
+}
+
+impl ToastIcon {
+    pub fn load(&self, app: &AppState) -> Option<CustomGlyphData> {
+        let globals = &app.wgui_globals;
+        let result = match self {
+            Self::Name(name) => {
+                let name = name.strip_prefix("file://").unwrap_or(name);
+                let path = if name.starts_with('/') {
+                    Some(name.to_string())
+                } else {
+                    app.desktop_finder.find_icon_path(name)
+                };
+                let Some(path) = path else {
+                    log::debug!("Could not find notification icon: {name}");
+                    return None;
+                };
+                CustomGlyphData::from_assets(globals, AssetPath::File(&path))
+            }
+            Self::Rgba {
+                width,
+                height,
+                data,
+            } => CustomGlyphData::from_rgba(
+                globals,
+                "notification-image",
+                *width,
+                *height,
+                data.to_vec(),
+            ),
+            Self::Encoded(data) => {
+                CustomGlyphData::from_bytes_raster(globals, "notification-image", data)
+            }
+        };
+
+        result
+            .inspect_err(|e| log::warn!("Could not load notification icon: {e:?}"))
+            .ok()
+    }
+}
 
 pub struct Toast {
     pub title: String,
@@ -32,6 +111,18 @@ pub struct Toast {
     pub timeout: f32,
     pub sound: bool,
     pub topic: ToastTopic,
+    pub urgency: ToastUrgency,
+    /// Overrides the display method of the topic
+    pub display: Option<ToastDisplayMethod>,
+    /// Stays on screen until the notification center is cleared,
+    /// or until a new toast needs its slot
+    pub sticky: bool,
+    /// Height in pixels, as requested by XSOverlay messages
+    pub height: Option<f32>,
+    pub app_name: Option<String>,
+    pub icon: Option<ToastIcon>,
+    pub actions: Vec<NotificationAction>,
+    pub origin: Option<DbusOrigin>,
 }
 
 #[allow(dead_code)]
@@ -44,6 +135,14 @@ impl Toast {
             timeout: 3.0,
             sound: false,
             topic,
+            urgency: ToastUrgency::Normal,
+            display: None,
+            sticky: false,
+            height: None,
+            app_name: None,
+            icon: None,
+            actions: Vec::new(),
+            origin: None,
         }
     }
     pub const fn with_timeout(mut self, timeout: f32) -> Self {
@@ -58,126 +157,378 @@ impl Toast {
         self.sound = sound;
         self
     }
-    pub fn submit(self, app: &mut AppState) {
-        self.submit_at(app, Instant::now());
+    pub const fn with_urgency(mut self, urgency: ToastUrgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
+    pub const fn with_height(mut self, height: Option<f32>) -> Self {
+        self.height = height;
+        self
+    }
+    pub fn with_app(mut self, app_name: Option<String>, icon: Option<ToastIcon>) -> Self {
+        self.app_name = app_name;
+        self.icon = icon;
+        self
+    }
+    pub fn with_actions(mut self, origin: DbusOrigin, actions: Vec<NotificationAction>) -> Self {
+        self.origin = Some(origin);
+        self.actions = actions;
+        self
+    }
+    fn apply_rule(&mut self, rule: &NotificationRule) {
+        if let Some(display) = rule.display {
+            self.display = Some(display);
+        }
+        if let Some(timeout) = rule.timeout {
+            self.timeout = timeout;
+        }
+        if rule.silent {
+            self.sound = false;
+        }
+        self.sticky = rule.sticky;
     }
-    pub fn submit_at(self, app: &mut AppState, instant: Instant) {
-        let selector = OverlaySelector::Name(TOAST_NAME.clone());
+    pub fn submit(mut self, app: &mut AppState) {
+        if let Some(rule) = notifications::find_rule(&app.session.notification_rules, &self) {
+            self.apply_rule(rule);
+        }
 
-        let destroy_at = instant.add(std::time::Duration::from_secs_f32(self.timeout));
+        // same as on screen, but resolved once for the history
+        let (title, body) = {
+            let mut i18n = app.wgui_globals.i18n();
+            let title = if self.title.is_empty() {
+                i18n.translate("TOAST.DEFAULT_TITLE").to_string()
+            } else if matches!(self.topic, ToastTopic::System | ToastTopic::Error) {
+                i18n.translate(&self.title).to_string()
+            } else {
+                self.title.clone()
+            };
+            let body = if matches!(self.topic, ToastTopic::System) && !self.body.is_empty() {
+                i18n.translate(&self.body).to_string()
+            } else {
+                self.body.clone()
+            };
+            (title, body)
+        };
+        app.notification_history.record(&self, title, body);
+        notification_center::history_changed(app);
+
+        app.wayvr_signals
+            .send(WayVRSignal::BroadcastEvent(WlxEvent::ToastPosted {
+                topic: self.topic.as_ref().to_string(),
+                title: self.title.clone(),
+                body: self.body.clone(),
+            }));
+
+        if app.do_not_disturb.intercept(&self) {
+            log::debug!("Not showing toast: do not disturb");
+            return;
+        }
 
         if self.sound && app.session.config.notifications_sound_enabled {
             app.audio_sample_player
                 .play_sample(&mut app.audio_system, "toast");
         }
 
-        // drop any toast that was created before us.
-        // (DropOverlay only drops overlays that were
-        // created before current frame)
-        app.tasks.enqueue_at(
-            TaskType::Overlay(OverlayTask::Drop(selector.clone())),
-            instant,
+        let method = self
+            .display
+            .or_else(|| app.session.toast_topics.get(self.topic).copied())
+            .unwrap_or(ToastDisplayMethod::Hide);
+
+        if method == ToastDisplayMethod::Hide {
+            log::debug!("Not showing toast: filtered out");
+            return;
+        }
+
+        app.toasts.push(self, method);
+    }
+    /// Same source and title, so that only the newest one needs to be shown.
+    fn is_duplicate_of(&self, other: &Self) -> bool {
+        self.topic == other.topic && self.app_name == other.app_name && self.title == other.title
+    }
+    fn translations(&self) -> (Translation, Translation) {
+        let title = if self.title.is_empty() {
+            Translation::from_translation_key("TOAST.DEFAULT_TITLE")
+        } else if matches!(self.topic, ToastTopic::System | ToastTopic::Error) {
+            Translation::from_translation_key(&self.title)
+        } else {
+            Translation::from_raw_text(&self.title)
+        };
+
+        let body = if matches!(self.topic, ToastTopic::System) {
+            Translation::from_translation_key(&self.body)
+        } else {
+            Translation::from_raw_text(&self.body)
+        };
+
+        (title, body)
+    }
+}
+
+struct QueuedToast {
+    id: u64,
+    toast: Toast,
+    method: ToastDisplayMethod,
+    /// Number of duplicates coalesced into this toast, including itself
+    count: u32,
+    /// Bumped whenever the contents change
+    revision: u32,
+    /// None while pending, or if sticky
+    expire_at: Option<Instant>,
+    /// Set once the toast starts sliding out
+    remove_at: Option<Instant>,
+}
+
+impl QueuedToast {
+    /// On screen and not sliding out
+    fn is_showing(&self, method: ToastDisplayMethod) -> bool {
+        self.remove_at.is_none() && self.method == method
+    }
+
+    fn coalesce(&mut self, mut toast: Toast) {
+        toast.urgency = toast.urgency.max(self.toast.urgency);
+        self.toast = toast;
+        self.count += 1;
+        self.revision += 1;
+    }
+}
+
+/// Toasts waiting for a free slot, and the ones on screen.
+/// Up to `toast_max_visible` toasts are stacked per display method.
+#[derive(Default)]
+pub struct ToastQueue {
+    next_id: u64,
+    /// Most urgent first, then oldest first
+    pending: Vec<QueuedToast>,
+    /// Oldest first, including the ones sliding out
+    visible: Vec<QueuedToast>,
+    /// Display methods whose toast overlay was created
+    overlays: Vec<ToastDisplayMethod>,
+    changed: bool,
+}
+
+impl ToastQueue {
+    fn push(&mut self, toast: Toast, method: ToastDisplayMethod) {
+        if let Some(queued) = self
+            .visible
+            .iter_mut()
+            .find(|t| t.is_showing(method) && t.toast.is_duplicate_of(&toast))
+        {
+            if !toast.sticky {
+                queued.expire_at = Some(Instant::now() + timeout(&toast));
+            }
+            queued.coalesce(toast);
+            self.changed = true;
+            return;
+        }
+
+        if let Some(queued) = self
+            .pending
+            .iter_mut()
+            .find(|t| t.method == method && t.toast.is_duplicate_of(&toast))
+        {
+            queued.coalesce(toast);
+            self.pending.sort_by_key(|t| Reverse(t.toast.urgency));
+            return;
+        }
+
+        self.next_id += 1;
+        let idx = self
+            .pending
+            .iter()
+            .position(|t| t.toast.urgency < toast.urgency)
+            .unwrap_or(self.pending.len());
+        self.pending.insert(
+            idx,
+            QueuedToast {
+                id: self.next_id,
+                toast,
+                method,
+                count: 1,
+                revision: 0,
+                expire_at: None,
+                remove_at: None,
+            },
         );
 
-        // CreateOverlay only creates the overlay if
-        // the selector doesn't exist yet, so in case
-        // multiple toasts are submitted for the same
-        // frame, only the first one gets created
-        app.tasks.enqueue_at(
-            TaskType::Overlay(OverlayTask::Create(
-                selector.clone(),
+        if self.pending.len() > MAX_PENDING {
+            // the oldest of the least urgent ones
+            let urgency = self.pending[self.pending.len() - 1].toast.urgency;
+            if let Some(idx) = self.pending.iter().position(|t| t.toast.urgency == urgency) {
+                let dropped = self.pending.remove(idx);
+                log::debug!("Too many toasts queued, dropping: {}", dropped.toast.title);
+            }
+        }
+    }
+}
+
+fn timeout(toast: &Toast) -> Duration {
+    Duration::from_secs_f32(toast.timeout.max(0.))
+}
+
+fn slide_duration(app: &AppState) -> Duration {
+    Duration::from_secs_f32(
+        SLIDE_TICKS / TICKS_PER_SECOND * app.wgui_globals.defaults().animation_mult,
+    )
+}
+
+fn overlay_name(method: ToastDisplayMethod) -> Arc<str> {
+    match method {
+        ToastDisplayMethod::Watch => TOAST_WATCH_NAME.clone(),
+        _ => TOAST_NAME.clone(),
+    }
+}
+
+/// Expires toasts and shows queued ones as slots free up. Call every frame.
+pub fn update(app: &mut AppState) {
+    let now = Instant::now();
+    let max_visible = app.session.config.toast_max_visible.max(1);
+    let slide = slide_duration(app);
+    let queue = &mut app.toasts;
+
+    let len = queue.visible.len();
+    queue
+        .visible
+        .retain(|t| t.remove_at.is_none_or(|at| at > now));
+    queue.changed |= queue.visible.len() != len;
+
+    for queued in &mut queue.visible {
+        if queued.remove_at.is_none() && queued.expire_at.is_some_and(|at| at <= now) {
+            queued.remove_at = Some(now + slide);
+            queue.changed = true;
+        }
+    }
+
+    let mut idx = 0;
+    while idx < queue.pending.len() {
+        let method = queue.pending[idx].method;
+        let showing = queue
+            .visible
+            .iter()
+            .filter(|t| t.is_showing(method))
+            .count();
+
+        if showing >= max_visible {
+            // sticky toasts make room for ones that are at least as urgent,
+            // critical toasts push out the oldest less urgent one
+            let urgency = queue.pending[idx].toast.urgency;
+            let oldest = queue.visible.iter_mut().find(|t| {
+                t.is_showing(method)
+                    && ((t.toast.sticky && t.toast.urgency <= urgency)
+                        || (urgency == ToastUrgency::Critical
+                            && t.toast.urgency < ToastUrgency::Critical))
+            });
+            let Some(oldest) = oldest else {
+                idx += 1;
+                continue;
+            };
+            oldest.remove_at = Some(now + slide);
+        }
+
+        let mut queued = queue.pending.remove(idx);
+        queued.expire_at = (!queued.toast.sticky).then(|| now + timeout(&queued.toast));
+        queue.visible.push(queued);
+        queue.changed = true;
+    }
+
+    if !std::mem::take(&mut queue.changed) {
+        return;
+    }
+
+    for method in [ToastDisplayMethod::Center, ToastDisplayMethod::Watch] {
+        let has_toasts = queue.visible.iter().any(|t| t.method == method);
+        let has_overlay = queue.overlays.contains(&method);
+        let selector = OverlaySelector::Name(overlay_name(method));
+
+        if has_toasts && !has_overlay {
+            // repeated while the overlay doesn't exist, later ones are no-ops
+            app.tasks.enqueue(TaskType::Overlay(OverlayTask::Create(
+                selector,
                 Box::new(move |app| {
-                    let maybe_toast = new_toast(self, app);
-                    app.tasks.enqueue_at(
-                        // at timeout, drop the overlay by ID instead
-                        // in order to avoid dropping any newer toasts
-                        TaskType::Overlay(OverlayTask::Drop(selector)),
-                        destroy_at,
-                    );
-                    maybe_toast
+                    // they may have expired since
+                    if !app.toasts.visible.iter().any(|t| t.method == method) {
+                        return None;
+                    }
+                    let config = new_toast_overlay(app, method)?;
+                    if !app.toasts.overlays.contains(&method) {
+                        app.toasts.overlays.push(method);
+                    }
+                    Some(config)
                 }),
-            )),
-            instant,
-        );
+            )));
+        } else if !has_toasts && has_overlay {
+            queue.overlays.retain(|m| *m != method);
+            app.tasks
+                .enqueue(TaskType::Overlay(OverlayTask::Drop(selector)));
+        } else if has_toasts {
+            app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+                selector,
+                Box::new(|app, config| {
+                    let _ = config
+                        .backend
+                        .notify(app, OverlayEventData::ToastsChanged)
+                        .inspect_err(|e| log::warn!("Could not update toasts: {e:?}"));
+                }),
+            )));
+        }
     }
 }
 
-fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
-    let current_method = app
-        .session
-        .toast_topics
-        .get(toast.topic)
-        .copied()
-        .unwrap_or(ToastDisplayMethod::Hide);
+struct MountedToast {
+    id: u64,
+    root: WidgetID,
+    /// None until the contents are set for the first time
+    revision: Option<u32>,
+    hiding: bool,
+}
 
-    let (spawn_point, spawn_rotation, positioning) = match current_method {
-        ToastDisplayMethod::Hide => {
-            log::debug!("Not showing toast: filtered out");
-            return None;
-        }
-        ToastDisplayMethod::Center => (
-            vec3(0., -0.2, -0.5),
-            Quat::IDENTITY,
-            Positioning::FollowHead { lerp: 0.1 },
-        ),
-        ToastDisplayMethod::Watch => {
-            let relative_to = Positioning::FollowHand {
+struct ToastPanelState {
+    method: ToastDisplayMethod,
+    mounted: Vec<MountedToast>,
+}
+
+fn new_toast_overlay(
+    app: &mut AppState,
+    method: ToastDisplayMethod,
+) -> Option<OverlayWindowConfig> {
+    let (spawn_point, positioning) = match method {
+        ToastDisplayMethod::Hide => return None,
+        ToastDisplayMethod::Center => (vec3(0., -0.2, -0.5), Positioning::FollowHead { lerp: 0.1 }),
+        ToastDisplayMethod::Watch => (
+            vec3(0., 0., 0.),
+            Positioning::FollowHand {
                 hand: LeftRight::Left,
                 lerp: 0.1,
                 align_to_hmd: true,
-            };
-            (vec3(0., 0., 0.), Quat::IDENTITY, relative_to)
-        }
+            },
+        ),
     };
 
-    let title = if toast.title.is_empty() {
-        Translation::from_translation_key("TOAST.DEFAULT_TITLE")
-    } else if matches!(toast.topic, ToastTopic::System | ToastTopic::Error) {
-        Translation::from_translation_key(&toast.title)
-    } else {
-        Translation::from_raw_text(&toast.title)
+    let xml = "gui/toast.xml";
+    let state = ToastPanelState {
+        method,
+        mounted: vec![],
     };
 
-    let body = if matches!(toast.topic, ToastTopic::System) {
-        Translation::from_translation_key(&toast.body)
-    } else {
-        Translation::from_raw_text(&toast.body)
+    let mut panel = GuiPanel::new_from_template(app, xml, state, NewGuiPanelParams::default())
+        .inspect_err(|e| log::error!("Could not create toast: {e:?}"))
+        .ok()?;
+
+    let doc_params = ParseDocumentParams {
+        globals: panel.layout.state.globals.clone(),
+        path: AssetPath::FileOrBuiltIn(xml),
+        extra: panel.doc_extra.take().unwrap_or_default(),
     };
 
-    let on_custom_id: OnCustomIdFunc<()> =
-        Box::new(move |id, widget, _doc_params, layout, _parser_state, ()| {
-            if &*id == "toast_title" {
-                let mut label = layout
-                    .state
-                    .widgets
-                    .get_as::<WidgetLabel>(widget)
-                    .context("toast.xml: missing element with id: toast_title")?;
-                let mut globals = layout.state.globals.get();
-                label.set_text_simple(&mut globals, title.clone());
-            }
-            if &*id == "toast_body" {
-                let mut label = layout
-                    .state
-                    .widgets
-                    .get_as::<WidgetLabel>(widget)
-                    .context("toast.xml: missing element with id: toast_body")?;
-                let mut globals = layout.state.globals.get();
-                label.set_text_simple(&mut globals, body.clone());
-            }
-            Ok(())
-        });
-
-    let mut panel = GuiPanel::new_from_template(
-        app,
-        "gui/toast.xml",
-        (),
-        NewGuiPanelParams {
-            on_custom_id: Some(on_custom_id),
-            ..Default::default()
-        },
-    )
-    .inspect_err(|e| log::error!("Could not create toast: {e:?}"))
-    .ok()?;
+    sync_toasts(&mut panel, app, &doc_params)
+        .inspect_err(|e| log::error!("Could not create toast: {e:?}"))
+        .ok()?;
+
+    panel.on_notify = Some(Box::new(move |panel, app, event_data| {
+        let OverlayEventData::ToastsChanged = event_data else {
+            return Ok(());
+        };
+        sync_toasts(panel, app, &doc_params)
+    }));
 
     panel
         .update_layout(app)
@@ -185,12 +536,12 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
         .ok()?;
 
     Some(OverlayWindowConfig {
-        name: TOAST_NAME.clone(),
+        name: overlay_name(method),
         default_state: OverlayWindowState {
             positioning,
             transform: Affine3A::from_scale_rotation_translation(
                 Vec3::ONE * panel.layout.content_size.x * PIXELS_TO_METERS,
-                spawn_rotation,
+                Quat::IDENTITY,
                 spawn_point,
             ),
             ..OverlayWindowState::default()
@@ -202,6 +553,175 @@ fn new_toast(toast: Toast, app: &mut AppState) -> Option<OverlayWindowConfig> {
     })
 }
 
+/// Mounts, updates and slides out toasts to match `AppState::toasts`.
+fn sync_toasts(
+    panel: &mut GuiPanel<ToastPanelState>,
+    app: &AppState,
+    doc_params: &ParseDocumentParams,
+) -> anyhow::Result<()> {
+    let method = panel.state.method;
+    let toasts = app
+        .toasts
+        .visible
+        .iter()
+        .filter(|t| t.method == method)
+        .collect::<Vec<_>>();
+    let root = panel.parser_state.get_widget_id("toasts_root")?;
+    let anim_mult = app.wgui_globals.defaults().animation_mult;
+
+    let mut alterables = EventAlterables::default();
+
+    let layout = &mut panel.layout;
+    panel.state.mounted.retain(|mounted| {
+        let keep = toasts.iter().any(|t| t.id == mounted.id);
+        if !keep {
+            layout.remove_widget(mounted.root);
+        }
+        keep
+    });
+
+    for queued in toasts {
+        let idx = if let Some(idx) = panel.state.mounted.iter().position(|m| m.id == queued.id) {
+            idx
+        } else {
+            let toast_root = mount_toast(panel, queued, doc_params, root, &mut alterables)?;
+            alterables.animate(slide_animation(toast_root, true, anim_mult));
+            panel.state.mounted.push(MountedToast {
+                id: queued.id,
+                root: toast_root,
+                revision: None,
+                hiding: false,
+            });
+            panel.state.mounted.len() - 1
+        };
+
+        let mounted = &mut panel.state.mounted[idx];
+        let needs_update = mounted.revision != Some(queued.revision);
+        mounted.revision = Some(queued.revision);
+
+        if queued.remove_at.is_some() && !mounted.hiding {
+            mounted.hiding = true;
+            alterables.animate(slide_animation(mounted.root, false, anim_mult));
+        }
+
+        if needs_update {
+            set_contents(panel, app, queued, &mut alterables)?;
+        }
+    }
+
+    alterables.mark_redraw();
+    panel.layout.process_alterables(alterables)?;
+    Ok(())
+}
+
+fn mount_toast(
+    panel: &mut GuiPanel<ToastPanelState>,
+    queued: &QueuedToast,
+    doc_params: &ParseDocumentParams,
+    root: WidgetID,
+    alterables: &mut EventAlterables,
+) -> anyhow::Result<WidgetID> {
+    let mut params = HashMap::new();
+    params.insert("id".into(), queued.id.to_string().into());
+    panel.parser_state.instantiate_template(
+        doc_params,
+        "Toast",
+        &mut panel.layout,
+        root,
+        params,
+    )?;
+
+    let toast_root = format!("toast_root_{}", queued.id);
+    let id = panel.parser_state.get_widget_id(&toast_root)?;
+
+    if let Some(height) = queued.toast.height {
+        alterables.set_style(id, StyleSetRequest::Height(taffy::prelude::length(height)));
+    }
+
+    let opacity = queued.toast.opacity.clamp(0., 1.);
+    if opacity < 1. {
+        let mut rect = panel
+            .parser_state
+            .fetch_widget_as::<WidgetRectangle>(&panel.layout.state, &toast_root)?;
+        rect.params.color.a *= opacity;
+        rect.params.border_color.a *= opacity;
+    }
+
+    Ok(id)
+}
+
+/// Sets everything that may change when a duplicate is coalesced.
+fn set_contents(
+    panel: &GuiPanel<ToastPanelState>,
+    app: &AppState,
+    queued: &QueuedToast,
+    alterables: &mut EventAlterables,
+) -> anyhow::Result<()> {
+    let (title, body) = queued.toast.translations();
+    let count = if queued.count > 1 {
+        format!("×{}", queued.count)
+    } else {
+        String::new()
+    };
+
+    let state = &panel.layout.state;
+    let mut com = CallbackDataCommon { alterables, state };
+
+    for (id, text) in [
+        ("toast_title", title),
+        ("toast_body", body),
+        ("toast_count", Translation::from_raw_text(&count)),
+    ] {
+        let mut label = panel
+            .parser_state
+            .fetch_widget_as::<WidgetLabel>(state, &format!("{id}_{}", queued.id))?;
+        label.set_text(&mut com, text);
+    }
+
+    if let Some(icon) = queued.toast.icon.as_ref().and_then(|icon| icon.load(app)) {
+        let mut sprite = panel
+            .parser_state
+            .fetch_widget_as::<WidgetSprite>(state, &format!("toast_icon_{}", queued.id))?;
+        sprite.set_content(&mut com, Some(icon));
+    }
+
+    Ok(())
+}
+
+/// Slides the toast in from the right, or back out.
+fn slide_animation(widget: WidgetID, slide_in: bool, anim_mult: f32) -> Animation {
+    let easing = if slide_in {
+        AnimationEasing::OutQuad
+    } else {
+        AnimationEasing::InQuad
+    };
+
+    Animation::new(
+        widget,
+        (SLIDE_TICKS * anim_mult) as _,
+        easing,
+        Box::new(move |common, data| {
+            let offset = if slide_in { 1. - data.pos } else { data.pos };
+            data.data.transform = Mat4::from_translation(vec3(offset * TOAST_WIDTH, 0., 0.));
+            common.alterables.mark_redraw();
+        }),
+    )
+}
+
+/// Slides out all toasts on screen and forgets the queued ones.
+pub fn dismiss_toasts(app: &mut AppState) {
+    let remove_at = Instant::now() + slide_duration(app);
+    let queue = &mut app.toasts;
+
+    queue.pending.clear();
+    for queued in &mut queue.visible {
+        if queued.remove_at.is_none() {
+            queued.remove_at = Some(remove_at);
+            queue.changed = true;
+        }
+    }
+}
+
 fn msg_err(app: &mut AppState, message: &str) {
     Toast::new(ToastTopic::Error, "TOAST.ERROR".into(), message.into())
         .with_timeout(3.)
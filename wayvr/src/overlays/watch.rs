@@ -112,6 +112,18 @@ pub fn create_watch(app: &mut AppState) -> anyhow::Result<OverlayWindowConfig> {
                         btn_edit_mode.set_sticky_state(&mut com, edit_mode);
                     }
                 }
+                OverlayEventData::DoNotDisturbChanged(active) => {
+                    if let Ok(btn_do_not_disturb) = panel
+                        .parser_state
+                        .fetch_component_as::<ComponentButton>("btn_do_not_disturb")
+                    {
+                        let mut com = CallbackDataCommon {
+                            alterables: &mut alterables,
+                            state: &panel.layout.state,
+                        };
+                        btn_do_not_disturb.set_sticky_state(&mut com, active);
+                    }
+                }
                 OverlayEventData::SettingsChanged => {
                     panel.layout.mark_redraw();
                     sets_or_overlays(panel, app, &mut alterables);
@@ -117,9 +117,13 @@ pub struct WvrWindowBackend {
     cur_image: Option<Arc<ImageView>>,
     panel: GuiPanel<WindowHandle>,
     inner_extent: [u32; 2],
+    /// Image pixels per unit of the app's coordinates, above 1 when it renders at a scale
+    logical_scale: f32,
     mouse_transform: Affine2,
     uv_range: RangeInclusive<f32>,
     panel_hovered: bool,
+    /// A button went down on the window itself, its release goes there too
+    window_pressed: bool,
 }
 
 impl WvrWindowBackend {
@@ -167,6 +171,25 @@ impl WvrWindowBackend {
 
                                 Ok(EventResult::Consumed)
                             }),
+                            "::DecorScale" => {
+                                let arg = args.next().unwrap_or_default();
+                                let Ok(steps) = arg.parse::<i32>() else {
+                                    log::error!("{command}: expected integer, found \"{arg}\"");
+                                    return;
+                                };
+                                Box::new(move |_common, data, app, state| {
+                                    if !test_button(data) || !test_duration(&button, app) {
+                                        return Ok(EventResult::Pass);
+                                    }
+
+                                    app.wvr_server
+                                        .as_mut()
+                                        .unwrap()
+                                        .step_window_scale(*state, steps);
+
+                                    Ok(EventResult::Consumed)
+                                })
+                            }
                             _ => return,
                         };
 
@@ -219,10 +242,12 @@ impl WvrWindowBackend {
             stereo_adjust_mouse: false,
             cur_image: None,
             inner_extent: [0, 0],
+            logical_scale: 1.0,
             panel,
             mouse_transform: Affine2::ZERO,
             uv_range: 0.0..=1.0,
             panel_hovered: false,
+            window_pressed: false,
         })
     }
 
@@ -293,16 +318,14 @@ impl OverlayBackend for WvrWindowBackend {
             return Ok(ShouldRender::Unable);
         };
 
-        let popups = PopupManager::popups_for_surface(toplevel.wl_surface())
+        let mut popups = PopupManager::popups_for_surface(toplevel.wl_surface())
             .filter_map(|(popup, point)| {
                 with_states(popup.wl_surface(), |states| {
-                    if !states
+                    // input method popups don't have a configure sequence
+                    if states
                         .data_map
                         .get::<XdgPopupSurfaceData>()
-                        .unwrap()
-                        .lock()
-                        .unwrap()
-                        .configured
+                        .is_some_and(|data| !data.lock().unwrap().configured)
                     {
                         // not yet configured
                         return None;
@@ -317,8 +340,28 @@ impl OverlayBackend for WvrWindowBackend {
             })
             .collect::<Vec<_>>();
 
+        // drag-and-drop icon follows the laser across windows
+        let dnd_icon = app.wvr_server.as_ref().and_then(|sv| {
+            let mouse = sv
+                .wm
+                .mouse
+                .as_ref()
+                .filter(|m| m.hover_window == self.window)?;
+            let icon = sv.manager.state.dnd_icon.as_ref()?;
+            with_states(icon, SurfaceBufWithImage::get_from_surface)
+                .map(|surf| (surf.image, vec2(mouse.x as _, mouse.y as _)))
+        });
+        popups.extend(dnd_icon);
+
         with_states(toplevel.wl_surface(), |states| {
             if let Some(surf) = SurfaceBufWithImage::get_from_surface(states) {
+                // popup positions and the pointer are in the app's coordinates
+                let logical_size = surf.logical_size(states);
+                self.logical_scale = surf.image.extent_u32arr()[0] as f32 / logical_size[0] as f32;
+                for (_, point) in &mut popups {
+                    *point *= self.logical_scale;
+                }
+
                 let mut meta = FrameMeta {
                     extent: surf.image.extent_u32arr(),
                     format: surf.image.format(),
@@ -376,8 +419,8 @@ impl OverlayBackend for WvrWindowBackend {
                     .as_ref()
                     .filter(|m| m.hover_window == self.window)
                     .map(|m| MouseMeta {
-                        x: (m.x as f32) / (inner_extent[0] as f32),
-                        y: (m.y as f32) / (inner_extent[1] as f32),
+                        x: (m.x as f32) * self.logical_scale / (inner_extent[0] as f32),
+                        y: (m.y as f32) * self.logical_scale / (inner_extent[1] as f32),
                     });
 
                 let dirty = self.mouse != mouse || self.popups != popups;
@@ -518,8 +561,8 @@ impl OverlayBackend for WvrWindowBackend {
 
         let clamped = transformed.clamp(Vec2::ZERO, Vec2::ONE);
 
-        let x = (clamped.x * (self.inner_extent[0] as f32)) as u32;
-        let y = (clamped.y * (self.inner_extent[1] as f32)) as u32;
+        let x = (clamped.x * (self.inner_extent[0] as f32) / self.logical_scale) as u32;
+        let y = (clamped.y * (self.inner_extent[1] as f32) / self.logical_scale) as u32;
 
         let wvr_server = app.wvr_server.as_mut().unwrap(); //never None
         wvr_server.send_mouse_move(self.window, x, y);
@@ -539,8 +582,12 @@ impl OverlayBackend for WvrWindowBackend {
 
     fn on_pointer(&mut self, app: &mut state::AppState, hit: &input::PointerHit, pressed: bool) {
         let transformed = self.mouse_transform.transform_point2(hit.uv);
+        // released over another overlay, e.g. at the end of a drag
+        let in_window = (self.uv_range.contains(&transformed.x)
+            && self.uv_range.contains(&transformed.y))
+            || (!pressed && self.window_pressed);
 
-        if !self.uv_range.contains(&transformed.x) || !self.uv_range.contains(&transformed.y) {
+        if !in_window {
             let Some(meta) = self.meta.as_ref() else {
                 return;
             };
@@ -562,6 +609,7 @@ impl OverlayBackend for WvrWindowBackend {
         } {
             let click_freeze = app.session.config.click_freeze_time_ms;
             let wvr_server = app.wvr_server.as_mut().unwrap(); //never None
+            self.window_pressed = pressed;
             if pressed {
                 wvr_server.send_mouse_down(click_freeze, self.window, index);
             } else {
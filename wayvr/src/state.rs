@@ -1,4 +1,4 @@
-use glam::Affine3A;
+use glam::{Affine3A, Vec2};
 use idmap::IdMap;
 use smallvec::{SmallVec, smallvec};
 use std::sync::Arc;
@@ -18,7 +18,7 @@ use wlx_common::{
 
 use crate::backend::wayvr::WvrServerState;
 #[cfg(feature = "osc")]
-use crate::subsystem::osc::OscSender;
+use crate::subsystem::osc::{OscReceiver, OscSender};
 
 use crate::{
     backend::{XrBackend, input::InputState, task::TaskContainer},
@@ -26,7 +26,16 @@ use crate::{
     graphics::WGfxExtras,
     gui,
     ipc::{event_queue::SyncEventQueue, ipc_server, signal::WayVRSignal},
-    subsystem::{dbus::DbusConnector, input::HidWrapper},
+    overlays::toast::ToastQueue,
+    subsystem::{
+        dbus::DbusConnector,
+        do_not_disturb::DoNotDisturb,
+        input::HidWrapper,
+        metrics::{FrameStats, SystemMetrics},
+        notifications::{self, CompiledRule, NotificationHistory},
+        variables::VariableStore,
+    },
+    windowing::OverlayStatus,
 };
 
 pub struct AppState {
@@ -46,6 +55,13 @@ pub struct AppState {
     pub screens: SmallVec<[ScreenMeta; 8]>,
     pub anchor: Affine3A,
     pub anchor_grabbed: bool,
+    pub overlay_status: OverlayStatus,
+    pub variables: VariableStore,
+    pub system_metrics: SystemMetrics,
+    pub frame_stats: FrameStats,
+    pub notification_history: NotificationHistory,
+    pub do_not_disturb: DoNotDisturb,
+    pub toasts: ToastQueue,
 
     pub wgui_globals: WguiGlobals,
 
@@ -61,6 +77,9 @@ pub struct AppState {
     #[cfg(feature = "osc")]
     pub osc_sender: Option<OscSender>,
 
+    #[cfg(feature = "osc")]
+    pub osc_receiver: Option<OscReceiver>,
+
     pub wvr_server: Option<WvrServerState>,
 
     #[cfg(feature = "openxr")]
@@ -87,7 +106,14 @@ impl AppState {
         let mut hid_provider = HidWrapper::new();
 
         #[cfg(feature = "osc")]
-        let osc_sender = crate::subsystem::osc::OscSender::new(session.config.osc_out_port).ok();
+        let osc_sender = crate::subsystem::osc::OscSender::new(&session.config).ok();
+
+        #[cfg(feature = "osc")]
+        let osc_receiver = session.config.osc_in_port.and_then(|port| {
+            OscReceiver::new(port)
+                .log_err("Could not start OSC listener")
+                .ok()
+        });
 
         let wgui_shared = WSharedContext::new(gfx.clone())?;
         let theme = session.config.theme_path.clone();
@@ -154,6 +180,13 @@ impl AppState {
             screens: smallvec![],
             anchor: Affine3A::IDENTITY,
             anchor_grabbed: false,
+            overlay_status: OverlayStatus::default(),
+            variables: VariableStore::default(),
+            system_metrics: SystemMetrics::default(),
+            frame_stats: FrameStats::default(),
+            notification_history: NotificationHistory::default(),
+            do_not_disturb: DoNotDisturb::default(),
+            toasts: ToastQueue::default(),
             wgui_globals: WguiGlobals::new(
                 assets,
                 &lang_provider,
@@ -169,6 +202,8 @@ impl AppState {
 
             #[cfg(feature = "osc")]
             osc_sender,
+            #[cfg(feature = "osc")]
+            osc_receiver,
 
             wvr_server,
 
@@ -192,6 +227,8 @@ pub struct AppSession {
     pub config_dirty: bool,
 
     pub toast_topics: IdMap<ToastTopic, ToastDisplayMethod>,
+    /// Compiled from `config.notification_rules`
+    pub notification_rules: Vec<CompiledRule>,
 }
 
 impl AppSession {
@@ -205,20 +242,33 @@ impl AppSession {
         toast_topics.insert(ToastTopic::Error, ToastDisplayMethod::Center);
         toast_topics.insert(ToastTopic::DesktopNotification, ToastDisplayMethod::Center);
         toast_topics.insert(ToastTopic::XSNotification, ToastDisplayMethod::Center);
+        toast_topics.insert(ToastTopic::OscMessage, ToastDisplayMethod::Center);
 
         config.notification_topics.iter().for_each(|(k, v)| {
             toast_topics.insert(*k, *v);
         });
 
+        let notification_rules = notifications::compile_rules(&config.notification_rules);
+
         Self {
             config,
             toast_topics,
+            notification_rules,
             config_dirty: false,
         }
     }
+
+    /// Call after `config` was changed at runtime.
+    pub fn config_changed(&mut self) {
+        self.config_dirty = true;
+        self.notification_rules = notifications::compile_rules(&self.config.notification_rules);
+    }
 }
 
 pub struct ScreenMeta {
     pub name: Arc<str>,
     pub native_handle: u32,
+    /// Position and size on the desktop, in logical pixels
+    pub logical_pos: Vec2,
+    pub logical_size: Vec2,
 }
@@ -6,11 +6,16 @@ use dbus::{
     arg::{PropMap, Variant},
     blocking::Connection,
     channel::MatchingReceiver,
-    message::MatchRule,
+    message::{MatchRule, SignalArgs},
+    strings::BusName,
 };
 
 use crate::subsystem::dbus::{
-    fcitx5::OrgFcitxFcitxController1, notifications::OrgFreedesktopNotifications,
+    fcitx5::OrgFcitxFcitxController1,
+    notifications::{
+        OrgFreedesktopNotifications, OrgFreedesktopNotificationsActionInvoked,
+        OrgFreedesktopNotificationsNotificationClosed,
+    },
 };
 
 mod fcitx5;
@@ -19,6 +24,9 @@ mod notifications;
 pub type DbusReceiveCallback = Box<dyn FnMut(Message, &Connection) -> bool + Send>;
 pub type DbusMatchCallback = Box<dyn FnMut((), &Connection, &Message) -> bool + Send>;
 
+/// `NotificationClosed` reason for notifications dismissed by the user
+const NOTIFICATION_CLOSED_DISMISSED: u32 = 2;
+
 #[derive(Default)]
 pub struct DbusConnector {
     pub connection: Option<Connection>,
@@ -31,9 +39,10 @@ impl DbusConnector {
         }
     }
 
+    /// Receives every message that matches any of `rules` on `callback`.
     pub fn become_monitor(
         &mut self,
-        rule: MatchRule<'static>,
+        rules: &[MatchRule<'static>],
         callback: DbusReceiveCallback,
     ) -> anyhow::Result<()> {
         let connection = self
@@ -50,12 +59,16 @@ impl DbusConnector {
         let result: Result<(), dbus::Error> = proxy.method_call(
             "org.freedesktop.DBus.Monitoring",
             "BecomeMonitor",
-            (vec![rule.match_str()], 0u32),
+            (
+                rules.iter().map(MatchRule::match_str).collect::<Vec<_>>(),
+                0u32,
+            ),
         );
 
         result?;
 
-        let _ = connection.start_receive(rule, callback);
+        // the bus already filters by the monitor rules
+        let _ = connection.start_receive(MatchRule::new(), callback);
 
         self.connection = Some(connection);
         Ok(())
@@ -135,4 +148,54 @@ impl DbusConnector {
         proxy.close_notification(id)?;
         Ok(())
     }
+
+    /// Unique bus name of the current owner of a well-known name.
+    pub fn name_owner(name: &str) -> anyhow::Result<String> {
+        let connection = Connection::new_session()?;
+        let proxy = connection.with_proxy(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            Duration::from_millis(500),
+        );
+
+        let (owner,): (String,) =
+            proxy.method_call("org.freedesktop.DBus", "GetNameOwner", (name,))?;
+        Ok(owner)
+    }
+
+    /// Tells the application that sent notification `id` that one of its actions was clicked,
+    /// the way the notification server would: `ActionInvoked` followed by `NotificationClosed`.
+    /// Both signals are sent only to `destination`, the unique bus name of the application.
+    /// The notification is then closed on the desktop as well.
+    pub fn notify_action_invoked(
+        destination: &str,
+        id: u32,
+        action_key: &str,
+    ) -> anyhow::Result<()> {
+        let connection = Connection::new_session()?;
+        let destination = BusName::new(destination.to_string()).map_err(anyhow::Error::msg)?;
+        let path = dbus::Path::from("/org/freedesktop/Notifications");
+
+        let invoked = OrgFreedesktopNotificationsActionInvoked {
+            id,
+            action_key: action_key.to_string(),
+        }
+        .to_emit_message(&path);
+        let closed = OrgFreedesktopNotificationsNotificationClosed {
+            id,
+            reason: NOTIFICATION_CLOSED_DISMISSED,
+        }
+        .to_emit_message(&path);
+
+        for mut msg in [invoked, closed] {
+            msg.set_destination(Some(destination.clone()));
+            connection
+                .channel()
+                .send(msg)
+                .map_err(|()| anyhow::anyhow!("Could not send notification signal"))?;
+        }
+        connection.channel().flush();
+
+        Self::notify_close(id)
+    }
 }
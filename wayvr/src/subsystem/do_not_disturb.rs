@@ -0,0 +1,219 @@
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveTime};
+use wayvr_ipc::packet_server::WlxEvent;
+use wlx_common::{config::DoNotDisturbSchedule, overlays::ToastTopic};
+
+use crate::{
+    backend::task::{OverlayTask, TaskType},
+    ipc::signal::WayVRSignal,
+    overlays::{
+        toast::{Toast, dismiss_toasts},
+        watch::WATCH_NAME,
+    },
+    state::AppState,
+    windowing::{OverlaySelector, backend::OverlayEventData},
+};
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Do-not-disturb mode. While active, external notifications are only recorded in
+/// the notification center. A summary toast is shown once it ends.
+///
+/// Active during `do_not_disturb_schedule` or while one of `do_not_disturb_apps` is focused,
+/// unless turned on or off manually since either of them last changed.
+#[derive(Default)]
+pub struct DoNotDisturb {
+    /// Overrides the schedule and focused app until one of them changes
+    manual: Option<bool>,
+    scheduled: bool,
+    app_focused: bool,
+    /// App names of notifications that arrived while active
+    queued: Vec<String>,
+    next_update: Option<Instant>,
+    schedule_error: bool,
+}
+
+impl DoNotDisturb {
+    pub const fn is_active(&self) -> bool {
+        match self.manual {
+            Some(manual) => manual,
+            None => self.is_triggered(),
+        }
+    }
+
+    const fn is_triggered(&self) -> bool {
+        self.scheduled || self.app_focused
+    }
+
+    /// Whether the toast should be held back. Only external notifications are affected.
+    pub fn intercept(&mut self, toast: &Toast) -> bool {
+        if !self.is_active()
+            || !matches!(
+                toast.topic,
+                ToastTopic::DesktopNotification | ToastTopic::XSNotification
+            )
+        {
+            return false;
+        }
+
+        self.queued.push(
+            toast
+                .app_name
+                .clone()
+                .unwrap_or_else(|| toast.topic.as_ref().to_string()),
+        );
+        true
+    }
+
+    fn is_scheduled(&mut self, schedule: Option<&DoNotDisturbSchedule>) -> bool {
+        let Some(schedule) = schedule else {
+            return false;
+        };
+
+        let parse = |s: &str| NaiveTime::parse_from_str(s, "%H:%M");
+        let (start, end) = match (parse(&schedule.start), parse(&schedule.end)) {
+            (Ok(start), Ok(end)) => (start, end),
+            (Err(e), _) | (_, Err(e)) => {
+                if !self.schedule_error {
+                    log::warn!("Invalid do_not_disturb_schedule, expected HH:MM: {e}");
+                }
+                self.schedule_error = true;
+                return false;
+            }
+        };
+        self.schedule_error = false;
+
+        let now = Local::now().time();
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+/// Turns do-not-disturb on or off until the schedule or the focused app changes.
+/// `None` toggles whether it's currently active.
+pub fn set_manual(app: &mut AppState, enabled: Option<bool>) {
+    let was_active = app.do_not_disturb.is_active();
+    app.do_not_disturb.manual = Some(enabled.unwrap_or(!was_active));
+    state_changed(app, was_active);
+}
+
+/// Re-evaluates the schedule and the focused Monado client. Call every frame.
+pub fn update(app: &mut AppState) {
+    if app
+        .do_not_disturb
+        .next_update
+        .is_some_and(|at| at > Instant::now())
+    {
+        return;
+    }
+    app.do_not_disturb.next_update = Some(Instant::now() + UPDATE_INTERVAL);
+
+    let was_active = app.do_not_disturb.is_active();
+    let was_triggered = app.do_not_disturb.is_triggered();
+    app.do_not_disturb.scheduled = app
+        .do_not_disturb
+        .is_scheduled(app.session.config.do_not_disturb_schedule.as_ref());
+    app.do_not_disturb.app_focused = is_listed_app_focused(app);
+    if app.do_not_disturb.is_triggered() != was_triggered {
+        app.do_not_disturb.manual = None;
+    }
+    state_changed(app, was_active);
+}
+
+#[cfg(feature = "openxr")]
+fn is_listed_app_focused(app: &mut AppState) -> bool {
+    let apps = &app.session.config.do_not_disturb_apps;
+    if apps.is_empty() {
+        return false;
+    }
+
+    let Some(monado) = &mut app.monado else {
+        return false;
+    };
+
+    let clients = match monado.clients() {
+        Ok(clients) => clients,
+        Err(e) => {
+            log::warn!("Failed to get clients from Monado: {e}");
+            return false;
+        }
+    };
+
+    clients.into_iter().any(|mut client| {
+        client
+            .state()
+            .is_ok_and(|s| s.contains(libmonado::ClientState::ClientSessionFocused))
+            && client.name().is_ok_and(|name| apps.contains(&name))
+    })
+}
+
+#[cfg(not(feature = "openxr"))]
+const fn is_listed_app_focused(_app: &mut AppState) -> bool {
+    false
+}
+
+fn state_changed(app: &mut AppState, was_active: bool) {
+    let active = app.do_not_disturb.is_active();
+    if active == was_active {
+        return;
+    }
+
+    log::info!(
+        "Do not disturb {}",
+        if active { "enabled" } else { "disabled" }
+    );
+
+    app.wayvr_signals
+        .send(WayVRSignal::BroadcastEvent(WlxEvent::DoNotDisturbChanged {
+            active,
+        }));
+
+    app.tasks.enqueue(TaskType::Overlay(OverlayTask::Modify(
+        OverlaySelector::Name(WATCH_NAME.into()),
+        Box::new(move |app, config| {
+            let _ = config
+                .backend
+                .notify(app, OverlayEventData::DoNotDisturbChanged(active))
+                .inspect_err(|e| log::warn!("Could not update watch: {e:?}"));
+        }),
+    )));
+
+    if active {
+        // a sticky toast could otherwise stay up for the whole session
+        dismiss_toasts(app);
+        return;
+    }
+
+    let queued = std::mem::take(&mut app.do_not_disturb.queued);
+    if queued.is_empty() {
+        return;
+    }
+
+    // app names in order of first appearance, with counts
+    let mut apps: Vec<(String, usize)> = vec![];
+    for name in &queued {
+        match apps.iter_mut().find(|(n, _)| n == name) {
+            Some((_, count)) => *count += 1,
+            None => apps.push((name.clone(), 1)),
+        }
+    }
+    let apps = apps
+        .iter()
+        .map(|(name, count)| format!("{name} ({count})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+        .replace(';', ","); // translation argument separator
+
+    Toast::new(
+        ToastTopic::System,
+        "DO_NOT_DISTURB.SUMMARY_TITLE".into(),
+        format!("DO_NOT_DISTURB.SUMMARY_BODY;{};{apps}", queued.len()),
+    )
+    .with_timeout(8.)
+    .with_sound(true)
+    .submit(app);
+}
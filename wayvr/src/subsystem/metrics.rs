@@ -0,0 +1,353 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use sysinfo::{Networks, System};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// hwmon drivers that report the CPU package temperature as `temp1`
+const CPU_HWMON_NAMES: [&str; 4] = ["k10temp", "coretemp", "zenpower", "cpu_thermal"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Metric {
+    /// percent
+    CpuLoad,
+    /// °C
+    CpuTemp,
+    /// percent, AMD only
+    GpuLoad,
+    /// °C
+    GpuTemp,
+    /// GiB
+    RamUsed,
+    /// GiB
+    RamTotal,
+    /// percent
+    RamPercent,
+    /// bytes per second, all interfaces
+    NetDown,
+    NetUp,
+    /// frames per second of WayVR itself
+    Fps,
+    /// milliseconds spent on each WayVR frame
+    FrameTime,
+}
+
+impl Metric {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "cpu" => Self::CpuLoad,
+            "cpu_temp" => Self::CpuTemp,
+            "gpu" => Self::GpuLoad,
+            "gpu_temp" => Self::GpuTemp,
+            "ram_used" => Self::RamUsed,
+            "ram_total" => Self::RamTotal,
+            "ram" => Self::RamPercent,
+            "net_down" => Self::NetDown,
+            "net_up" => Self::NetUp,
+            "fps" => Self::Fps,
+            "frame_ms" => Self::FrameTime,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+struct Wanted {
+    cpu: bool,
+    cpu_temp: bool,
+    gpu: bool,
+    ram: bool,
+    net: bool,
+}
+
+impl Wanted {
+    const fn add(&mut self, metric: Metric) {
+        match metric {
+            Metric::CpuLoad => self.cpu = true,
+            Metric::CpuTemp => self.cpu_temp = true,
+            Metric::GpuLoad | Metric::GpuTemp => self.gpu = true,
+            Metric::RamUsed | Metric::RamTotal | Metric::RamPercent => self.ram = true,
+            Metric::NetDown | Metric::NetUp => self.net = true,
+            Metric::Fps | Metric::FrameTime => {}
+        }
+    }
+}
+
+/// System load, sampled at most once per second and only for metrics that labels ask for.
+pub struct SystemMetrics {
+    system: System,
+    networks: Option<Networks>,
+    cpu_temp_path: Option<Option<PathBuf>>,
+    gpu_paths: Option<GpuPaths>,
+    /// Metrics that were requested at least once
+    wanted: Wanted,
+    last_refresh: Instant,
+    /// Incremented on every refresh, so that labels know when to re-render.
+    generation: u64,
+
+    cpu_load: f32,
+    cpu_temp: Option<f32>,
+    gpu_load: Option<f32>,
+    gpu_temp: Option<f32>,
+    ram_used: u64,
+    ram_total: u64,
+    net_down: f64,
+    net_up: f64,
+}
+
+#[derive(Default)]
+struct GpuPaths {
+    busy_percent: Option<PathBuf>,
+    temp: Option<PathBuf>,
+}
+
+impl Default for SystemMetrics {
+    fn default() -> Self {
+        Self {
+            system: System::new(),
+            networks: None,
+            cpu_temp_path: None,
+            gpu_paths: None,
+            wanted: Wanted::default(),
+            last_refresh: Instant::now(),
+            generation: 0,
+            cpu_load: 0.,
+            cpu_temp: None,
+            gpu_load: None,
+            gpu_temp: None,
+            ram_used: 0,
+            ram_total: 0,
+            net_down: 0.,
+            net_up: 0.,
+        }
+    }
+}
+
+impl SystemMetrics {
+    /// Marks the metrics as wanted and refreshes them if due.
+    /// Returns the current generation.
+    pub fn request(&mut self, metrics: &[Metric]) -> u64 {
+        let before = self.wanted;
+        for metric in metrics {
+            self.wanted.add(*metric);
+        }
+
+        // sample newly wanted metrics right away
+        if before != self.wanted || self.last_refresh.elapsed() >= REFRESH_INTERVAL {
+            self.refresh();
+        }
+        self.generation
+    }
+
+    fn refresh(&mut self) {
+        let elapsed = self.last_refresh.elapsed().as_secs_f64().max(0.001);
+        self.last_refresh = Instant::now();
+        self.generation += 1;
+
+        let wanted = self.wanted;
+
+        if wanted.cpu {
+            // usage is relative to the previous refresh, so the first value is 0
+            self.system.refresh_cpu_usage();
+            self.cpu_load = self.system.global_cpu_usage();
+        }
+
+        if wanted.ram {
+            self.system.refresh_memory();
+            self.ram_used = self.system.used_memory();
+            self.ram_total = self.system.total_memory();
+        }
+
+        if wanted.net {
+            if let Some(networks) = self.networks.as_mut() {
+                networks.refresh(true);
+                let (down, up) = networks.values().fold((0, 0), |(down, up), data| {
+                    (down + data.received(), up + data.transmitted())
+                });
+                self.net_down = down as f64 / elapsed;
+                self.net_up = up as f64 / elapsed;
+            } else {
+                self.networks = Some(Networks::new_with_refreshed_list());
+            }
+        }
+
+        if wanted.cpu_temp {
+            let path = self.cpu_temp_path.get_or_insert_with(find_cpu_temp_path);
+            self.cpu_temp = path.as_deref().and_then(read_millidegrees);
+        }
+
+        if wanted.gpu {
+            let paths = self.gpu_paths.get_or_insert_with(find_gpu_paths);
+            self.gpu_load = paths
+                .busy_percent
+                .as_deref()
+                .and_then(read_number)
+                .map(|v| v as f32);
+            self.gpu_temp = paths.temp.as_deref().and_then(read_millidegrees);
+        }
+    }
+
+    /// Formats a metric for display. Unavailable values are shown as `-`.
+    pub fn format(&self, metric: Metric, frame_stats: &FrameStats) -> String {
+        const GIB: f64 = 1024. * 1024. * 1024.;
+
+        let opt = |v: Option<f32>| v.map_or_else(|| "-".into(), |v| format!("{v:.0}"));
+
+        match metric {
+            Metric::CpuLoad => format!("{:.0}", self.cpu_load),
+            Metric::CpuTemp => opt(self.cpu_temp),
+            Metric::GpuLoad => opt(self.gpu_load),
+            Metric::GpuTemp => opt(self.gpu_temp),
+            Metric::RamUsed => format!("{:.1}", self.ram_used as f64 / GIB),
+            Metric::RamTotal => format!("{:.1}", self.ram_total as f64 / GIB),
+            Metric::RamPercent => {
+                if self.ram_total == 0 {
+                    "-".into()
+                } else {
+                    format!("{:.0}", self.ram_used as f64 * 100. / self.ram_total as f64)
+                }
+            }
+            Metric::NetDown => format_rate(self.net_down),
+            Metric::NetUp => format_rate(self.net_up),
+            Metric::Fps => format!("{:.0}", frame_stats.fps()),
+            Metric::FrameTime => format!("{:.1}", frame_stats.frame_ms()),
+        }
+    }
+}
+
+fn format_rate(bytes_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "KB/s", "MB/s", "GB/s"];
+
+    let mut value = bytes_per_sec;
+    let mut unit = 0;
+    while value >= 1000. && unit < UNITS.len() - 1 {
+        value /= 1000.;
+        unit += 1;
+    }
+
+    if unit == 0 || value >= 100. {
+        format!("{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn read_number(path: &Path) -> Option<i64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    read_number(path).map(|v| v as f32 / 1000.)
+}
+
+fn find_cpu_temp_path() -> Option<PathBuf> {
+    let path = fs::read_dir("/sys/class/hwmon")
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| {
+            fs::read_to_string(path.join("name"))
+                .is_ok_and(|name| CPU_HWMON_NAMES.contains(&name.trim()))
+        })
+        .map(|path| path.join("temp1_input"));
+
+    if path.is_none() {
+        log::warn!("Could not find a CPU temperature sensor in /sys/class/hwmon");
+    }
+    path
+}
+
+/// Uses the first card that reports `gpu_busy_percent`, which is AMD only.
+fn find_gpu_paths() -> GpuPaths {
+    let Ok(cards) = fs::read_dir("/sys/class/drm") else {
+        return GpuPaths::default();
+    };
+
+    let mut devices: Vec<_> = cards
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("card") && !name.contains('-')
+        })
+        .map(|entry| entry.path().join("device"))
+        .collect();
+    devices.sort();
+
+    let Some(device) = devices
+        .iter()
+        .find(|device| device.join("gpu_busy_percent").exists())
+        .or_else(|| devices.first())
+    else {
+        log::warn!("Could not find a GPU in /sys/class/drm");
+        return GpuPaths::default();
+    };
+
+    let busy_percent = Some(device.join("gpu_busy_percent")).filter(|p| p.exists());
+    let temp = fs::read_dir(device.join("hwmon"))
+        .ok()
+        .and_then(|mut dirs| dirs.find_map(Result::ok))
+        .map(|entry| entry.path().join("temp1_input"))
+        .filter(|p| p.exists());
+
+    GpuPaths { busy_percent, temp }
+}
+
+/// Timing of WayVR's own frames.
+pub struct FrameStats {
+    frame_start: Instant,
+    in_frame: bool,
+    /// smoothed, in seconds
+    interval: f32,
+    /// smoothed, in seconds
+    work: f32,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            frame_start: Instant::now(),
+            in_frame: false,
+            interval: 0.,
+            work: 0.,
+        }
+    }
+}
+
+impl FrameStats {
+    const SMOOTHING: f32 = 0.05;
+
+    /// Call once the runtime lets us start working on a frame.
+    pub fn begin_frame(&mut self) {
+        let interval = self.frame_start.elapsed().as_secs_f32();
+        self.interval += (interval - self.interval) * Self::SMOOTHING;
+        self.frame_start = Instant::now();
+        self.in_frame = true;
+    }
+
+    /// Call when the frame is done, before waiting for the next one.
+    pub fn end_frame(&mut self) {
+        if !self.in_frame {
+            return;
+        }
+        let work = self.frame_start.elapsed().as_secs_f32();
+        self.work += (work - self.work) * Self::SMOOTHING;
+        self.in_frame = false;
+    }
+
+    pub fn fps(&self) -> f32 {
+        if self.interval > 0. {
+            1. / self.interval
+        } else {
+            0.
+        }
+    }
+
+    pub fn frame_ms(&self) -> f32 {
+        self.work * 1000.
+    }
+}
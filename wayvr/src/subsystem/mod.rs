@@ -1,7 +1,10 @@
 pub mod dbus;
+pub mod do_not_disturb;
 pub mod hid;
 pub mod input;
+pub mod metrics;
 pub mod notifications;
+pub mod variables;
 
 #[cfg(feature = "osc")]
 pub mod osc;
@@ -1,7 +1,14 @@
 use anyhow::Context;
-use dbus::message::MatchRule;
+use base64::Engine;
+use chrono::{DateTime, Local};
+use dbus::{
+    arg::{PropMap, RefArg},
+    message::{MatchRule, MessageType},
+};
+use regex::Regex;
 use serde::Deserialize;
 use std::{
+    collections::VecDeque,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -9,13 +16,33 @@ use std::{
     },
     time::Duration,
 };
-use wlx_common::overlays::ToastTopic;
+use wlx_common::{
+    config::NotificationRule,
+    overlays::{ToastTopic, ToastUrgency},
+};
+
+use crate::{
+    overlays::toast::{Toast, ToastIcon},
+    state::AppState,
+    subsystem::dbus::DbusConnector,
+};
 
-use crate::{overlays::toast::Toast, state::AppState, subsystem::dbus::DbusConnector};
+const DBUS_NAME: &str = "org.freedesktop.Notifications";
+const HISTORY_PER_TOPIC: usize = 50;
+
+enum Incoming {
+    Toast(Toast),
+    /// The notification daemon's reply to a `Notify` call
+    Reply {
+        caller: String,
+        serial: u32,
+        id: u32,
+    },
+}
 
 pub struct NotificationManager {
-    rx_toast: mpsc::Receiver<Toast>,
-    tx_toast: mpsc::SyncSender<Toast>,
+    rx_toast: mpsc::Receiver<Incoming>,
+    tx_toast: mpsc::SyncSender<Incoming>,
     running: Arc<AtomicBool>,
 }
 
@@ -30,32 +57,47 @@ impl NotificationManager {
     }
 
     pub fn submit_pending(&self, app: &mut AppState) {
-        if app.session.config.notifications_enabled {
-            self.rx_toast.try_iter().for_each(|toast| {
-                toast.submit(app);
-            });
-        } else {
-            // consume without submitting
-            self.rx_toast.try_iter().last();
+        for incoming in self.rx_toast.try_iter() {
+            match incoming {
+                Incoming::Toast(toast) => {
+                    // consume without submitting if disabled
+                    if app.session.config.notifications_enabled {
+                        toast.submit(app);
+                    }
+                }
+                Incoming::Reply { caller, serial, id } => {
+                    app.notification_history.set_dbus_id(&caller, serial, id);
+                }
+            }
         }
     }
 
     pub fn run_dbus(&mut self, dbus: &mut DbusConnector) {
         let rule = MatchRule::new_method_call()
             .with_member("Notify")
-            .with_interface("org.freedesktop.Notifications")
+            .with_interface(DBUS_NAME)
             .with_path("/org/freedesktop/Notifications");
 
+        // replies carry the notification IDs, which are needed to invoke actions
+        let reply_rule = DbusConnector::name_owner(DBUS_NAME)
+            .inspect_err(|e| log::warn!("No notification daemon, actions won't work: {e:?}"))
+            .ok()
+            .map(|owner| {
+                MatchRule::new()
+                    .with_type(MessageType::MethodReturn)
+                    .with_sender(owner)
+            });
+
+        let rules: Vec<_> = std::iter::once(rule.clone())
+            .chain(reply_rule.clone())
+            .collect();
+
         let sender = self.tx_toast.clone();
         if dbus
             .become_monitor(
-                rule.clone(),
+                &rules,
                 Box::new(move |msg, _| {
-                    if let Ok(toast) = parse_dbus(&msg) {
-                        let _ = sender
-                            .try_send(toast)
-                            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
-                    }
+                    handle_dbus(&msg, &sender);
                     true
                 }),
             )
@@ -67,21 +109,21 @@ impl NotificationManager {
             return;
         }
 
-        let sender = self.tx_toast.clone();
-        let _ = dbus
-            .add_match(
-                rule.with_eavesdrop(),
-                Box::new(move |(), _, msg| {
-                    if let Ok(toast) = parse_dbus(msg) {
-                        let _ = sender
-                            .try_send(toast)
-                            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
-                    }
-                    true
-                }),
-            )
-            .context("Failed to register D-Bus notifications. Desktop notifications won't work.")
-            .inspect_err(|e| log::warn!("{e:?}"));
+        for rule in rules {
+            let sender = self.tx_toast.clone();
+            let _ = dbus
+                .add_match(
+                    rule.with_eavesdrop(),
+                    Box::new(move |(), _, msg| {
+                        handle_dbus(msg, &sender);
+                        true
+                    }),
+                )
+                .context(
+                    "Failed to register D-Bus notifications. Desktop notifications won't work.",
+                )
+                .inspect_err(|e| log::warn!("{e:?}"));
+        }
     }
 
     pub fn run_udp(&mut self) {
@@ -100,7 +142,7 @@ impl NotificationManager {
                 log::error!("Failed to set read timeout: {err:?}");
             }
 
-            let mut buf = [0u8; 1024 * 16]; // vrcx embeds icons as b64
+            let mut buf = vec![0u8; u16::MAX as usize]; // vrcx embeds icons as b64
 
             while running.load(Ordering::Relaxed) {
                 if let Ok((num_bytes, _)) = socket.recv_from(&mut buf) {
@@ -123,15 +165,19 @@ impl NotificationManager {
                         continue;
                     }
 
+                    let icon = xso_icon(&msg);
                     let toast = Toast::new(
                         ToastTopic::XSNotification,
                         msg.title,
                         msg.content.unwrap_or(String::new()),
                     )
                     .with_timeout(msg.timeout.unwrap_or(5.))
+                    .with_opacity(msg.opacity.unwrap_or(1.))
+                    .with_height(msg.height)
+                    .with_app(msg.sourceApp, icon)
                     .with_sound(msg.volume.unwrap_or(-1.) >= 0.); // XSOverlay still plays at 0,
 
-                    match sender.try_send(toast) {
+                    match sender.try_send(Incoming::Toast(toast)) {
                         Ok(()) => {}
                         Err(e) => {
                             log::error!("Failed to send notification: {e:?}");
@@ -150,24 +196,361 @@ impl Drop for NotificationManager {
     }
 }
 
+fn handle_dbus(msg: &dbus::Message, sender: &mpsc::SyncSender<Incoming>) {
+    let incoming = match msg.msg_type() {
+        MessageType::MethodCall => parse_dbus(msg).map(Incoming::Toast).ok(),
+        MessageType::MethodReturn => parse_dbus_reply(msg),
+        _ => None,
+    };
+
+    if let Some(incoming) = incoming {
+        let _ = sender
+            .try_send(incoming)
+            .inspect_err(|e| log::error!("Failed to send notification: {e:?}"));
+    }
+}
+
 fn parse_dbus(msg: &dbus::Message) -> anyhow::Result<Toast> {
     let mut args = msg.iter_init();
     let app_name: String = args.read()?;
     let _replaces_id: u32 = args.read()?;
-    let _app_icon: String = args.read()?;
+    let app_icon: String = args.read()?;
     let summary: String = args.read()?;
     let body: String = args.read()?;
+    let actions: Vec<String> = args.read().unwrap_or_default();
+    let hints: PropMap = args.read().unwrap_or_default();
 
     let title = if summary.is_empty() {
-        app_name
+        app_name.clone()
     } else {
         summary
     };
 
-    Ok(Toast::new(ToastTopic::DesktopNotification, title, body)
+    // flat list of key, label pairs
+    let actions = actions
+        .chunks_exact(2)
+        .map(|pair| NotificationAction {
+            key: pair[0].clone(),
+            label: if pair[1].is_empty() {
+                pair[0].clone()
+            } else {
+                pair[1].clone()
+            },
+        })
+        .collect();
+
+    let origin = msg.sender().map(|caller| DbusOrigin {
+        caller: caller.to_string(),
+        serial: msg.get_serial().unwrap_or_default(),
+        id: None,
+    });
+
+    let mut toast = Toast::new(ToastTopic::DesktopNotification, title, body)
         .with_timeout(5.0)
-        .with_opacity(1.0))
+        .with_opacity(1.0)
+        .with_urgency(dbus_urgency(&hints))
+        .with_app(
+            Some(app_name).filter(|s| !s.is_empty()),
+            dbus_icon(&hints, app_icon),
+        );
     // leave the audio part to the desktop env
+
+    if let Some(origin) = origin {
+        toast = toast.with_actions(origin, actions);
+    }
+    Ok(toast)
+}
+
+fn dbus_urgency(hints: &PropMap) -> ToastUrgency {
+    match hints.get("urgency").and_then(|v| v.0.as_u64()) {
+        Some(0) => ToastUrgency::Low,
+        Some(2) => ToastUrgency::Critical,
+        _ => ToastUrgency::Normal,
+    }
+}
+
+/// In order of precedence: `image-data`, `image-path`, then `app_icon`.
+fn dbus_icon(hints: &PropMap, app_icon: String) -> Option<ToastIcon> {
+    // older names from previous spec versions
+    let image_data = ["image-data", "image_data", "icon_data"]
+        .iter()
+        .find_map(|key| hints.get(*key));
+
+    if let Some(image_data) = image_data {
+        match parse_image_data(&*image_data.0) {
+            Some(icon) => return Some(icon),
+            None => log::debug!("Could not parse notification image-data"),
+        }
+    }
+
+    ["image-path", "image_path"]
+        .iter()
+        .find_map(|key| hints.get(*key)?.0.as_str().map(ToString::to_string))
+        .into_iter()
+        .chain(std::iter::once(app_icon))
+        .find(|s| !s.is_empty())
+        .map(ToastIcon::Name)
+}
+
+/// `(iiibiiay)`: width, height, rowstride, has alpha, bits per sample, channels, data
+fn parse_image_data(arg: &dyn RefArg) -> Option<ToastIcon> {
+    let mut fields = arg.as_iter()?;
+    let width = u32::try_from(fields.next()?.as_i64()?).ok()?;
+    let height = u32::try_from(fields.next()?.as_i64()?).ok()?;
+    let rowstride = usize::try_from(fields.next()?.as_i64()?).ok()?;
+    let has_alpha = fields.next()?.as_i64()? != 0;
+    let bits_per_sample = fields.next()?.as_i64()?;
+    let channels = usize::try_from(fields.next()?.as_i64()?).ok()?;
+    let data: Vec<u8> = fields
+        .next()?
+        .as_iter()?
+        .map(|b| b.as_u64().map(|b| b as u8))
+        .collect::<Option<_>>()?;
+
+    let expected_channels = if has_alpha { 4 } else { 3 };
+    if bits_per_sample != 8 || channels != expected_channels {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height as usize {
+        let row = data.get(y * rowstride..)?;
+        for x in 0..width as usize {
+            let px = row.get(x * channels..(x + 1) * channels)?;
+            rgba.extend_from_slice(&px[..3]);
+            rgba.push(if has_alpha { px[3] } else { u8::MAX });
+        }
+    }
+
+    Some(ToastIcon::Rgba {
+        width,
+        height,
+        data: rgba.into(),
+    })
+}
+
+/// Only replies to `Notify` consist of a single `u32`.
+fn parse_dbus_reply(msg: &dbus::Message) -> Option<Incoming> {
+    let id: u32 = msg.read1().ok()?;
+    Some(Incoming::Reply {
+        caller: msg.destination()?.to_string(),
+        serial: msg.get_reply_serial()?,
+        id,
+    })
+}
+
+/// A `NotificationRule` with its `summary` pattern compiled.
+pub struct CompiledRule {
+    rule: NotificationRule,
+    summary: Option<Regex>,
+}
+
+/// Compiles the patterns of `rules`, so that it's not done for every notification.
+/// Rules with an invalid pattern are logged and left out.
+pub fn compile_rules(rules: &[NotificationRule]) -> Vec<CompiledRule> {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, rule)| {
+            let summary = rule
+                .summary
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .inspect_err(|e| {
+                    log::error!(
+                        "Notification rule {} has an invalid summary regex: {e}",
+                        idx + 1
+                    );
+                })
+                .ok()?;
+            Some(CompiledRule {
+                rule: rule.clone(),
+                summary,
+            })
+        })
+        .collect()
+}
+
+/// The first rule in `rules` that matches the toast.
+/// WayVR's own System and Error toasts are never matched.
+pub fn find_rule<'a>(rules: &'a [CompiledRule], toast: &Toast) -> Option<&'a NotificationRule> {
+    if matches!(toast.topic, ToastTopic::System | ToastTopic::Error) {
+        return None;
+    }
+
+    rules
+        .iter()
+        .find(|CompiledRule { rule, summary }| {
+            if let Some(app_name) = rule.app_name.as_deref()
+                && !toast
+                    .app_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(app_name))
+            {
+                return false;
+            }
+
+            if rule.urgency.is_some_and(|urgency| urgency != toast.urgency) {
+                return false;
+            }
+
+            summary.as_ref().is_none_or(|re| re.is_match(&toast.title))
+        })
+        .map(|compiled| &compiled.rule)
+}
+
+#[derive(Clone, Debug)]
+pub struct NotificationAction {
+    pub key: String,
+    pub label: String,
+}
+
+/// Identifies a D-Bus notification, so that its actions can be invoked later.
+#[derive(Clone, Debug)]
+pub struct DbusOrigin {
+    /// Unique bus name of the application
+    pub caller: String,
+    /// Serial of the `Notify` call
+    pub serial: u32,
+    /// Assigned by the notification daemon once it replies
+    pub id: Option<u32>,
+}
+
+pub struct NotificationEntry {
+    pub id: u64,
+    pub topic: ToastTopic,
+    pub app_name: Option<String>,
+    pub icon: Option<ToastIcon>,
+    pub title: String,
+    pub body: String,
+    pub time: DateTime<Local>,
+    pub actions: Vec<NotificationAction>,
+    pub origin: Option<DbusOrigin>,
+}
+
+/// Toasts that were submitted, kept for the notification center.
+/// Bounded to `HISTORY_PER_TOPIC` entries per `ToastTopic`.
+#[derive(Default)]
+pub struct NotificationHistory {
+    /// Oldest first
+    entries: VecDeque<NotificationEntry>,
+    next_id: u64,
+    /// Bumped whenever the entries change
+    revision: u64,
+}
+
+impl NotificationHistory {
+    /// `title` and `body` are passed separately as they may need to be translated.
+    pub fn record(&mut self, toast: &Toast, title: String, body: String) {
+        self.next_id += 1;
+        self.revision += 1;
+        self.entries.push_back(NotificationEntry {
+            id: self.next_id,
+            topic: toast.topic,
+            app_name: toast.app_name.clone(),
+            icon: toast.icon.clone(),
+            title,
+            body,
+            time: Local::now(),
+            actions: toast.actions.clone(),
+            origin: toast.origin.clone(),
+        });
+
+        let count = self
+            .entries
+            .iter()
+            .filter(|e| e.topic == toast.topic)
+            .count();
+        if count > HISTORY_PER_TOPIC
+            && let Some(idx) = self.entries.iter().position(|e| e.topic == toast.topic)
+        {
+            self.entries.remove(idx);
+        }
+    }
+
+    /// Newest first
+    pub fn iter(&self) -> impl Iterator<Item = &NotificationEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub const fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns false if there was no such entry.
+    pub fn dismiss(&mut self, id: u64) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        let removed = len != self.entries.len();
+        if removed {
+            self.revision += 1;
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.revision += 1;
+    }
+
+    /// Invokes an action of a D-Bus notification and removes it from the history.
+    pub fn invoke_action(&mut self, id: u64, action_idx: usize) -> anyhow::Result<()> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .context("No such notification")?;
+        let action = entry
+            .actions
+            .get(action_idx)
+            .context("No such notification action")?;
+        let origin = entry
+            .origin
+            .as_ref()
+            .context("Notification did not come from D-Bus")?;
+        let dbus_id = origin
+            .id
+            .context("Notification daemon did not report an ID for this notification")?;
+
+        DbusConnector::notify_action_invoked(&origin.caller, dbus_id, &action.key)?;
+        self.dismiss(id);
+        Ok(())
+    }
+
+    fn set_dbus_id(&mut self, caller: &str, serial: u32, id: u32) {
+        if let Some(origin) = self
+            .entries
+            .iter_mut()
+            .rev()
+            .filter_map(|e| e.origin.as_mut())
+            .find(|o| o.serial == serial && o.caller == caller)
+        {
+            origin.id = Some(id);
+        }
+    }
+}
+
+/// XSOverlay's built-in icons are not available, so those fall back to the default.
+fn xso_icon(msg: &XsoMessage) -> Option<ToastIcon> {
+    let icon = msg.icon.as_deref().filter(|s| !s.is_empty())?;
+
+    if msg.useBase64Icon.unwrap_or(false) {
+        return base64::engine::general_purpose::STANDARD
+            .decode(icon)
+            .inspect_err(|e| log::debug!("Could not decode notification icon: {e:?}"))
+            .ok()
+            .map(|data| ToastIcon::Encoded(data.into()));
+    }
+
+    match icon {
+        "default" | "error" | "warning" => None,
+        path => Some(ToastIcon::Name(path.to_string())),
+    }
 }
 
 #[allow(dead_code)]
@@ -1,58 +1,88 @@
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
-    time::Instant,
+    io::ErrorKind,
+    net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
 };
 
-use anyhow::bail;
-use rosc::{OscMessage, OscPacket, OscType};
+use anyhow::{Context, bail};
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType};
+use wlx_common::{
+    config::{GeneralConfig, OscInAction, OscInMapping, OscOutParam, OscOutValue, OscOutput},
+    overlays::ToastTopic,
+};
 
 use crate::{
-    backend::input::TrackedDevice,
-    overlays::{keyboard::KEYBOARD_NAME, watch::WATCH_NAME},
-    windowing::manager::OverlayWindowManager,
+    backend::{
+        input::TrackedDevice,
+        task::{ModifyPanelCommand, ModifyPanelTask, OverlayTask, TaskType, ToggleMode},
+    },
+    overlays::{keyboard::KEYBOARD_NAME, toast::Toast, watch::WATCH_NAME},
+    state::AppState,
+    windowing::{OverlaySelector, manager::OverlayWindowManager},
 };
 
 use crate::backend::input::TrackedDeviceRole;
 
+struct OutputState {
+    config: OscOutput,
+    targets: Vec<SocketAddr>,
+    interval: Duration,
+    last_sent: Instant,
+}
+
 pub struct OscSender {
-    last_sent_overlay: Instant,
-    last_sent_device: Instant,
+    outputs: Vec<OutputState>,
+    /// Where `send_message` goes, e.g. for `::OscSend` buttons.
+    default_target: SocketAddr,
     upstream: UdpSocket,
 }
 
 impl OscSender {
-    pub fn new(send_port: u16) -> anyhow::Result<Self> {
-        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
-
+    pub fn new(config: &GeneralConfig) -> anyhow::Result<Self> {
         let Ok(upstream) = UdpSocket::bind("0.0.0.0:0") else {
             bail!("Failed to bind UDP socket - OSC will not function.");
         };
 
-        let Ok(()) = upstream.connect(SocketAddr::new(ip, send_port)) else {
-            bail!("Failed to connect UDP socket - OSC will not function.");
-        };
+        let default_target = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), config.osc_out_port);
+
+        let outputs = config
+            .osc_outputs
+            .clone()
+            .unwrap_or_else(|| default_outputs(config.osc_out_port))
+            .into_iter()
+            .map(|output| {
+                let targets = output
+                    .targets
+                    .iter()
+                    .filter_map(|t| {
+                        t.to_socket_addrs()
+                            .inspect_err(|e| log::error!("Invalid OSC target '{t}': {e}"))
+                            .ok()?
+                            .next()
+                    })
+                    .collect();
+
+                OutputState {
+                    targets,
+                    interval: Duration::from_millis(output.interval_ms.into()),
+                    last_sent: Instant::now(),
+                    config: output,
+                }
+            })
+            .collect();
 
         Ok(Self {
+            outputs,
+            default_target,
             upstream,
-            last_sent_overlay: Instant::now(),
-            last_sent_device: Instant::now(),
         })
     }
 
     pub fn send_message(&self, addr: String, args: Vec<OscType>) -> anyhow::Result<()> {
         let packet = OscPacket::Message(OscMessage { addr, args });
-        let Ok(bytes) = rosc::encoder::encode(&packet) else {
-            bail!("Could not encode OSC packet.");
-        };
-
-        let Ok(_) = self.upstream.send(&bytes) else {
-            bail!("Could not send OSC packet.");
-        };
-
-        Ok(())
+        send_packet(&self.upstream, &packet, self.default_target)
     }
 
-    #[allow(clippy::too_many_lines)]
     pub fn send_params<D>(
         &mut self,
         overlay_manager: &OverlayWindowManager<D>,
@@ -61,164 +91,417 @@ impl OscSender {
     where
         D: Default,
     {
-        // send overlay parameters every 0.1 seconds
-        if self.last_sent_overlay.elapsed().as_millis() >= 100 {
-            self.last_sent_overlay = Instant::now();
-
-            let edit_mode = overlay_manager.get_edit_mode();
-            let current_set = overlay_manager.get_current_set().unwrap_or(0) as i32;
-            let total_sets = overlay_manager.get_total_sets() as i32;
-
-            // check state of each active overlay and count them
-            let mut num_overlays = 0;
-            let mut has_keyboard = false;
-            let mut has_wrist = false;
-            for o in overlay_manager.values() {
-                let Some(state) = o.config.active_state.as_ref() else {
-                    continue;
-                };
-
-                // skip overlays that are fully transparent; e.g. the watch when not looking at it
-                if state.alpha <= 0.05 {
-                    continue;
-                }
+        if !self
+            .outputs
+            .iter()
+            .any(|o| o.last_sent.elapsed() >= o.interval)
+        {
+            return Ok(());
+        }
+
+        let overlays = OverlayValues::collect(overlay_manager);
+        let devices = DeviceValues::collect(devices);
 
-                match o.config.name.as_ref() {
-                    WATCH_NAME => has_wrist = true,
-                    KEYBOARD_NAME => has_keyboard = true,
-                    _ => {
-                        if state.interactable {
-                            num_overlays += 1;
-                        }
+        let mut result = Ok(());
+        for output in &mut self.outputs {
+            if output.last_sent.elapsed() < output.interval {
+                continue;
+            }
+            output.last_sent = Instant::now();
+
+            let messages: Vec<_> = output
+                .config
+                .params
+                .iter()
+                .flat_map(|param| param_messages(param, &overlays, &devices))
+                .collect();
+
+            let packets = if output.config.bundle {
+                vec![OscPacket::Bundle(OscBundle {
+                    // immediately
+                    timetag: OscTime {
+                        seconds: 0,
+                        fractional: 1,
+                    },
+                    content: messages.into_iter().map(OscPacket::Message).collect(),
+                })]
+            } else {
+                messages.into_iter().map(OscPacket::Message).collect()
+            };
+
+            for target in &output.targets {
+                for packet in &packets {
+                    if let Err(e) = send_packet(&self.upstream, packet, *target) {
+                        result = Err(e);
                     }
                 }
             }
+        }
 
-            // overlays
-            self.send_message(
-                "/avatar/parameters/isOverlayOpen".into(),
-                vec![OscType::Bool(num_overlays > 0)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/ToggleWindows".into(),
-                vec![OscType::Bool(num_overlays > 0)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/openOverlayCount".into(),
-                vec![OscType::Int(num_overlays)],
-            )?;
-
-            // working sets
-            self.send_message(
-                "/avatar/parameters/isEditModeActive".into(),
-                vec![OscType::Bool(edit_mode)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/ToggleEditMode".into(),
-                vec![OscType::Bool(edit_mode)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/currentWorkingSet".into(),
-                vec![OscType::Int(current_set)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/CurrentProfile".into(),
-                vec![OscType::Int(current_set)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/totalWorkingSets".into(),
-                vec![OscType::Int(total_sets)],
-            )?;
-
-            // keyboard
-            self.send_message(
-                "/avatar/parameters/isKeyboardOpen".into(),
-                vec![OscType::Bool(has_keyboard)],
-            )?;
-            self.send_message(
-                "/avatar/parameters/ToggleKeyboard".into(),
-                vec![OscType::Bool(has_keyboard)],
-            )?;
-
-            // watch
-            self.send_message(
-                "/avatar/parameters/isWristVisible".into(),
-                vec![OscType::Bool(has_wrist)],
-            )?;
-        }
-
-        // send device parameters every 10 seconds
-        if self.last_sent_device.elapsed().as_millis() >= 10000 {
-            self.last_sent_device = Instant::now();
-
-            let mut tracker_count: i8 = 0;
-            let mut controller_count: i8 = 0;
-            let mut tracker_total_bat = 0.0;
-            let mut controller_total_bat = 0.0;
-
-            let mut lowest_battery = 1f32;
-
-            for device in devices {
-                let tracker_param;
-
-                // soc is the battery level (set to device status.charge)
-                let level = device.soc.unwrap_or(-1.0);
-                let parameter = match device.role {
-                    TrackedDeviceRole::None => continue,
-                    TrackedDeviceRole::Hmd => "hmd",
-                    TrackedDeviceRole::LeftHand => {
-                        controller_count += 1;
-                        controller_total_bat += level;
-                        "leftController"
-                    }
-                    TrackedDeviceRole::RightHand => {
-                        controller_count += 1;
-                        controller_total_bat += level;
-                        "rightController"
-                    }
-                    TrackedDeviceRole::Tracker => {
-                        tracker_count += 1;
-                        tracker_total_bat += level;
-                        tracker_param = format!("tracker{tracker_count}");
-                        tracker_param.as_str()
+        result
+    }
+}
+
+fn send_packet(upstream: &UdpSocket, packet: &OscPacket, target: SocketAddr) -> anyhow::Result<()> {
+    let Ok(bytes) = rosc::encoder::encode(packet) else {
+        bail!("Could not encode OSC packet.");
+    };
+
+    let Ok(_) = upstream.send_to(&bytes, target) else {
+        bail!("Could not send OSC packet.");
+    };
+
+    Ok(())
+}
+
+struct OverlayValues {
+    num_overlays: i32,
+    has_keyboard: bool,
+    has_wrist: bool,
+    edit_mode: bool,
+    current_set: i32,
+    total_sets: i32,
+}
+
+impl OverlayValues {
+    fn collect<D>(overlay_manager: &OverlayWindowManager<D>) -> Self
+    where
+        D: Default,
+    {
+        // check state of each active overlay and count them
+        let mut num_overlays = 0;
+        let mut has_keyboard = false;
+        let mut has_wrist = false;
+        for o in overlay_manager.values() {
+            let Some(state) = o.config.active_state.as_ref() else {
+                continue;
+            };
+
+            // skip overlays that are fully transparent; e.g. the watch when not looking at it
+            if state.alpha <= 0.05 {
+                continue;
+            }
+
+            match o.config.name.as_ref() {
+                WATCH_NAME => has_wrist = true,
+                KEYBOARD_NAME => has_keyboard = true,
+                _ => {
+                    if state.interactable {
+                        num_overlays += 1;
                     }
-                };
-
-                lowest_battery = lowest_battery.min(level);
-
-                // send device battery parameters
-                self.send_message(
-                    format!("/avatar/parameters/{parameter}Battery"),
-                    vec![OscType::Float(level)],
-                )?;
-                self.send_message(
-                    format!("/avatar/parameters/{parameter}Charging"),
-                    vec![OscType::Bool(device.charging)],
-                )?;
+                }
             }
+        }
+
+        Self {
+            num_overlays,
+            has_keyboard,
+            has_wrist,
+            edit_mode: overlay_manager.get_edit_mode(),
+            current_set: overlay_manager.get_current_set().unwrap_or(0) as i32,
+            total_sets: overlay_manager.get_total_sets() as i32,
+        }
+    }
+}
+
+struct DeviceValues {
+    /// (name, battery level, charging)
+    devices: Vec<(String, f32, bool)>,
+    average_controller: f32,
+    average_tracker: f32,
+    lowest: f32,
+}
 
-            // send controller- and tracker-specific battery parameters
-            self.send_message(
-                String::from("/avatar/parameters/averageControllerBattery"),
-                vec![OscType::Float(
-                    controller_total_bat / f32::from(controller_count),
-                )],
-            )?;
-            self.send_message(
-                String::from("/avatar/parameters/averageTrackerBattery"),
-                vec![OscType::Float(tracker_total_bat / f32::from(tracker_count))],
-            )?;
-            self.send_message(
-                String::from("/avatar/parameters/LowestBattery"),
-                vec![OscType::Float(lowest_battery)],
-            )?;
-            self.send_message(
-                String::from("/avatar/parameters/lowestBattery"),
-                vec![OscType::Float(lowest_battery)],
-            )?;
-        }
-
-        Ok(())
+impl DeviceValues {
+    fn collect(devices: &[TrackedDevice]) -> Self {
+        let mut tracker_count: i8 = 0;
+        let mut controller_count: i8 = 0;
+        let mut tracker_total_bat = 0.0;
+        let mut controller_total_bat = 0.0;
+
+        let mut lowest_battery = 1f32;
+        let mut values = Vec::with_capacity(devices.len());
+
+        for device in devices {
+            // soc is the battery level (set to device status.charge)
+            let level = device.soc.unwrap_or(-1.0);
+            let name = match device.role {
+                TrackedDeviceRole::None => continue,
+                TrackedDeviceRole::Hmd => "hmd".into(),
+                TrackedDeviceRole::LeftHand => {
+                    controller_count += 1;
+                    controller_total_bat += level;
+                    "leftController".into()
+                }
+                TrackedDeviceRole::RightHand => {
+                    controller_count += 1;
+                    controller_total_bat += level;
+                    "rightController".into()
+                }
+                TrackedDeviceRole::Tracker => {
+                    tracker_count += 1;
+                    tracker_total_bat += level;
+                    format!("tracker{tracker_count}")
+                }
+            };
+
+            lowest_battery = lowest_battery.min(level);
+            values.push((name, level, device.charging));
+        }
+
+        Self {
+            devices: values,
+            average_controller: controller_total_bat / f32::from(controller_count),
+            average_tracker: tracker_total_bat / f32::from(tracker_count),
+            lowest: lowest_battery,
+        }
+    }
+}
+
+fn param_messages(
+    param: &OscOutParam,
+    overlays: &OverlayValues,
+    devices: &DeviceValues,
+) -> Vec<OscMessage> {
+    let single = |arg: OscType| {
+        vec![OscMessage {
+            addr: param.address.clone(),
+            args: vec![arg],
+        }]
+    };
+
+    match param.value {
+        OscOutValue::AnyOverlayOpen => single(OscType::Bool(overlays.num_overlays > 0)),
+        OscOutValue::OpenOverlayCount => single(OscType::Int(overlays.num_overlays)),
+        OscOutValue::EditMode => single(OscType::Bool(overlays.edit_mode)),
+        OscOutValue::CurrentSet => single(OscType::Int(overlays.current_set)),
+        OscOutValue::TotalSets => single(OscType::Int(overlays.total_sets)),
+        OscOutValue::KeyboardOpen => single(OscType::Bool(overlays.has_keyboard)),
+        OscOutValue::WatchVisible => single(OscType::Bool(overlays.has_wrist)),
+        OscOutValue::AverageControllerBattery => single(OscType::Float(devices.average_controller)),
+        OscOutValue::AverageTrackerBattery => single(OscType::Float(devices.average_tracker)),
+        OscOutValue::LowestBattery => single(OscType::Float(devices.lowest)),
+        OscOutValue::DeviceBattery | OscOutValue::DeviceCharging => devices
+            .devices
+            .iter()
+            .map(|(name, level, charging)| OscMessage {
+                addr: param.address.replace("{device}", name),
+                args: vec![if matches!(param.value, OscOutValue::DeviceBattery) {
+                    OscType::Float(*level)
+                } else {
+                    OscType::Bool(*charging)
+                }],
+            })
+            .collect(),
+    }
+}
+
+/// The parameters that were sent before `osc_outputs` existed.
+/// Overlay state every 0.1 seconds, device batteries every 10 seconds.
+fn default_outputs(port: u16) -> Vec<OscOutput> {
+    let params = |list: &[(OscOutValue, &str)]| {
+        list.iter()
+            .map(|(value, param)| OscOutParam {
+                value: *value,
+                address: format!("/avatar/parameters/{param}"),
+            })
+            .collect()
+    };
+
+    let targets = vec![format!("127.0.0.1:{port}")];
+
+    vec![
+        OscOutput {
+            targets: targets.clone(),
+            interval_ms: 100,
+            bundle: false,
+            params: params(&[
+                (OscOutValue::AnyOverlayOpen, "isOverlayOpen"),
+                (OscOutValue::AnyOverlayOpen, "ToggleWindows"),
+                (OscOutValue::OpenOverlayCount, "openOverlayCount"),
+                (OscOutValue::EditMode, "isEditModeActive"),
+                (OscOutValue::EditMode, "ToggleEditMode"),
+                (OscOutValue::CurrentSet, "currentWorkingSet"),
+                (OscOutValue::CurrentSet, "CurrentProfile"),
+                (OscOutValue::TotalSets, "totalWorkingSets"),
+                (OscOutValue::KeyboardOpen, "isKeyboardOpen"),
+                (OscOutValue::KeyboardOpen, "ToggleKeyboard"),
+                (OscOutValue::WatchVisible, "isWristVisible"),
+            ]),
+        },
+        OscOutput {
+            targets,
+            interval_ms: 10000,
+            bundle: false,
+            params: params(&[
+                (OscOutValue::DeviceBattery, "{device}Battery"),
+                (OscOutValue::DeviceCharging, "{device}Charging"),
+                (
+                    OscOutValue::AverageControllerBattery,
+                    "averageControllerBattery",
+                ),
+                (OscOutValue::AverageTrackerBattery, "averageTrackerBattery"),
+                (OscOutValue::LowestBattery, "LowestBattery"),
+                (OscOutValue::LowestBattery, "lowestBattery"),
+            ]),
+        },
+    ]
+}
+
+pub struct OscReceiver {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+}
+
+impl OscReceiver {
+    pub fn new(listen_port: u16) -> anyhow::Result<Self> {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
+        let socket = UdpSocket::bind(addr)
+            .with_context(|| format!("Failed to bind OSC listener to {addr}"))?;
+        socket.set_nonblocking(true)?;
+
+        log::info!("Listening for OSC messages on {addr}");
+
+        Ok(Self {
+            socket,
+            buf: vec![0; rosc::decoder::MTU],
+        })
+    }
+
+    /// Returns the messages received since the last call, with bundles flattened.
+    pub fn receive(&mut self) -> Vec<OscMessage> {
+        let mut messages = Vec::new();
+
+        loop {
+            let size = match self.socket.recv(&mut self.buf) {
+                Ok(size) => size,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::warn!("OSC receive error: {e}");
+                    break;
+                }
+            };
+
+            match rosc::decoder::decode_udp(&self.buf[..size]) {
+                Ok((_, packet)) => flatten_packet(packet, &mut messages),
+                Err(e) => log::warn!("Could not decode OSC packet: {e:?}"),
+            }
+        }
+
+        messages
+    }
+}
+
+fn flatten_packet(packet: OscPacket, messages: &mut Vec<OscMessage>) {
+    match packet {
+        OscPacket::Message(msg) => messages.push(msg),
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                flatten_packet(packet, messages);
+            }
+        }
+    }
+}
+
+fn mapped_action<'a>(mappings: &'a [OscInMapping], addr: &str) -> Option<&'a OscInAction> {
+    mappings
+        .iter()
+        .find(|m| m.address == addr)
+        .map(|m| &m.action)
+}
+
+/// Runs the action mapped to the message's address in `osc_in_actions`, if any.
+pub fn handle_osc_message(app: &mut AppState, msg: OscMessage) {
+    let Some(action) = mapped_action(&app.session.config.osc_in_actions, &msg.addr).cloned() else {
+        log::trace!("OSC: no action for {}", msg.addr);
+        return;
+    };
+
+    log::debug!("OSC: {} {:?} → {action:?}", msg.addr, msg.args);
+
+    let first_arg = msg.args.first();
+    let pressed = first_arg.is_none_or(is_truthy);
+
+    let task = match action {
+        OscInAction::ToggleOverlay { overlay } => {
+            if !pressed {
+                return;
+            }
+            OverlayTask::ToggleOverlay(OverlaySelector::Name(overlay), ToggleMode::Toggle)
+        }
+        OscInAction::SetOverlayVisible { overlay } => {
+            let mode = if pressed {
+                ToggleMode::EnsureOn
+            } else {
+                ToggleMode::EnsureOff
+            };
+            OverlayTask::ToggleOverlay(OverlaySelector::Name(overlay), mode)
+        }
+        OscInAction::SwitchSet { set } => {
+            let Some(set) = set.or_else(|| first_arg.and_then(as_int)) else {
+                log::warn!("OSC: {} expects an integer argument", msg.addr);
+                return;
+            };
+            OverlayTask::SwitchSet(usize::try_from(set).ok())
+        }
+        OscInAction::ShowHide => {
+            if !pressed {
+                return;
+            }
+            OverlayTask::ShowHide
+        }
+        OscInAction::Toast { title } => {
+            let body = first_arg.map(as_text).unwrap_or_default();
+            Toast::new(ToastTopic::OscMessage, title.unwrap_or_default(), body)
+                .with_timeout(5.)
+                .submit(app);
+            return;
+        }
+        OscInAction::SetPanelText { overlay, element } => {
+            let text = first_arg.map(as_text).unwrap_or_default();
+            OverlayTask::ModifyPanel(ModifyPanelTask {
+                overlay,
+                element,
+                command: ModifyPanelCommand::SetText(text),
+            })
+        }
+    };
+
+    app.tasks.enqueue(TaskType::Overlay(task));
+}
+
+fn is_truthy(arg: &OscType) -> bool {
+    match arg {
+        OscType::Bool(b) => *b,
+        OscType::Int(i) => *i != 0,
+        OscType::Long(i) => *i != 0,
+        OscType::Float(f) => *f >= 0.5,
+        OscType::Double(f) => *f >= 0.5,
+        OscType::Nil => false,
+        _ => true,
+    }
+}
+
+fn as_int(arg: &OscType) -> Option<i32> {
+    match arg {
+        OscType::Int(i) => Some(*i),
+        OscType::Long(i) => i32::try_from(*i).ok(),
+        OscType::Float(f) => Some(*f as i32),
+        OscType::Double(f) => Some(*f as i32),
+        OscType::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(arg: &OscType) -> String {
+    match arg {
+        OscType::String(s) => s.clone(),
+        OscType::Int(i) => i.to_string(),
+        OscType::Long(i) => i.to_string(),
+        OscType::Float(f) => f.to_string(),
+        OscType::Double(f) => f.to_string(),
+        OscType::Bool(b) => b.to_string(),
+        other => format!("{other:?}"),
     }
 }
 
@@ -247,3 +530,63 @@ pub fn parse_osc_value(s: &str) -> anyhow::Result<OscType> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receive_over_loopback() {
+        let mut receiver = OscReceiver::new(0).unwrap();
+        let target = receiver.socket.local_addr().unwrap();
+
+        let mappings = vec![
+            OscInMapping {
+                address: "/wayvr/keyboard".into(),
+                action: OscInAction::ToggleOverlay {
+                    overlay: KEYBOARD_NAME.into(),
+                },
+            },
+            OscInMapping {
+                address: "/wayvr/set".into(),
+                action: OscInAction::SwitchSet { set: None },
+            },
+        ];
+
+        let message = |addr: &str, args| {
+            OscPacket::Message(OscMessage {
+                addr: addr.into(),
+                args,
+            })
+        };
+        let packet = OscPacket::Bundle(OscBundle {
+            timetag: OscTime {
+                seconds: 0,
+                fractional: 1,
+            },
+            content: vec![
+                message("/wayvr/set", vec![OscType::Int(2)]),
+                message("/unmapped", vec![]),
+            ],
+        });
+
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        send_packet(&sender, &packet, target).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut messages = receiver.receive();
+        while messages.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            messages = receiver.receive();
+        }
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].addr, "/wayvr/set");
+        assert!(matches!(
+            mapped_action(&mappings, &messages[0].addr),
+            Some(OscInAction::SwitchSet { set: None })
+        ));
+        assert_eq!(messages[0].args.first().and_then(as_int), Some(2));
+        assert!(mapped_action(&mappings, &messages[1].addr).is_none());
+    }
+}
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, sync::Arc};
+
+use wayvr_ipc::packet_server::WlxEvent;
+
+use crate::ipc::{event_queue::SyncEventQueue, signal::WayVRSignal};
+
+/// Key/value store shared by all panels.
+/// Written by `::VarSet` and similar button actions as well as IPC, read by `variable` labels
+/// and `::If var` conditions.
+#[derive(Default)]
+pub struct VariableStore {
+    values: HashMap<Arc<str>, Arc<str>>,
+    /// Incremented on every change, so that bound labels know when to re-render.
+    generation: u64,
+}
+
+impl VariableStore {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(AsRef::as_ref)
+    }
+
+    /// Variables that are set and not `""`, `"0"` or `"false"`.
+    pub fn is_truthy(&self, key: &str) -> bool {
+        self.get(key)
+            .is_some_and(|v| !matches!(v, "" | "0" | "false"))
+    }
+
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Sorted by key.
+    pub fn list(&self) -> Vec<(String, String)> {
+        let mut list: Vec<_> = self
+            .values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        list.sort();
+        list
+    }
+
+    /// Sets or removes (`None`) a variable and notifies IPC subscribers if it changed.
+    pub fn set(&mut self, signals: &SyncEventQueue<WayVRSignal>, key: &str, value: Option<&str>) {
+        let changed = match value {
+            Some(value) => {
+                if self.get(key) == Some(value) {
+                    false
+                } else {
+                    self.values.insert(key.into(), value.into());
+                    true
+                }
+            }
+            None => self.values.remove(key).is_some(),
+        };
+
+        if !changed {
+            return;
+        }
+
+        log::debug!("Variable {key} = {value:?}");
+        self.generation += 1;
+        signals.send(WayVRSignal::BroadcastEvent(WlxEvent::VariableChanged {
+            key: key.to_string(),
+            value: value.map(ToString::to_string),
+        }));
+    }
+}
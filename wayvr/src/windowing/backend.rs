@@ -120,7 +120,16 @@ pub enum OverlayEventData {
     OverlaysChanged(Rc<[OverlayMeta]>),
     VisibleOverlaysChanged(Rc<[OverlayID]>),
     DevicesChanged,
+    NotificationsChanged,
+    /// Sent by `GuiPanel` when it becomes visible again.
+    Resumed,
+    ToastsChanged,
+    DoNotDisturbChanged(bool),
     SettingsChanged,
+    /// `keyboard.yaml` was saved, the keyboard needs to be rebuilt.
+    KeyboardLayoutChanged,
+    /// A layout profile was saved, the dashboard needs to refresh its list.
+    LayoutProfilesChanged,
     OverlayGrabbed {
         name: Arc<str>,
         pos: Positioning,
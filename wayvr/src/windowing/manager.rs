@@ -1,12 +1,13 @@
 use std::{
     collections::{HashMap, VecDeque},
     rc::Rc,
-    sync::atomic::Ordering,
+    sync::{Arc, atomic::Ordering},
 };
 
 use anyhow::Context;
 use glam::{Affine3A, Vec3, Vec3A};
 use slotmap::{HopSlotMap, Key, SecondaryMap};
+use wayvr_ipc::packet_server::WlxEvent;
 use wgui::log::LogErr;
 use wlx_common::{
     astr_containers::{AStrMap, AStrMapExt},
@@ -17,15 +18,17 @@ use wlx_common::{
 use crate::{
     FRAME_COUNTER,
     backend::task::{OverlayTask, ToggleMode},
-    config::save_state,
+    config::{self, save_state},
+    ipc::signal::WayVRSignal,
     overlays::{
         anchor::{create_anchor, create_grab_help},
         custom::create_custom,
         dashboard::{DASH_NAME, create_dash_frontend},
         edit::EditWrapperManager,
         keyboard::create_keyboard,
-        screen::create_screens,
-        toast::Toast,
+        notification_center::create_notification_center,
+        screen::{arrange, create_screens},
+        toast::{Toast, error_toast},
         watch::{WATCH_NAME, create_watch},
     },
     state::AppState,
@@ -55,6 +58,8 @@ pub struct OverlayWindowManager<T> {
     keyboard_id: OverlayID,
     edit_mode: bool,
     dropped_overlays: VecDeque<OverlayWindowData<T>>,
+    /// Visible overlays as of the last VisibleOverlaysChanged, used to emit IPC events.
+    last_visible: Rc<[OverlayID]>,
     initialized: bool,
 }
 
@@ -75,6 +80,7 @@ where
             keyboard_id: OverlayID::null(), // set down below
             edit_mode: false,
             dropped_overlays: VecDeque::with_capacity(8),
+            last_visible: Rc::new([]),
             initialized: false,
         };
 
@@ -136,6 +142,9 @@ where
         let grab_help = OverlayWindowData::from_config(create_grab_help(app)?);
         me.add(grab_help, app);
 
+        let notification_center = OverlayWindowData::from_config(create_notification_center(app)?);
+        me.add(notification_center, app);
+
         let custom_panels = app.session.config.custom_panels.clone();
         for name in custom_panels {
             let Some(panel) = create_custom(app, name) else {
@@ -159,6 +168,7 @@ where
             }
         }
 
+        app.overlay_status.current_set = me.current_set;
         me.initialized = true;
 
         Ok(me)
@@ -282,10 +292,20 @@ where
                     return Ok(());
                 }
 
-                self.switch_to_set(app, None, false);
-                self.sets.remove(set);
-                self.restore_set = 0;
-                self.sets_changed(app);
+                self.delete_set(app, set)?;
+            }
+            OverlayTask::SaveProfile(name) => {
+                if let Err(e) = self.save_profile(app, &name) {
+                    error_toast(app, "Could not save layout profile", e);
+                }
+            }
+            OverlayTask::LoadProfile(name) => {
+                if let Err(e) = self.load_profile(app, &name) {
+                    error_toast(app, "Could not load layout profile", e);
+                }
+            }
+            OverlayTask::ArrangeScreens(arrangement) => {
+                arrange::arrange_screens(self, app, arrangement);
             }
             OverlayTask::SettingsChanged => {
                 for o in self.overlays.values_mut() {
@@ -385,6 +405,86 @@ where
         }
         Ok(())
     }
+
+    /// Makes the overlay part of the given set, using its last known state in that set if any.
+    /// If the set is currently active, the overlay is shown right away.
+    pub fn add_overlay_to_set(
+        &mut self,
+        app: &mut AppState,
+        set_idx: usize,
+        name: &str,
+    ) -> anyhow::Result<()> {
+        if set_idx >= self.sets.len() {
+            anyhow::bail!("Set {} does not exist", set_idx + 1);
+        }
+        let Some(id) = self.lookup(name) else {
+            anyhow::bail!("Overlay not found: {name}");
+        };
+        if self.overlays[id].config.global {
+            anyhow::bail!("Overlay {name} is global and does not belong to any set");
+        }
+
+        if self.current_set == Some(set_idx) {
+            return self.handle_task(
+                app,
+                OverlayTask::ToggleOverlay(OverlaySelector::Id(id), ToggleMode::EnsureOn),
+            );
+        }
+
+        let set = &mut self.sets[set_idx];
+        if set.overlays.contains_key(id) {
+            return Ok(());
+        }
+        let state = set
+            .hidden_overlays
+            .arc_rm(name)
+            .unwrap_or_else(|| self.overlays[id].config.default_state.clone());
+        set.overlays.insert(id, state);
+
+        Ok(())
+    }
+
+    /// Removes the overlay from the given set, keeping its state in case it's added back later.
+    /// If the set is currently active, the overlay is hidden right away.
+    pub fn remove_overlay_from_set(
+        &mut self,
+        app: &mut AppState,
+        set_idx: usize,
+        name: &str,
+    ) -> anyhow::Result<()> {
+        if set_idx >= self.sets.len() {
+            anyhow::bail!("Set {} does not exist", set_idx + 1);
+        }
+
+        let Some(id) = self.lookup(name) else {
+            // not seen since startup, e.g. a wayvr app that hasn't been launched yet
+            let set = &mut self.sets[set_idx];
+            let Some(state) = set.inactive_overlays.arc_rm(name) else {
+                anyhow::bail!("Overlay not found: {name}");
+            };
+            set.hidden_overlays.arc_set(name.into(), state);
+            return Ok(());
+        };
+
+        if self.overlays[id].config.global {
+            anyhow::bail!("Overlay {name} is global and does not belong to any set");
+        }
+
+        if self.current_set == Some(set_idx) {
+            return self.handle_task(
+                app,
+                OverlayTask::ToggleOverlay(OverlaySelector::Id(id), ToggleMode::EnsureOff),
+            );
+        }
+
+        let set = &mut self.sets[set_idx];
+        if let Some(state) = set.overlays.remove(id) {
+            set.hidden_overlays
+                .arc_set(self.overlays[id].config.name.clone(), state);
+        }
+
+        Ok(())
+    }
 }
 
 const SAVED_ATTRIBS: [BackendAttrib; 3] = [
@@ -404,13 +504,10 @@ impl<T> OverlayWindowManager<T> {
         app.session.config.sets.reserve(self.sets.len());
         app.session.config.last_set = self.restore_set as _;
 
-        // only safe to save when current_set is None
-        let restore_after = if self.current_set.is_some() {
-            self.switch_to_set(app, None, true);
-            true
-        } else {
-            false
-        };
+        // only safe to save when current_set is None.
+        // swapped back right after, so no events are sent
+        let restore_after = self.current_set;
+        self.swap_active_set(app, None, true);
 
         for set in &self.sets {
             let mut overlays: HashMap<_, _> = set
@@ -465,8 +562,8 @@ impl<T> OverlayWindowManager<T> {
             );
         }
 
-        if restore_after {
-            self.switch_to_set(app, Some(self.restore_set), true);
+        if restore_after.is_some() {
+            self.swap_active_set(app, restore_after, true);
         }
     }
 
@@ -560,6 +657,39 @@ impl<T> OverlayWindowManager<T> {
         self.restore_set = (app.session.config.last_set as usize).min(self.sets.len() - 1);
     }
 
+    /// Saves the current sets, global overlays and attribs as a named layout profile.
+    pub fn save_profile(&mut self, app: &mut AppState, name: &str) -> anyhow::Result<()> {
+        self.persist_layout(app);
+        config::save_profile(name, &app.session.config)?;
+
+        if let Some(overlay) = self.mut_by_selector(&OverlaySelector::Name(DASH_NAME.into())) {
+            let _ = overlay
+                .config
+                .backend
+                .notify(app, OverlayEventData::LayoutProfilesChanged)
+                .log_err("Could not notify LayoutProfilesChanged");
+        }
+        Ok(())
+    }
+
+    /// Replaces the current sets, global overlays and attribs with those of a layout profile.
+    pub fn load_profile(&mut self, app: &mut AppState, name: &str) -> anyhow::Result<()> {
+        let profile = config::load_profile(name)?;
+        let restore_after = self.current_set.is_some();
+
+        app.session.config.sets = profile.sets;
+        app.session.config.global_set = profile.global_set;
+        app.session.config.attribs = profile.attribs;
+        app.session.config.last_set = 0;
+        self.restore_layout(app);
+
+        if restore_after {
+            self.switch_to_set(app, Some(self.restore_set), false);
+        }
+        self.sets_changed(app);
+        Ok(())
+    }
+
     pub const fn get_edit_mode(&self) -> bool {
         self.edit_mode
     }
@@ -572,9 +702,107 @@ impl<T> OverlayWindowManager<T> {
         self.sets.len()
     }
 
+    pub fn get_set_name(&self, set_idx: usize) -> Option<Arc<str>> {
+        self.sets.get(set_idx).map(|s| s.name.clone())
+    }
+
+    /// Names of the overlays that are shown while the given set is active.
+    pub fn get_set_overlays(&self, set_idx: usize) -> Vec<Arc<str>> {
+        if self.current_set == Some(set_idx) {
+            return self
+                .overlays
+                .values()
+                .filter(|o| !o.config.global && o.config.is_active())
+                .map(|o| o.config.name.clone())
+                .collect();
+        }
+
+        let Some(set) = self.sets.get(set_idx) else {
+            return vec![];
+        };
+
+        set.overlays
+            .keys()
+            .filter_map(|id| self.overlays.get(id).map(|o| o.config.name.clone()))
+            .chain(set.inactive_overlays.iter().map(|(name, _)| name.clone()))
+            .collect()
+    }
+
+    /// Appends a new set that only contains the keyboard, without switching to it.
+    /// Returns the index of the new set.
+    pub fn add_set(&mut self, app: &mut AppState, name: Arc<str>) -> anyhow::Result<usize> {
+        let new_idx = self.sets.len();
+        if new_idx >= MAX_OVERLAY_SETS {
+            anyhow::bail!("Maximum number of sets reached");
+        }
+
+        let mut set = OverlayWindowSet {
+            name,
+            ..OverlayWindowSet::default()
+        };
+
+        // same as OverlayTask::AddSet, new sets start with the keyboard
+        if let Some(keyboard) = self.overlays.get(self.keyboard_id) {
+            set.overlays
+                .insert(self.keyboard_id, keyboard.config.default_state.clone());
+        }
+
+        self.sets.push(set);
+        self.sets_changed(app);
+        Ok(new_idx)
+    }
+
+    pub fn delete_set(&mut self, app: &mut AppState, set_idx: usize) -> anyhow::Result<()> {
+        if set_idx >= self.sets.len() {
+            anyhow::bail!("Set {} does not exist", set_idx + 1);
+        }
+        if self.sets.len() <= 1 {
+            anyhow::bail!("Cannot remove the last existing set");
+        }
+
+        // the overlays of the active set are hidden, while those of
+        // any other set aren't visible to begin with
+        if self.current_set == Some(set_idx) {
+            self.switch_to_set(app, None, false);
+        }
+        self.sets.remove(set_idx);
+
+        self.restore_set = match self.restore_set {
+            x if x > set_idx => x - 1,
+            x if x == set_idx => 0,
+            x => x,
+        };
+
+        if let Some(cur) = self.current_set.filter(|cur| *cur > set_idx) {
+            self.current_set = Some(cur - 1);
+            app.overlay_status.current_set = self.current_set;
+            self.active_set_changed(app);
+        }
+
+        self.sets_changed(app);
+        Ok(())
+    }
+
+    pub fn rename_set(
+        &mut self,
+        app: &mut AppState,
+        set_idx: usize,
+        name: Arc<str>,
+    ) -> anyhow::Result<()> {
+        let Some(set) = self.sets.get_mut(set_idx) else {
+            anyhow::bail!("Set {} does not exist", set_idx + 1);
+        };
+        set.name = name;
+
+        self.sets_changed(app);
+        self.persist_layout(app);
+        save_state(&app.session.config).context("Could not save state")
+    }
+
     pub fn set_edit_mode(&mut self, enabled: bool, app: &mut AppState) -> anyhow::Result<()> {
         let changed = enabled != self.edit_mode;
         self.edit_mode = enabled;
+        app.overlay_status.edit_mode = enabled;
         if !enabled {
             for o in self.overlays.values_mut() {
                 self.wrappers.unwrap_edit_mode(&mut o.config, app)?;
@@ -587,6 +815,12 @@ impl<T> OverlayWindowManager<T> {
                 }
             }
         }
+        if changed {
+            app.wayvr_signals
+                .send(WayVRSignal::BroadcastEvent(WlxEvent::EditModeChanged {
+                    enabled,
+                }));
+        }
         if changed && let Some(watch) = self.mut_by_id(self.watch_id) {
             watch
                 .config
@@ -778,6 +1012,35 @@ impl<T> OverlayWindowManager<T> {
             return;
         }
 
+        let num_overlays = self.swap_active_set(app, new_set, keep_transforms);
+
+        if new_set.is_some() && !self.edit_mode && self.initialized && num_overlays < 1 {
+            Toast::new(
+                ToastTopic::System,
+                "TOAST.EMPTY_SET".into(),
+                "TOAST.LETS_ADD_OVERLAYS".into(),
+            )
+            .with_timeout(3.)
+            .submit(app);
+        }
+
+        self.active_set_changed(app);
+        let _ = self
+            .visible_overlays_changed(app)
+            .inspect_err(|e| log::error!("VisibleOverlaysChanged: {e:?}"));
+    }
+
+    /// Moves the states of the current set's overlays back into the set and activates `new_set`.
+    /// Sends no events, so the caller must do so if the visible overlays are meant to change.
+    /// Returns the number of user-facing overlays in `new_set`.
+    fn swap_active_set(
+        &mut self,
+        app: &mut AppState,
+        new_set: Option<usize>,
+        keep_transforms: bool,
+    ) -> usize {
+        let mut num_overlays = 0;
+
         if let Some(current_set) = self.current_set.as_ref() {
             let ws = &mut self.sets[*current_set];
             for (id, data) in self.overlays.iter_mut().filter(|(_, d)| !d.config.global) {
@@ -789,7 +1052,6 @@ impl<T> OverlayWindowManager<T> {
         }
 
         if let Some(new_set) = new_set {
-            let mut num_overlays = 0;
             let ws = &mut self.sets[new_set];
             for (id, data) in self.overlays.iter_mut().filter(|(_, d)| !d.config.global) {
                 if let Some(state) = ws.overlays.remove(id) {
@@ -810,29 +1072,25 @@ impl<T> OverlayWindowManager<T> {
             }
             ws.overlays.clear();
             self.restore_set = new_set;
-
-            if !self.edit_mode && self.initialized && num_overlays < 1 {
-                Toast::new(
-                    ToastTopic::System,
-                    "TOAST.EMPTY_SET".into(),
-                    "TOAST.LETS_ADD_OVERLAYS".into(),
-                )
-                .with_timeout(3.)
-                .submit(app);
-            }
         }
         self.current_set = new_set;
+        app.overlay_status.current_set = new_set;
+
+        num_overlays
+    }
+
+    /// Lets the watch, keyboard and IPC clients know about the index of the current set.
+    fn active_set_changed(&mut self, app: &mut AppState) {
+        let set = self.current_set;
+        app.wayvr_signals
+            .send(WayVRSignal::BroadcastEvent(WlxEvent::SetSwitched { set }));
 
         for id in [self.watch_id, self.keyboard_id] {
             let _ = self.mut_by_id(id).context("Missing overlay").and_then(|o| {
                 o.config
                     .backend
-                    .notify(app, OverlayEventData::ActiveSetChanged(new_set))
+                    .notify(app, OverlayEventData::ActiveSetChanged(set))
             });
-
-            let _ = self
-                .visible_overlays_changed(app)
-                .inspect_err(|e| log::error!("VisibleOverlaysChanged: {e:?}"));
         }
     }
 
@@ -899,6 +1157,29 @@ impl<T> OverlayWindowManager<T> {
         }
 
         let vis: Rc<[OverlayID]> = vis.into();
+
+        for id in vis.iter().filter(|id| !self.last_visible.contains(id)) {
+            let name = self.overlays[*id].config.name.to_string();
+            app.wayvr_signals
+                .send(WayVRSignal::BroadcastEvent(WlxEvent::OverlayShown { name }));
+        }
+        for id in self.last_visible.iter().filter(|id| !vis.contains(id)) {
+            // overlay may have been removed in the meantime
+            let Some(o) = self.overlays.get(*id) else {
+                continue;
+            };
+            let name = o.config.name.to_string();
+            app.wayvr_signals
+                .send(WayVRSignal::BroadcastEvent(WlxEvent::OverlayHidden {
+                    name,
+                }));
+        }
+        self.last_visible = vis.clone();
+        app.overlay_status.visible = vis
+            .iter()
+            .map(|id| self.overlays[*id].config.name.clone())
+            .collect();
+
         for id in [self.watch_id, self.keyboard_id] {
             let _ = self.mut_by_id(id).context("Missing overlay").and_then(|o| {
                 o.config
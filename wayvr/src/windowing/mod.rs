@@ -11,6 +11,16 @@ new_key_type! {
     pub struct OverlayID;
 }
 
+/// Overlay and set state as of the last change, for code that has no access to the
+/// `OverlayWindowManager`, such as panel button actions.
+#[derive(Default)]
+pub struct OverlayStatus {
+    /// Names of visible non-internal overlays.
+    pub visible: Vec<Arc<str>>,
+    pub current_set: Option<usize>,
+    pub edit_mode: bool,
+}
+
 #[derive(Clone, Debug)]
 pub enum OverlaySelector {
     Id(OverlayID),
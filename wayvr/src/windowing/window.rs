@@ -1,4 +1,4 @@
-use glam::{Affine3A, Mat3A, Quat, Vec3, Vec3A};
+use glam::{Affine3A, Mat3A, Quat, Vec2, Vec3, Vec3A, vec2};
 use idmap_derive::IntegerId;
 use std::{f32::consts::PI, sync::Arc};
 use wlx_common::windowing::{OverlayWindowState, Positioning};
@@ -50,6 +50,26 @@ impl<T> OverlayWindowData<T> {
     pub fn frame_meta(&mut self) -> Option<FrameMeta> {
         self.config.backend.frame_meta()
     }
+
+    /// Pose and size in meters of the quad submitted to the runtime, if visible.
+    pub fn quad(&mut self) -> Option<(Affine3A, Vec2)> {
+        let meta = self.frame_meta()?;
+        let state = self.config.active_state.as_ref()?;
+        if meta.extent[0] == 0 || meta.extent[1] == 0 {
+            return None;
+        }
+
+        let transform = state.transform * meta.transform;
+        let aspect_ratio = meta.extent[0] as f32 / meta.extent[1] as f32;
+        let size = if aspect_ratio < 1.0 {
+            let major = transform.matrix3.col(0).length();
+            vec2(major * aspect_ratio, major)
+        } else {
+            let major = transform.matrix3.col(1).length();
+            vec2(major, major / aspect_ratio)
+        };
+        Some((transform, size))
+    }
 }
 
 #[derive(Debug, Clone, Copy, IntegerId, PartialEq, Eq)]
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
 use anyhow::Context;
 use serde::Serialize;
@@ -164,6 +167,25 @@ pub async fn wlx_show_hide(state: &mut WayVRClientState) {
     )
 }
 
+pub async fn wlx_do_not_disturb_set(state: &mut WayVRClientState, enabled: Option<bool>) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_do_not_disturb_set(state.wayvr_client.clone(), enabled)
+            .await
+            .context("failed to set do not disturb"),
+    )
+}
+
+pub async fn wlx_screens_arrange(
+    state: &mut WayVRClientState,
+    arrangement: packet_client::WlxScreenArrangement,
+) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_screens_arrange(state.wayvr_client.clone(), arrangement)
+            .await
+            .context("failed to arrange screens"),
+    )
+}
+
 pub async fn wlx_switch_set(state: &mut WayVRClientState, set: Option<usize>) {
     handle_empty_result(
         WayVRClient::fn_wlx_switch_set(state.wayvr_client.clone(), set)
@@ -192,6 +214,241 @@ pub async fn wlx_panel_modify(
     )
 }
 
+pub async fn wlx_overlay_list(state: &mut WayVRClientState) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_overlay_list(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+        )
+        .await
+        .context("failed to list overlays"),
+    )
+}
+
+pub async fn wlx_overlay_get(state: &mut WayVRClientState, name: String) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_overlay_get(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+        )
+        .await
+        .context("failed to get overlay"),
+    )
+}
+
+pub async fn wlx_overlay_set(
+    state: &mut WayVRClientState,
+    params: packet_client::WlxOverlaySetParams,
+) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_overlay_set(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            params,
+        )
+        .await
+        .context("failed to set overlay state"),
+    )
+}
+
+pub async fn wlx_set_list(state: &mut WayVRClientState) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_set_list(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+        )
+        .await
+        .context("failed to list sets"),
+    )
+}
+
+pub async fn wlx_set_create(state: &mut WayVRClientState, name: Option<String>) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_set_create(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+        )
+        .await
+        .map(|idx| idx + 1)
+        .context("failed to create set"),
+    )
+}
+
+pub async fn wlx_set_delete(state: &mut WayVRClientState, set: usize) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_set_delete(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            set,
+        )
+        .await
+        .context("failed to delete set"),
+    )
+}
+
+pub async fn wlx_set_rename(state: &mut WayVRClientState, set: usize, name: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_set_rename(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            set,
+            name,
+        )
+        .await
+        .context("failed to rename set"),
+    )
+}
+
+pub async fn wlx_set_add_overlay(state: &mut WayVRClientState, set: usize, overlay: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_set_add_overlay(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            set,
+            overlay,
+        )
+        .await
+        .context("failed to add overlay to set"),
+    )
+}
+
+pub async fn wlx_set_remove_overlay(state: &mut WayVRClientState, set: usize, overlay: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_set_remove_overlay(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            set,
+            overlay,
+        )
+        .await
+        .context("failed to remove overlay from set"),
+    )
+}
+
+pub async fn wlx_profile_list(state: &mut WayVRClientState) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_profile_list(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+        )
+        .await
+        .context("failed to list profiles"),
+    )
+}
+
+pub async fn wlx_profile_save(state: &mut WayVRClientState, name: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_profile_save(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+        )
+        .await
+        .context("failed to save profile"),
+    )
+}
+
+pub async fn wlx_profile_load(state: &mut WayVRClientState, name: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_profile_load(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+        )
+        .await
+        .context("failed to load profile"),
+    )
+}
+
+pub async fn wlx_profile_delete(state: &mut WayVRClientState, name: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_profile_delete(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+        )
+        .await
+        .context("failed to delete profile"),
+    )
+}
+
+pub async fn wlx_profile_import(state: &mut WayVRClientState, name: String, data: String) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_profile_import(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+            name,
+            data,
+        )
+        .await
+        .context("failed to import profile"),
+    )
+}
+
+pub async fn wlx_profile_export(
+    state: &mut WayVRClientState,
+    name: String,
+    file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let data = WayVRClient::fn_wlx_profile_export(
+        state.wayvr_client.clone(),
+        state.serial_generator.increment_get(),
+        name,
+    )
+    .await
+    .context("failed to export profile")?;
+
+    match file {
+        Some(file) => std::fs::write(&file, data)
+            .with_context(|| format!("Could not write {}", file.display()))?,
+        None => println!("{data}"),
+    }
+    Ok(())
+}
+
+pub async fn wlx_var_list(state: &mut WayVRClientState) {
+    handle_result(
+        state.pretty_print,
+        WayVRClient::fn_wlx_var_list(
+            state.wayvr_client.clone(),
+            state.serial_generator.increment_get(),
+        )
+        .await
+        .map(|list| list.into_iter().collect::<BTreeMap<_, _>>())
+        .context("failed to list variables"),
+    )
+}
+
+pub async fn wlx_var_get(state: &mut WayVRClientState, key: String) -> anyhow::Result<()> {
+    let list = WayVRClient::fn_wlx_var_list(
+        state.wayvr_client.clone(),
+        state.serial_generator.increment_get(),
+    )
+    .await
+    .context("failed to list variables")?;
+
+    let Some((_, value)) = list.into_iter().find(|(k, _)| *k == key) else {
+        anyhow::bail!("Variable {key} is not set");
+    };
+    println!("{value}");
+    Ok(())
+}
+
+pub async fn wlx_var_set(state: &mut WayVRClientState, key: String, value: Option<String>) {
+    handle_empty_result(
+        WayVRClient::fn_wlx_var_set(state.wayvr_client.clone(), key, value)
+            .await
+            .context("failed to set variable"),
+    )
+}
+
 pub async fn wlx_input_state(state: &mut WayVRClientState) {
     handle_result(
         state.pretty_print,
@@ -203,3 +460,34 @@ pub async fn wlx_input_state(state: &mut WayVRClientState) {
         .context("failed to get input state"),
     )
 }
+
+pub async fn wlx_watch(
+    state: &mut WayVRClientState,
+    topics: Vec<packet_server::WlxEventTopic>,
+) -> anyhow::Result<()> {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    state
+        .wayvr_client
+        .lock()
+        .await
+        .set_signal_handler(Box::new(move |packet| {
+            if let packet_server::PacketServer::WlxEvent(event) = packet {
+                let _ = sender.send(event.clone());
+                true
+            } else {
+                false
+            }
+        }));
+
+    WayVRClient::fn_wlx_subscribe(state.wayvr_client.clone(), topics)
+        .await
+        .context("failed to subscribe to events")?;
+
+    // one JSON object per line, regardless of pretty_print
+    while let Some(event) = receiver.recv().await {
+        println!("{}", serde_json::to_string(&event)?);
+    }
+
+    anyhow::bail!("connection to WayVR closed")
+}
@@ -1,21 +1,27 @@
 use std::{
     collections::HashMap,
+    path::PathBuf,
     process::{self, ExitCode},
     time::Duration,
 };
 
 use anyhow::Context;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use env_logger::Env;
 use wayvr_ipc::{
     client::WayVRClient,
     ipc,
     packet_client::{self, PositionMode},
+    packet_server::{WlxEventTopic, WlxHand, WlxPositioning},
 };
 
 use crate::helper::{
-    WayVRClientState, wlx_device_haptics, wlx_input_state, wlx_panel_modify, wlx_show_hide,
-    wlx_switch_set, wvr_process_get, wvr_process_launch, wvr_process_list, wvr_process_terminate,
+    WayVRClientState, wlx_device_haptics, wlx_do_not_disturb_set, wlx_input_state, wlx_overlay_get,
+    wlx_overlay_list, wlx_overlay_set, wlx_panel_modify, wlx_profile_delete, wlx_profile_export,
+    wlx_profile_import, wlx_profile_list, wlx_profile_load, wlx_profile_save, wlx_screens_arrange,
+    wlx_set_add_overlay, wlx_set_create, wlx_set_delete, wlx_set_list, wlx_set_remove_overlay,
+    wlx_set_rename, wlx_show_hide, wlx_switch_set, wlx_var_get, wlx_var_list, wlx_var_set,
+    wlx_watch, wvr_process_get, wvr_process_launch, wvr_process_list, wvr_process_terminate,
     wvr_window_list, wvr_window_set_visible,
 };
 
@@ -166,6 +172,26 @@ async fn run_once(state: &mut WayVRClientState, args: Args) -> anyhow::Result<()
         Subcommands::ShowHide {} => {
             wlx_show_hide(state).await;
         }
+        Subcommands::DoNotDisturb { mode } => {
+            let enabled = match mode {
+                DoNotDisturbModeEnum::On => Some(true),
+                DoNotDisturbModeEnum::Off => Some(false),
+                DoNotDisturbModeEnum::Toggle => None,
+            };
+            wlx_do_not_disturb_set(state, enabled).await;
+        }
+        Subcommands::ArrangeScreens {
+            arrangement,
+            radius,
+        } => {
+            let arrangement = match arrangement {
+                ArrangementEnum::Row => packet_client::WlxScreenArrangement::Row,
+                ArrangementEnum::Arc => packet_client::WlxScreenArrangement::Arc { radius },
+                ArrangementEnum::Stack => packet_client::WlxScreenArrangement::Stack,
+                ArrangementEnum::Desktop => packet_client::WlxScreenArrangement::Desktop,
+            };
+            wlx_screens_arrange(state, arrangement).await;
+        }
         Subcommands::PanelModify {
             overlay,
             element,
@@ -195,10 +221,179 @@ async fn run_once(state: &mut WayVRClientState, args: Args) -> anyhow::Result<()
             let set = if set <= 0 { None } else { Some((set - 1) as _) };
             wlx_switch_set(state, set).await;
         }
+        Subcommands::Watch { topics } => {
+            let topics = if topics.is_empty() {
+                TopicEnum::value_variants().to_vec()
+            } else {
+                topics
+            };
+
+            let topics = topics
+                .into_iter()
+                .map(|topic| match topic {
+                    TopicEnum::Overlays => WlxEventTopic::OverlayVisibility,
+                    TopicEnum::Sets => WlxEventTopic::SetSwitched,
+                    TopicEnum::EditMode => WlxEventTopic::EditMode,
+                    TopicEnum::KeyboardFocus => WlxEventTopic::KeyboardFocus,
+                    TopicEnum::Toasts => WlxEventTopic::Toast,
+                    TopicEnum::Variables => WlxEventTopic::Variables,
+                    TopicEnum::DoNotDisturb => WlxEventTopic::DoNotDisturb,
+                })
+                .collect();
+
+            wlx_watch(state, topics).await?;
+        }
+        Subcommands::Overlay { command } => match command {
+            SubcommandOverlay::List => {
+                wlx_overlay_list(state).await;
+            }
+            SubcommandOverlay::Get { name } => {
+                wlx_overlay_get(state, name).await;
+            }
+            SubcommandOverlay::Set {
+                name,
+                visible,
+                pos,
+                rot,
+                scale,
+                alpha,
+                curvature,
+                positioning,
+                lerp,
+                align_to_hmd,
+                grabbable,
+                interactable,
+            } => {
+                let positioning = positioning.map(|p| match p {
+                    OverlayPosEnum::Floating => WlxPositioning::Floating,
+                    OverlayPosEnum::Anchored => WlxPositioning::Anchored,
+                    OverlayPosEnum::Static => WlxPositioning::Static,
+                    OverlayPosEnum::Head => WlxPositioning::FollowHead { lerp },
+                    OverlayPosEnum::LeftHand => WlxPositioning::FollowHand {
+                        hand: WlxHand::Left,
+                        lerp,
+                        align_to_hmd,
+                    },
+                    OverlayPosEnum::RightHand => WlxPositioning::FollowHand {
+                        hand: WlxHand::Right,
+                        lerp,
+                        align_to_hmd,
+                    },
+                });
+
+                wlx_overlay_set(
+                    state,
+                    packet_client::WlxOverlaySetParams {
+                        name,
+                        visible: visible.map(|v| v != 0),
+                        pos,
+                        rot,
+                        scale,
+                        alpha,
+                        curvature,
+                        positioning,
+                        grabbable: grabbable.map(|v| v != 0),
+                        interactable: interactable.map(|v| v != 0),
+                    },
+                )
+                .await;
+            }
+        },
+        Subcommands::Set { command } => match command {
+            SubcommandSet::List => {
+                wlx_set_list(state).await;
+            }
+            SubcommandSet::Create { name } => {
+                wlx_set_create(state, name).await;
+            }
+            SubcommandSet::Delete { set } => {
+                wlx_set_delete(state, set_index(set)?).await;
+            }
+            SubcommandSet::Rename { set, name } => {
+                wlx_set_rename(state, set_index(set)?, name).await;
+            }
+            SubcommandSet::AddOverlay { set, overlay } => {
+                wlx_set_add_overlay(state, set_index(set)?, overlay).await;
+            }
+            SubcommandSet::RemoveOverlay { set, overlay } => {
+                wlx_set_remove_overlay(state, set_index(set)?, overlay).await;
+            }
+        },
+        Subcommands::Profile { command } => match command {
+            SubcommandProfile::List => {
+                wlx_profile_list(state).await;
+            }
+            SubcommandProfile::Save { name } => {
+                wlx_profile_save(state, name).await;
+            }
+            SubcommandProfile::Load { name } => {
+                wlx_profile_load(state, name).await;
+            }
+            SubcommandProfile::Delete { name } => {
+                wlx_profile_delete(state, name).await;
+            }
+            SubcommandProfile::Export { name, file } => {
+                wlx_profile_export(state, name, file).await?;
+            }
+            SubcommandProfile::Import { file, name } => {
+                let name = match name {
+                    Some(name) => name,
+                    None => file
+                        .file_stem()
+                        .context("Could not derive profile name from file name")?
+                        .to_string_lossy()
+                        .into_owned(),
+                };
+                let data = std::fs::read_to_string(&file)
+                    .with_context(|| format!("Could not read {}", file.display()))?;
+                wlx_profile_import(state, name, data).await;
+            }
+        },
+        Subcommands::Var { command } => match command {
+            SubcommandVar::List => {
+                wlx_var_list(state).await;
+            }
+            SubcommandVar::Get { key } => {
+                wlx_var_get(state, key).await?;
+            }
+            SubcommandVar::Set { key, value } => {
+                wlx_var_set(state, key, Some(value.join(" "))).await;
+            }
+            SubcommandVar::Unset { key } => {
+                wlx_var_set(state, key, None).await;
+            }
+        },
     }
     Ok(())
 }
 
+/// Set numbers are 1-based on the command line, same as on the watch
+fn set_index(set: usize) -> anyhow::Result<usize> {
+    set.checked_sub(1).context("Set numbers start from 1")
+}
+
+fn parse_floats<const N: usize>(arg: &str) -> anyhow::Result<[f32; N]> {
+    let values = arg
+        .split(',')
+        .map(|s| s.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .context("not a number")?;
+
+    values
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expecting {N} comma-separated numbers"))
+}
+
+/// Same lower bound as WayVR uses, so that bad values fail here instead of being clamped
+fn parse_radius(arg: &str) -> anyhow::Result<f32> {
+    let radius = arg.trim().parse::<f32>().context("not a number")?;
+    anyhow::ensure!(
+        radius.is_finite() && radius >= 0.3,
+        "expecting a radius of at least 0.3 meters"
+    );
+    Ok(radius)
+}
+
 /// A command-line interface for WayVR IPC
 #[derive(clap::Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -277,6 +472,20 @@ enum Subcommands {
     },
     /// Toggle overlay show or hide
     ShowHide,
+    /// Turn do-not-disturb on or off, until its schedule or the focused app changes
+    #[command(visible_alias = "dnd-mode")]
+    DoNotDisturb {
+        #[arg(value_enum, default_value = "toggle")]
+        mode: DoNotDisturbModeEnum,
+    },
+    /// Move all visible screens in front of the user
+    ArrangeScreens {
+        #[arg(value_enum)]
+        arrangement: ArrangementEnum,
+        /// Radius of the arc in meters, defaults to `screen_arc_radius` from the config
+        #[arg(short, long, value_parser = parse_radius)]
+        radius: Option<f32>,
+    },
     /// Apply a modification to a panel element
     PanelModify {
         /// The name of the overlay (XML file name without extension)
@@ -291,6 +500,197 @@ enum Subcommands {
         /// Set number to switch to, 0 to hide all sets
         set_or_0: usize,
     },
+    /// List, inspect or reconfigure overlays
+    Overlay {
+        #[command(subcommand)]
+        command: SubcommandOverlay,
+    },
+    /// Create, delete, rename or list sets and change which overlays they contain
+    Set {
+        #[command(subcommand)]
+        command: SubcommandSet,
+    },
+    /// Save, load, export or import named layout profiles
+    Profile {
+        #[command(subcommand)]
+        command: SubcommandProfile,
+    },
+    /// List, get or set shared panel variables
+    Var {
+        #[command(subcommand)]
+        command: SubcommandVar,
+    },
+    /// Stream overlay events as JSON lines until the connection is closed
+    Watch {
+        /// Topics to subscribe to. Subscribes to all topics if none are given.
+        #[arg(value_enum)]
+        topics: Vec<TopicEnum>,
+    },
+}
+
+#[derive(clap::Parser, Debug)]
+enum SubcommandOverlay {
+    /// List all overlays, including hidden ones
+    List,
+    /// Retrieve the state of an overlay
+    Get {
+        /// The name of the overlay, as returned by List
+        name: String,
+    },
+    /// Change the state of an overlay. Options that are not given are left unchanged.
+    Set {
+        /// The name of the overlay, as returned by List
+        name: String,
+        #[arg(long)]
+        visible: Option<u8>,
+        /// Position in stage space, in meters: x,y,z
+        #[arg(long, value_parser = parse_floats::<3>, allow_hyphen_values = true)]
+        pos: Option<[f32; 3]>,
+        /// Rotation quaternion in stage space: x,y,z,w
+        #[arg(long, value_parser = parse_floats::<4>, allow_hyphen_values = true)]
+        rot: Option<[f32; 4]>,
+        /// Width of the overlay, in meters
+        #[arg(long)]
+        scale: Option<f32>,
+        #[arg(long)]
+        alpha: Option<f32>,
+        /// 0 for a flat overlay
+        #[arg(long)]
+        curvature: Option<f32>,
+        #[arg(long)]
+        positioning: Option<OverlayPosEnum>,
+        /// Interpolation factor for head or hand positioning. 1 to disable smoothing.
+        #[arg(long, default_value = "1.0")]
+        lerp: f32,
+        /// Keep the overlay facing the HMD when following a hand
+        #[arg(long)]
+        align_to_hmd: bool,
+        #[arg(long)]
+        grabbable: Option<u8>,
+        #[arg(long)]
+        interactable: Option<u8>,
+    },
+}
+
+#[derive(clap::Parser, Debug)]
+enum SubcommandSet {
+    /// List all sets along with the overlays they contain
+    List,
+    /// Append a new set containing only the keyboard. Prints the new set number.
+    Create {
+        name: Option<String>,
+    },
+    /// Delete a set. The last remaining set cannot be deleted.
+    Delete {
+        set: usize,
+    },
+    Rename {
+        set: usize,
+        name: String,
+    },
+    /// Add an overlay to a set. Shows the overlay if the set is active.
+    AddOverlay {
+        set: usize,
+        overlay: String,
+    },
+    /// Remove an overlay from a set. Hides the overlay if the set is active.
+    RemoveOverlay {
+        set: usize,
+        overlay: String,
+    },
+}
+
+#[derive(clap::Parser, Debug)]
+enum SubcommandProfile {
+    /// List saved layout profiles
+    List,
+    /// Save the current sets and overlay positions, overwriting any profile with the same name
+    Save {
+        name: String,
+    },
+    /// Replace the current sets and overlay positions with those of a profile
+    Load {
+        name: String,
+    },
+    Delete {
+        name: String,
+    },
+    /// Write a profile to a file, or to stdout if no file is given
+    Export {
+        name: String,
+        file: Option<PathBuf>,
+    },
+    /// Add a profile from an exported file
+    Import {
+        file: PathBuf,
+        /// Defaults to the file name without extension
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(clap::Parser, Debug)]
+enum SubcommandVar {
+    /// List all variables
+    List,
+    /// Print the value of a variable. Fails if the variable is not set.
+    Get {
+        key: String,
+    },
+    Set {
+        key: String,
+        value: Vec<String>,
+    },
+    Unset {
+        key: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum OverlayPosEnum {
+    Floating,
+    Anchored,
+    Static,
+    Head,
+    LeftHand,
+    RightHand,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TopicEnum {
+    /// An overlay was shown or hidden
+    Overlays,
+    /// The active set was switched
+    Sets,
+    /// Edit mode was toggled
+    EditMode,
+    /// Keyboard focus moved between the desktop and WayVR
+    KeyboardFocus,
+    /// A toast notification was posted
+    Toasts,
+    /// A shared panel variable was set or removed
+    Variables,
+    /// Do-not-disturb was turned on or off
+    DoNotDisturb,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum DoNotDisturbModeEnum {
+    On,
+    Off,
+    Toggle,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ArrangementEnum {
+    /// Side by side, facing the same way
+    Row,
+    /// Side by side, curved around the user
+    Arc,
+    /// On top of each other
+    Stack,
+    /// Same layout as on the desktop
+    Desktop,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
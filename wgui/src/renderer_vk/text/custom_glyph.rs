@@ -177,6 +177,22 @@ impl CustomGlyphData {
 			}
 		}
 	}
+
+	/// Creates a glyph from raw, tightly packed RGBA pixels.
+	/// `path` is only used as a cache key.
+	pub fn from_rgba(globals: &WguiGlobals, path: &str, width: u32, height: u32, data: Vec<u8>) -> anyhow::Result<Self> {
+		let globals_borrow = &mut globals.get();
+		match globals_borrow.custom_glyph_cache.get(path, &data) {
+			Ok(data) => Ok(data),
+			Err(hashed_asset) => {
+				let image = RgbaImage::from_raw(width, height, data)
+					.ok_or_else(|| anyhow::anyhow!("RGBA data does not match {width}x{height}"))?;
+				let data = Self::new(CustomGlyphContent::Image(image));
+				globals_borrow.custom_glyph_cache.insert(hashed_asset, &data);
+				Ok(data)
+			}
+		}
+	}
 }
 
 impl PartialEq for CustomGlyphData {
@@ -17,7 +17,9 @@ use smithay_client_toolkit::reexports::{
     },
 };
 
+pub use smithay_client_toolkit::reexports::{protocols_misc, protocols_wlr};
 pub use wayland_client;
+pub use wayland_protocols;
 use wayland_client::{
     Connection, Dispatch, EventQueue, Proxy, QueueHandle,
     backend::WaylandError,
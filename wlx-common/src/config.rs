@@ -7,7 +7,7 @@ use strum::{AsRefStr, EnumProperty, EnumString, VariantArray};
 use wayvr_ipc::packet_client::WvrProcessLaunchParams;
 
 use crate::{
-	astr_containers::{AStrMap, AStrSet}, locale::{self}, overlays::{BackendAttribValue, ToastDisplayMethod, ToastTopic}, windowing::OverlayWindowState
+	astr_containers::{AStrMap, AStrSet}, common::LeftRight, locale::{self}, overlays::{BackendAttribValue, ToastDisplayMethod, ToastTopic, ToastUrgency}, windowing::OverlayWindowState
 };
 
 pub type PwTokenMap = AStrMap<String>;
@@ -74,6 +74,83 @@ pub struct SerializedWindowSet {
 	pub hidden_overlays: SerializedWindowStates,
 }
 
+/// What to do when an OSC message arrives on a given address.
+/// Argument-less messages and truthy first arguments (true, non-zero, >= 0.5) trigger the
+/// button-like actions; avatar menus send a falsy value on release, which is ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OscInAction {
+	/// Toggles the overlay.
+	ToggleOverlay { overlay: Arc<str> },
+	/// Shows the overlay if the first argument is truthy, hides it otherwise.
+	SetOverlayVisible { overlay: Arc<str> },
+	/// Switches to the given set, 0-indexed. If not set, the first argument is used instead.
+	/// Negative values hide all sets.
+	SwitchSet { set: Option<i32> },
+	ShowHide,
+	/// Shows a toast with the first argument as its body.
+	Toast { title: Option<String> },
+	/// Sets the text of a panel element to the first argument.
+	SetPanelText { overlay: String, element: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscInMapping {
+	pub address: String,
+	pub action: OscInAction,
+}
+
+/// A state value that can be sent out over OSC.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OscOutValue {
+	/// bool: any interactable overlay is visible
+	AnyOverlayOpen,
+	/// int: number of visible interactable overlays
+	OpenOverlayCount,
+	/// bool
+	EditMode,
+	/// int: 0-indexed active set
+	CurrentSet,
+	/// int
+	TotalSets,
+	/// bool
+	KeyboardOpen,
+	/// bool
+	WatchVisible,
+	/// float, one message per device. `{device}` in the address is replaced with
+	/// hmd, leftController, rightController, tracker1, tracker2, ...
+	DeviceBattery,
+	/// bool, one message per device. See `DeviceBattery`.
+	DeviceCharging,
+	/// float
+	AverageControllerBattery,
+	/// float
+	AverageTrackerBattery,
+	/// float
+	LowestBattery,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscOutParam {
+	pub value: OscOutValue,
+	pub address: String,
+}
+
+/// A group of OSC parameters sent to the same targets at the same rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscOutput {
+	/// List of `host:port`
+	pub targets: Vec<String>,
+
+	#[serde(default = "def_osc_interval_ms")]
+	pub interval_ms: u32,
+
+	/// Send all params of this output in a single OSC bundle.
+	#[serde(default = "def_false")]
+	pub bundle: bool,
+
+	pub params: Vec<OscOutParam>,
+}
+
 pub const fn def_pw_tokens() -> PwTokenMap {
 	AStrMap::new()
 }
@@ -110,6 +187,10 @@ const fn def_point3() -> f32 {
 	0.3
 }
 
+const fn def_osc_interval_ms() -> u32 {
+	100
+}
+
 const fn def_osc_port() -> u16 {
 	9000
 }
@@ -138,7 +219,111 @@ const fn def_max_height() -> u16 {
 	1440
 }
 
+const fn def_toast_max_visible() -> usize {
+	3
+}
+
+const fn def_snap_angle() -> f32 {
+	15.0
+}
+
+const fn def_screen_arc_radius() -> f32 {
+	1.5
+}
+
+const fn def_clipboard_max_size_mb() -> u32 {
+	16
+}
+
+fn def_clipboard_mime_groups() -> Vec<Vec<String>> {
+	let group = |mimes: &[&str]| mimes.iter().map(ToString::to_string).collect();
+	vec![
+		group(&[
+			"text/plain;charset=utf-8",
+			"text/plain",
+			"UTF8_STRING",
+			"STRING",
+			"TEXT",
+		]),
+		group(&["image/png"]),
+	]
+}
+
+
+
+/// Per-application notification settings.
+/// The first rule where all of the given conditions match is applied.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NotificationRule {
+	/// D-Bus `app_name` or XSOverlay `sourceApp`, case-insensitive
+	pub app_name: Option<String>,
+	/// Regular expression, matched against the notification summary
+	pub summary: Option<String>,
+	pub urgency: Option<ToastUrgency>,
+
+	/// Overrides `notification_topics`
+	pub display: Option<ToastDisplayMethod>,
+	/// In seconds
+	pub timeout: Option<f32>,
+	#[serde(default)]
+	pub silent: bool,
+	/// Keep the toast until it's replaced or the notification center is cleared
+	#[serde(default)]
+	pub sticky: bool,
+}
+
+/// Local times, as `HH:MM`. `end` may be before `start` to span midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoNotDisturbSchedule {
+	pub start: String,
+	pub end: String,
+}
+
+/// What a double tap or flick does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GestureAction {
+	/// The next click is a right click
+	RightClick,
+	/// The next click is a middle click
+	MiddleClick,
+	/// Hides the overlay being pointed at
+	Dismiss,
+	ShowHide,
+	ToggleDashboard,
+}
+
+/// Alternatives to rolling the wrist or pressing buttons, mainly for hand tracking.
+/// `hand` limits a gesture to one hand, both are used if unset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "gesture")]
+pub enum GestureBinding {
+	/// Two quick pinches in place. The first one still clicks.
+	DoubleTap {
+		hand: Option<LeftRight>,
+		action: GestureAction,
+	},
+	/// A quick wave of the hand while not pinching
+	Flick {
+		hand: Option<LeftRight>,
+		action: GestureAction,
+	},
+	/// Pinch and move the hand up or down to scroll.
+	/// Clicks are held back briefly to tell them apart from scrolling.
+	PinchScroll { hand: Option<LeftRight> },
+	/// Pinch with both hands while pointing at the same overlay,
+	/// then move the hands apart or together to resize it
+	PinchZoom,
+}
 
+impl GestureBinding {
+	pub fn applies_to(&self, side: LeftRight) -> bool {
+		let hand = match self {
+			Self::DoubleTap { hand, .. } | Self::Flick { hand, .. } | Self::PinchScroll { hand } => *hand,
+			Self::PinchZoom => None,
+		};
+		hand.is_none_or(|hand| hand == side)
+	}
+}
 
 #[derive(Deserialize, Serialize)]
 pub struct GeneralConfig {
@@ -184,6 +369,9 @@ pub struct GeneralConfig {
 	#[serde(default = "def_one")]
 	pub long_press_duration: f32,
 
+	#[serde(default)]
+	pub gestures: Vec<GestureBinding>,
+
 	#[serde(default = "def_mouse_move_interval_ms")]
 	pub mouse_move_interval_ms: i32,
 
@@ -196,6 +384,20 @@ pub struct GeneralConfig {
 	#[serde(default)]
 	pub notification_topics: IdMap<ToastTopic, ToastDisplayMethod>,
 
+	#[serde(default)]
+	pub notification_rules: Vec<NotificationRule>,
+
+	/// Toasts stacked on screen at once, per display method. The rest wait in a queue.
+	#[serde(default = "def_toast_max_visible")]
+	pub toast_max_visible: usize,
+
+	/// Turns do-not-disturb on between these times every day
+	pub do_not_disturb_schedule: Option<DoNotDisturbSchedule>,
+
+	/// Monado client names that turn do-not-disturb on while focused
+	#[serde(default)]
+	pub do_not_disturb_apps: Vec<String>,
+
 	#[serde(default = "def_true")]
 	pub keyboard_sound_enabled: bool,
 
@@ -205,6 +407,13 @@ pub struct GeneralConfig {
 	#[serde(default = "def_one")]
 	pub desktop_view_scale: f32,
 
+	/// Radius of the Arc screen arrangement, and distance of the other arrangements, in meters
+	#[serde(default = "def_screen_arc_radius")]
+	pub screen_arc_radius: f32,
+
+	/// Directory that the dashboard exports layout profiles to and imports them from, relative to the home directory
+	pub profile_export_path: Option<String>,
+
 	#[serde(default = "def_half")]
 	pub watch_view_angle_min: f32,
 
@@ -214,6 +423,15 @@ pub struct GeneralConfig {
 	#[serde(default = "def_osc_port")]
 	pub osc_out_port: u16,
 
+	/// Receive OSC messages on this port. Disabled if not set.
+	pub osc_in_port: Option<u16>,
+
+	#[serde(default)]
+	pub osc_in_actions: Vec<OscInMapping>,
+
+	/// Replaces the built-in set of OSC parameters sent to `osc_out_port`.
+	pub osc_outputs: Option<Vec<OscOutput>>,
+
 	#[serde(default = "def_false")]
 	pub upright_screen_fix: bool,
 
@@ -241,6 +459,17 @@ pub struct GeneralConfig {
 	#[serde(default = "def_true")]
 	pub allow_sliding: bool,
 
+	/// Snap grabbed overlays to other overlays while in edit mode, with guide lines
+	#[serde(default = "def_false")]
+	pub snap_overlays: bool,
+
+	/// Yaw increments around the HMD to snap to, in degrees. 0 disables
+	#[serde(default = "def_snap_angle")]
+	pub snap_angle: f32,
+
+	/// Snap the distance from the HMD to this radius, keeping overlays on a cylinder
+	pub snap_cylinder_radius: Option<f32>,
+
 	#[serde(default = "def_false")]
 	pub focus_follows_mouse_mode: bool,
 
@@ -316,6 +545,23 @@ pub struct GeneralConfig {
 	#[serde(default)]
 	pub xwayland_by_default: bool,
 
+	/// Two-way clipboard sync between the desktop and WayVR apps
+	#[serde(default)]
+	pub clipboard_sync: bool,
+
+	/// Larger clipboard contents are not synced, in MiB
+	#[serde(default = "def_clipboard_max_size_mb")]
+	pub clipboard_max_size_mb: u32,
+
+	/// MIME types that are synced. Each group lists types that carry the same data, in order of preference.
+	/// Only one of them is copied and the whole group is offered on the other side.
+	#[serde(default = "def_clipboard_mime_groups")]
+	pub clipboard_mime_groups: Vec<Vec<String>>,
+
+	/// Compose text from the keyboard overlay through the built-in input method
+	#[serde(default)]
+	pub keyboard_ime: bool,
+
 	#[serde(default)]
 	pub context_menu_hold_and_release: bool,
 
@@ -1,9 +1,11 @@
+use std::path::PathBuf;
+
 use wayvr_ipc::{
-	packet_client::WvrProcessLaunchParams,
+	packet_client::{WlxScreenArrangement, WvrProcessLaunchParams},
 	packet_server::{WvrProcess, WvrProcessHandle, WvrWindow, WvrWindowHandle},
 };
 
-use crate::{config::GeneralConfig, desktop_finder::DesktopFinder};
+use crate::{config::GeneralConfig, desktop_finder::DesktopFinder, keyboard_layout::KeyboardLayout};
 
 #[derive(Clone)]
 pub struct MonadoClient {
@@ -16,6 +18,13 @@ pub struct MonadoClient {
 	pub is_io_active: bool,
 }
 
+/// A notification from the history, offered as a starting point for notification rules.
+#[derive(Clone)]
+pub struct RecentNotification {
+	pub app_name: Option<String>,
+	pub summary: String,
+}
+
 #[derive(Clone, Copy)]
 pub enum RecenterMode {
 	FixFloor,
@@ -41,11 +50,29 @@ pub trait DashInterface<T> {
 	fn monado_brightness_get(&mut self, data: &mut T) -> Option<f32>;
 	fn monado_brightness_set(&mut self, data: &mut T, brightness: f32) -> Option<()>;
 	fn recenter_playspace(&mut self, data: &mut T, mode: RecenterMode) -> anyhow::Result<()>;
+	fn screens_arrange(&mut self, data: &mut T, arrangement: WlxScreenArrangement);
 	fn desktop_finder<'a>(&'a mut self, data: &'a mut T) -> &'a mut DesktopFinder;
 	fn general_config<'a>(&'a mut self, data: &'a mut T) -> &'a mut GeneralConfig;
 	fn config_changed(&mut self, data: &mut T);
 	fn restart(&mut self, data: &mut T);
 	fn toggle_dashboard(&mut self, data: &mut T);
+	fn layout_profile_list(&mut self, data: &mut T) -> Vec<String>;
+	/// Returns `false` if the profile is saved later, the dashboard is then notified once it has been.
+	fn layout_profile_save(&mut self, data: &mut T, name: &str) -> bool;
+	fn layout_profile_load(&mut self, data: &mut T, name: &str);
+	fn layout_profile_delete(&mut self, data: &mut T, name: &str) -> anyhow::Result<()>;
+	/// Copies a layout profile to the export directory and returns the path of the copy.
+	fn layout_profile_export(&mut self, data: &mut T, name: &str) -> anyhow::Result<PathBuf>;
+	/// Names of the layout profiles in the export directory.
+	fn layout_profile_import_list(&mut self, data: &mut T) -> Vec<String>;
+	fn layout_profile_import(&mut self, data: &mut T, name: &str) -> anyhow::Result<()>;
+	/// The user's keyboard layout, or the built-in one if `default` is set.
+	fn keyboard_layout_get(&mut self, data: &mut T, default: bool) -> KeyboardLayout;
+	fn keyboard_layout_save(&mut self, data: &mut T, layout: &KeyboardLayout) -> anyhow::Result<()>;
+	/// Names of the keys that can be placed on the keyboard or used in macros.
+	fn keyboard_key_names(&mut self, data: &mut T) -> Vec<String>;
+	/// Desktop notifications that were received recently, newest first.
+	fn notification_recent_list(&mut self, data: &mut T) -> Vec<RecentNotification>;
 }
 
 pub type BoxDashInterface<T> = Box<dyn DashInterface<T>>;
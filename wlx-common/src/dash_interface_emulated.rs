@@ -1,5 +1,7 @@
+use std::path::PathBuf;
+
 use wayvr_ipc::{
-	packet_client::WvrProcessLaunchParams,
+	packet_client::{WlxScreenArrangement, WvrProcessLaunchParams},
 	packet_server::{WvrProcess, WvrProcessHandle, WvrWindow, WvrWindowHandle},
 };
 
@@ -8,8 +10,25 @@ use crate::{
 	dash_interface::{self, DashInterface, RecenterMode},
 	desktop_finder::DesktopFinder,
 	gen_id,
+	keyboard_layout::KeyboardLayout,
 };
 
+const EMU_KEY_NAMES: [&str; 10] = ["Q", "W", "E", "R", "C", "V", "LShift", "LCtrl", "Space", "Return"];
+
+fn emu_keyboard_layout() -> KeyboardLayout {
+	let keys = |keys: &[&str]| keys.iter().map(|k| Some(k.to_string())).collect();
+	let mut layout = KeyboardLayout {
+		row_size: 4.0,
+		key_sizes: vec![vec![1.0; 4], vec![1.0, 2.0, 1.0]],
+		main_layout: vec![keys(&["Q", "W", "E", "R"]), keys(&["LShift", "Space", "COPY"])],
+		..Default::default()
+	};
+	layout
+		.macros
+		.insert("COPY".into(), vec!["LCtrl DOWN".into(), "C".into(), "LCtrl UP".into()]);
+	layout
+}
+
 #[derive(Debug)]
 pub struct EmuProcess {
 	name: String,
@@ -63,6 +82,8 @@ pub struct DashInterfaceEmulated {
 	general_config: GeneralConfig,
 	monado_clients: Vec<dash_interface::MonadoClient>,
 	brightness: f32,
+	layout_profiles: Vec<String>,
+	keyboard_layout: KeyboardLayout,
 }
 
 impl DashInterfaceEmulated {
@@ -121,6 +142,8 @@ impl DashInterfaceEmulated {
 			general_config,
 			monado_clients,
 			brightness: 1.0,
+			layout_profiles: vec![String::from("Streaming"), String::from("Work")],
+			keyboard_layout: emu_keyboard_layout(),
 		}
 	}
 }
@@ -215,6 +238,10 @@ impl DashInterface<()> for DashInterfaceEmulated {
 		Ok(())
 	}
 
+	fn screens_arrange(&mut self, _: &mut (), _: WlxScreenArrangement) {
+		// stub!
+	}
+
 	fn desktop_finder<'a>(&'a mut self, _: &'a mut ()) -> &'a mut DesktopFinder {
 		&mut self.desktop_finder
 	}
@@ -229,6 +256,72 @@ impl DashInterface<()> for DashInterfaceEmulated {
 
 	fn toggle_dashboard(&mut self, _data: &mut ()) {}
 
+	fn layout_profile_list(&mut self, _: &mut ()) -> Vec<String> {
+		self.layout_profiles.clone()
+	}
+
+	fn layout_profile_save(&mut self, _: &mut (), name: &str) -> bool {
+		if !self.layout_profiles.iter().any(|p| p == name) {
+			self.layout_profiles.push(name.to_string());
+			self.layout_profiles.sort();
+		}
+		true
+	}
+
+	fn layout_profile_load(&mut self, _: &mut (), _: &str) {}
+
+	fn layout_profile_delete(&mut self, _: &mut (), name: &str) -> anyhow::Result<()> {
+		self.layout_profiles.retain(|p| p != name);
+		Ok(())
+	}
+
+	fn layout_profile_export(&mut self, _: &mut (), name: &str) -> anyhow::Result<PathBuf> {
+		Ok(PathBuf::from(format!("/tmp/{name}.json5")))
+	}
+
+	fn layout_profile_import_list(&mut self, _: &mut ()) -> Vec<String> {
+		vec![String::from("Shared")]
+	}
+
+	fn layout_profile_import(&mut self, data: &mut (), name: &str) -> anyhow::Result<()> {
+		self.layout_profile_save(data, name);
+		Ok(())
+	}
+
+	fn keyboard_layout_get(&mut self, _: &mut (), default: bool) -> KeyboardLayout {
+		if default {
+			emu_keyboard_layout()
+		} else {
+			self.keyboard_layout.clone()
+		}
+	}
+
+	fn keyboard_layout_save(&mut self, _: &mut (), layout: &KeyboardLayout) -> anyhow::Result<()> {
+		let errors = layout.validate(|key| EMU_KEY_NAMES.contains(&key));
+		if let Some(e) = errors.first() {
+			anyhow::bail!("{e}");
+		}
+		self.keyboard_layout = layout.clone();
+		Ok(())
+	}
+
+	fn keyboard_key_names(&mut self, _: &mut ()) -> Vec<String> {
+		EMU_KEY_NAMES.iter().map(ToString::to_string).collect()
+	}
+
+	fn notification_recent_list(&mut self, _: &mut ()) -> Vec<dash_interface::RecentNotification> {
+		vec![
+			dash_interface::RecentNotification {
+				app_name: Some(String::from("Chat app")),
+				summary: String::from("New message"),
+			},
+			dash_interface::RecentNotification {
+				app_name: Some(String::from("Sync client")),
+				summary: String::from("3 files synced"),
+			},
+		]
+	}
+
 	fn monado_client_list(&mut self, _data: &mut ()) -> anyhow::Result<Vec<dash_interface::MonadoClient>> {
 		Ok(self.monado_clients.clone())
 	}
@@ -263,13 +263,20 @@ impl DesktopFinder {
 			return Some(icon_name.to_string());
 		}
 
+		// names may come from notifications, they must not escape the icon folders
+		if icon_name.is_empty() || icon_name.contains('/') || icon_name.contains("..") {
+			return None;
+		}
+		let icon_name = glob::Pattern::escape(icon_name);
+
 		for folder in &params.icon_folders {
-			let pattern = format!("{}/hicolor/*/apps/{}.*", folder, icon_name);
+			let pattern = format!("{}/hicolor/*/apps/{}.*", glob::Pattern::escape(folder), icon_name);
 
-			let mut entries: Vec<_> = glob::glob(&pattern)
-				.expect("Bad glob pattern!")
-				.filter_map(Result::ok)
-				.collect();
+			let Ok(paths) = glob::glob(&pattern) else {
+				log::warn!("Bad icon glob pattern: {pattern}");
+				continue;
+			};
+			let mut entries: Vec<_> = paths.filter_map(Result::ok).collect();
 
 			// sort by SIZE_PREFERENCES
 			entries.sort_by_key(|path| {
@@ -289,6 +296,12 @@ impl DesktopFinder {
 		None
 	}
 
+	/// Looks up a theme icon by name in the same folders as desktop entry icons.
+	/// Absolute paths are returned as-is if they exist.
+	pub fn find_icon_path(&self, icon_name: &str) -> Option<String> {
+		Self::find_icon(&self.params, icon_name)
+	}
+
 	pub fn create_icon(desktop_entry_name: &str) -> anyhow::Result<String> {
 		let relative_path = format!("icons/{}.svg", desktop_entry_name);
 		let file_path = cache_dir::get_path(&relative_path);
@@ -0,0 +1,260 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+/// How far the keys of a row may add up away from `row_size`
+const ROW_SIZE_TOLERANCE: f32 = 0.001;
+
+/// Contents of `keyboard.yaml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyboardLayout {
+	/// Width of every row, in key units
+	pub row_size: f32,
+	/// Width of each key, in key units
+	pub key_sizes: Vec<Vec<f32>>,
+	/// Virtual key, macro or exec command of each key. `None` leaves a gap.
+	pub main_layout: Vec<Vec<Option<String>>>,
+	/// Program and arguments. A `null` argument separates the program to run on release.
+	pub exec_commands: BTreeMap<String, Vec<String>>,
+	/// Virtual keys to press in order, optionally with `UP` or `DOWN`
+	pub macros: BTreeMap<String, Vec<String>>,
+	pub labels: BTreeMap<String, Vec<String>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub auto_labels: Option<bool>,
+}
+
+/// Row and column numbers start at 0.
+#[derive(Debug, Clone)]
+pub enum LayoutError {
+	RowCount { rows: usize, size_rows: usize },
+	RowLength { row: usize, keys: usize, sizes: usize },
+	RowWidth { row: usize, width: f32, row_size: f32 },
+	KeySize { row: usize, col: usize, size: f32 },
+	UnknownKey { row: usize, col: usize, key: String },
+	EmptyExec { name: String },
+	MacroVerb { name: String, verb: String },
+}
+
+impl fmt::Display for LayoutError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::RowCount { rows, size_rows } => {
+				write!(f, "main_layout has {rows} rows, but key_sizes has {size_rows}")
+			}
+			Self::RowLength { row, keys, sizes } => {
+				write!(f, "Row {} has {keys} keys, but {sizes} key sizes", row + 1)
+			}
+			Self::RowWidth { row, width, row_size } => {
+				write!(
+					f,
+					"Row {} has a width of {width}, but the row size is {row_size}",
+					row + 1
+				)
+			}
+			Self::KeySize { row, col, size } => {
+				write!(f, "Key {} in row {} has a size of {size}", col + 1, row + 1)
+			}
+			Self::UnknownKey { row, col, key } => write!(
+				f,
+				"Key {} in row {}: '{key}' is not a key, macro or exec command",
+				col + 1,
+				row + 1
+			),
+			Self::EmptyExec { name } => write!(f, "Exec command '{name}' has no program"),
+			Self::MacroVerb { name, verb } => write!(f, "Macro '{name}': invalid step '{verb}'"),
+		}
+	}
+}
+
+impl std::error::Error for LayoutError {}
+
+impl LayoutError {
+	/// Whether the rows don't line up, so that the layout can't be shown at all.
+	/// Other errors only affect single keys, which can be left out.
+	pub const fn is_structural(&self) -> bool {
+		matches!(
+			self,
+			Self::RowCount { .. } | Self::RowLength { .. } | Self::RowWidth { .. } | Self::KeySize { .. }
+		)
+	}
+}
+
+/// Splits a macro step into the key name and whether it's pressed (`Some(true)`),
+/// released (`Some(false)`) or both (`None`).
+pub fn parse_macro_verb(verb: &str) -> Option<(&str, Option<bool>)> {
+	let (key, state) = match verb.split_once(' ') {
+		None => (verb, None),
+		Some((key, state)) => match state.trim_start_matches(' ') {
+			"DOWN" => (key, Some(true)),
+			"UP" => (key, Some(false)),
+			_ => return None,
+		},
+	};
+	if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+		return None;
+	}
+	Some((key, state))
+}
+
+impl KeyboardLayout {
+	/// Checks that all rows line up and every key can be resolved.
+	/// `is_virtual_key` tells whether a name is a key that can be sent as-is.
+	pub fn validate(&self, is_virtual_key: impl Fn(&str) -> bool) -> Vec<LayoutError> {
+		let mut errors = vec![];
+
+		if self.main_layout.len() != self.key_sizes.len() {
+			errors.push(LayoutError::RowCount {
+				rows: self.main_layout.len(),
+				size_rows: self.key_sizes.len(),
+			});
+		}
+
+		for (row, sizes) in self.key_sizes.iter().enumerate() {
+			let width = self.row_width(row);
+			if (width - self.row_size).abs() >= ROW_SIZE_TOLERANCE {
+				errors.push(LayoutError::RowWidth {
+					row,
+					width,
+					row_size: self.row_size,
+				});
+			}
+			for (col, size) in sizes.iter().enumerate() {
+				if *size <= 0.0 {
+					errors.push(LayoutError::KeySize { row, col, size: *size });
+				}
+			}
+		}
+
+		for (row, keys) in self.main_layout.iter().enumerate() {
+			if let Some(sizes) = self.key_sizes.get(row)
+				&& sizes.len() != keys.len()
+			{
+				errors.push(LayoutError::RowLength {
+					row,
+					keys: keys.len(),
+					sizes: sizes.len(),
+				});
+			}
+			for (col, key) in keys.iter().enumerate() {
+				if let Some(key) = key
+					&& !is_virtual_key(key)
+					&& !self.macros.contains_key(key)
+					&& !self.exec_commands.contains_key(key)
+				{
+					errors.push(LayoutError::UnknownKey {
+						row,
+						col,
+						key: key.clone(),
+					});
+				}
+			}
+		}
+
+		for (name, args) in &self.exec_commands {
+			if args.first().is_none_or(|p| p == "null") {
+				errors.push(LayoutError::EmptyExec { name: name.clone() });
+			}
+		}
+
+		for (name, verbs) in &self.macros {
+			for verb in verbs {
+				if parse_macro_verb(verb).is_none_or(|(key, _)| !is_virtual_key(key)) {
+					errors.push(LayoutError::MacroVerb {
+						name: name.clone(),
+						verb: verb.clone(),
+					});
+				}
+			}
+		}
+
+		errors
+	}
+
+	/// Leaves out what the non-structural `errors` point at: broken macros and exec commands
+	/// are removed, and keys that no longer resolve become gaps.
+	pub fn skip_invalid_keys(&mut self, errors: &[LayoutError], is_virtual_key: impl Fn(&str) -> bool) {
+		for error in errors {
+			match error {
+				LayoutError::EmptyExec { name } => {
+					self.exec_commands.remove(name);
+				}
+				LayoutError::MacroVerb { name, .. } => {
+					self.macros.remove(name);
+				}
+				_ => {}
+			}
+		}
+
+		for key in self.main_layout.iter_mut().flatten() {
+			if key.as_deref().is_some_and(|key| {
+				!is_virtual_key(key) && !self.macros.contains_key(key) && !self.exec_commands.contains_key(key)
+			}) {
+				*key = None;
+			}
+		}
+	}
+
+	pub fn row_width(&self, row: usize) -> f32 {
+		self.key_sizes.get(row).map_or(0.0, |sizes| sizes.iter().sum())
+	}
+
+	/// Adds a key to both `main_layout` and `key_sizes`, so that they stay in sync.
+	pub fn insert_key(&mut self, row: usize, col: usize, key: Option<String>, size: f32) {
+		if let (Some(keys), Some(sizes)) = (self.main_layout.get_mut(row), self.key_sizes.get_mut(row)) {
+			keys.insert(col.min(keys.len()), key);
+			sizes.insert(col.min(sizes.len()), size);
+		}
+	}
+
+	pub fn remove_key(&mut self, row: usize, col: usize) {
+		if let (Some(keys), Some(sizes)) = (self.main_layout.get_mut(row), self.key_sizes.get_mut(row))
+			&& col < keys.len()
+			&& col < sizes.len()
+		{
+			keys.remove(col);
+			sizes.remove(col);
+		}
+	}
+
+	/// Adds an empty row, made of a single gap as wide as `row_size`.
+	pub fn insert_row(&mut self, row: usize) {
+		let row = row.min(self.main_layout.len()).min(self.key_sizes.len());
+		self.main_layout.insert(row, vec![None]);
+		self.key_sizes.insert(row, vec![self.row_size]);
+	}
+
+	pub fn remove_row(&mut self, row: usize) {
+		if row < self.main_layout.len() && row < self.key_sizes.len() {
+			self.main_layout.remove(row);
+			self.key_sizes.remove(row);
+		}
+	}
+
+	/// Label from `labels`, otherwise made up from the key name.
+	pub fn label_for_key(&self, key: &str) -> Vec<String> {
+		if let Some(label) = self.labels.get(key) {
+			return label.clone();
+		}
+		if key.is_empty() {
+			return vec![];
+		}
+		if key.len() == 1 {
+			return vec![key.to_string().to_lowercase()];
+		}
+		let mut key = key;
+		if key.starts_with("KP_") {
+			key = &key[3..];
+		}
+		if key.contains('_') {
+			key = key.split('_').next().unwrap_or_else(|| {
+				log::error!("keyboard.yaml: Key '{key}' must not start or end with '_'!");
+				"???"
+			});
+		}
+		let mut chars = key.chars();
+		let Some(first) = chars.next() else {
+			return vec![];
+		};
+		vec![format!("{}{}", first.to_uppercase(), chars.as_str().to_lowercase())]
+	}
+}
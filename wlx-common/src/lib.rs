@@ -8,6 +8,7 @@ pub mod dash_interface;
 pub mod dash_interface_emulated;
 pub mod desktop_finder;
 mod handle;
+pub mod keyboard_layout;
 pub mod locale;
 pub mod overlays;
 pub mod timestep;
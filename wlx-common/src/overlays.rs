@@ -2,23 +2,34 @@ use std::sync::Arc;
 
 use idmap_derive::IntegerId;
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, IntegerId, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntegerId, Serialize, Deserialize, AsRefStr)]
 pub enum ToastTopic {
 	System,
 	Error,
 	DesktopNotification,
 	XSNotification,
 	IpdChange,
+	OscMessage,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, AsRefStr, EnumString)]
 pub enum ToastDisplayMethod {
 	Hide,
 	Center,
 	Watch,
 }
 
+/// As in the `urgency` hint of the freedesktop notification spec
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, AsRefStr, EnumString)]
+pub enum ToastUrgency {
+	Low,
+	#[default]
+	Normal,
+	Critical,
+}
+
 #[derive(Debug, Clone, Copy, IntegerId, PartialEq)]
 pub enum BackendAttrib {
 	Stereo,